@@ -1,14 +1,24 @@
-use parking_lot::RwLock;
-use pollster::block_on;
-use radiantkit_core::{RadiantDocumentListener, RadiantDocumentNode, RadiantNode};
+use parking_lot::{Mutex, RwLock};
+use radiantkit_core::{
+    RadiantDocumentListener, RadiantDocumentNode, RadiantNode, RadiantTextDelta,
+};
 use std::sync::{Arc, Weak};
 use uuid::Uuid;
 use y_sync::awareness::{Awareness, UpdateSubscription as AwarenessUpdateSubscription};
 use yrs::{
-    types::{map::MapEvent, EntryChange},
+    types::{
+        map::MapEvent, DeepEventsSubscription, Delta, EntryChange, Event, PathSegment, Value,
+    },
     *,
 };
 
+/// Serialized nodes, by id.
+const ROOT: &str = "radiantkit-root";
+/// Characters of text nodes, by id, edited one by one so that concurrent typing merges.
+const TEXTS: &str = "radiantkit-text";
+/// Marks transactions made here, whose changes the nodes already have.
+const LOCAL_ORIGIN: &str = "radiantkit-local";
+
 #[cfg(not(target_arch = "wasm32"))]
 use crate::native_connection::NativeConnection;
 #[cfg(target_arch = "wasm32")]
@@ -22,9 +32,34 @@ type Connection = WasmConnection;
 pub struct Collaborator<N: RadiantNode> {
     id: Uuid,
     _document: Weak<RwLock<RadiantDocumentNode<N>>>,
+    /// Kept open for as long as the collaborator lives.
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
     connection: Arc<RwLock<Connection>>,
     _awareness_sub: Option<AwarenessUpdateSubscription>,
-    _root_sub: Subscription<Arc<dyn Fn(&TransactionMut, &MapEvent)>>,
+    _remote_changes: RemoteChanges,
+    /// Text edits waiting to be sent along with the change of their node.
+    pending_text: Vec<(Uuid, Vec<RadiantTextDelta>)>,
+    /// Node changes, sent one after the other by a single task so that they keep their order.
+    #[cfg(not(target_arch = "wasm32"))]
+    changes: tokio::sync::mpsc::UnboundedSender<NodeChange>,
+}
+
+/// A change of the document's nodes, made here, for the shared document.
+enum NodeChange {
+    Added {
+        id: Uuid,
+        data: String,
+        text: Option<String>,
+    },
+    Removed {
+        id: Uuid,
+    },
+    /// A node as it is now, and the edits of its text since it was last sent.
+    Changed {
+        id: Uuid,
+        deltas: Vec<Vec<RadiantTextDelta>>,
+        data: String,
+    },
 }
 
 impl<'a, N: 'static + RadiantNode + serde::de::DeserializeOwned> Collaborator<N> {
@@ -35,39 +70,14 @@ impl<'a, N: 'static + RadiantNode + serde::de::DeserializeOwned> Collaborator<N>
         let extension_id = Uuid::new_v4();
         let url = "ws://localhost:8000/sync";
 
-        let doc = Doc::with_client_id(client_id);
-        let mut root = doc.get_or_insert_map("radiantkit-root");
-        let document_clone = document.clone();
-        let root_sub = root.observe(move |txn, event| {
-            let Some(document) = document_clone.upgrade() else {
-                return;
-            };
-            let Some(mut document) = document.try_write() else {
-                return;
-            };
-            event
-                .keys(txn)
-                .iter()
-                .for_each(|(key, change)| match change {
-                    EntryChange::Inserted(val) => {
-                        let id = Uuid::parse_str(key).unwrap();
-                        let node: String = val.clone().cast().unwrap();
-                        let mut node: N = serde_json::from_str(&node).unwrap();
-                        node.set_needs_tessellation(false);
-                        if document.get_node(id).is_none() {
-                            document.add_excluding_listener(node, extension_id);
-                        }
-                    }
-                    EntryChange::Removed(_val) => {}
-                    EntryChange::Updated(_old, new) => {
-                        let id = Uuid::parse_str(key).unwrap();
-                        if let Some(mut node) = document.get_node_mut(id) {
-                            let n: String = new.clone().cast().unwrap();
-                            node.replace(&n);
-                        }
-                    }
-                });
+        // Text deltas count UTF-16 code units, like web peers do.
+        let doc = Doc::with_options(Options {
+            client_id,
+            offset_kind: OffsetKind::Utf16,
+            ..Options::default()
         });
+        let remote_changes =
+            RemoteChanges::observe(&doc, document.clone(), extension_id).ok_or(())?;
 
         let connection;
 
@@ -95,82 +105,296 @@ impl<'a, N: 'static + RadiantNode + serde::de::DeserializeOwned> Collaborator<N>
             }
         }
 
+        #[cfg(not(target_arch = "wasm32"))]
+        let changes = {
+            let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<NodeChange>();
+            let awareness = connection.read().awareness();
+            tokio::spawn(async move {
+                while let Some(change) = receiver.recv().await {
+                    let awareness = awareness.write().await;
+                    send_node_change(awareness.doc(), &change);
+                }
+            });
+            sender
+        };
+
         Ok(Self {
             id: extension_id,
             _document: document,
             connection,
             _awareness_sub: awareness_sub,
-            _root_sub: root_sub,
+            _remote_changes: remote_changes,
+            pending_text: Vec::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            changes,
         })
     }
 }
 
-impl<N: RadiantNode> RadiantDocumentListener<N> for Collaborator<N> {
-    fn get_id(&self) -> Uuid {
-        self.id
-    }
+/// Keeps a document in step with the changes others make to the shared document.
+pub struct RemoteChanges {
+    _root_sub: Subscription<Arc<dyn Fn(&TransactionMut, &MapEvent)>>,
+    _texts_sub: DeepEventsSubscription,
+    _after_transaction_sub: AfterTransactionSubscription,
+}
 
-    fn on_node_added(&mut self, document: &RadiantDocumentNode<N>, id: Uuid) {
-        block_on(async {
-            let connection = self.connection.write();
-            let awareness = connection.awareness();
-            #[cfg(not(target_arch = "wasm32"))]
-            let awareness = awareness.write().await;
-            #[cfg(target_arch = "wasm32")]
-            let Some(awareness) = awareness.try_write() else {
+impl RemoteChanges {
+    /// Applies remote changes of `doc` to `document`, without calling back the listener with
+    /// `listener_id`. Changed nodes are replaced once the transaction is done, after the edits of
+    /// their text, so that the styles of the new characters are not counted twice.
+    pub fn observe<N: 'static + RadiantNode + serde::de::DeserializeOwned>(
+        doc: &Doc,
+        document: Weak<RwLock<RadiantDocumentNode<N>>>,
+        listener_id: Uuid,
+    ) -> Option<Self> {
+        let mut root = doc.get_or_insert_map(ROOT);
+        let mut texts = doc.get_or_insert_map(TEXTS);
+        let updated = Arc::new(Mutex::new(Vec::<(Uuid, String)>::new()));
+
+        let document_clone = document.clone();
+        let updated_clone = updated.clone();
+        let root_sub = root.observe(move |txn, event| {
+            if txn.origin() == Some(&Origin::from(LOCAL_ORIGIN)) {
+                return;
+            }
+            let Some(document) = document_clone.upgrade() else {
                 return;
             };
-            if let Some(node) = document.node(id) {
-                let doc = awareness.doc();
-                let Ok(mut txn) = doc.try_transact_mut() else {
-                    log::error!("Failed to transact");
-                    return;
+            let Some(mut document) = document.try_write() else {
+                return;
+            };
+            for (key, change) in event.keys(txn).iter() {
+                let Ok(id) = Uuid::parse_str(key) else {
+                    log::warn!("Ignoring remote node with invalid id {}", key);
+                    continue;
+                };
+                match change {
+                    EntryChange::Inserted(val) => {
+                        let Some(mut node) = parse_node::<N>(id, val) else {
+                            continue;
+                        };
+                        node.set_needs_tessellation(false);
+                        if document.get_node(id).is_none() {
+                            document.add_excluding_listener(node, listener_id);
+                        }
+                    }
+                    EntryChange::Removed(_val) => {
+                        document.remove_excluding_listener(id, listener_id);
+                    }
+                    EntryChange::Updated(_old, new) => {
+                        let Ok(node) = new.clone().cast::<String>() else {
+                            log::warn!("Ignoring remote node {} that is not a string", id);
+                            continue;
+                        };
+                        updated_clone.lock().push((id, node));
+                    }
+                }
+            }
+        });
+
+        let document_clone = document.clone();
+        let texts_sub = texts.observe_deep(move |txn, events| {
+            if txn.origin() == Some(&Origin::from(LOCAL_ORIGIN)) {
+                return;
+            }
+            let Some(document) = document_clone.upgrade() else {
+                return;
+            };
+            let Some(mut document) = document.try_write() else {
+                return;
+            };
+            for event in events.iter() {
+                let Event::Text(event) = event else {
+                    continue;
+                };
+                let Some(PathSegment::Key(key)) = event.path().pop_front() else {
+                    continue;
                 };
-                if let Some(root) = txn.get_map("radiantkit-root") {
-                    root.insert(
-                        &mut txn,
-                        id.to_string(),
-                        serde_json::to_string(node).unwrap(),
-                    );
+                let Ok(id) = Uuid::parse_str(&key) else {
+                    continue;
+                };
+                let delta: Vec<RadiantTextDelta> =
+                    event.delta(txn).iter().filter_map(to_text_delta).collect();
+                if let Some(mut node) = document.get_node_mut(id) {
+                    node.apply_text_delta(&delta);
                 }
-                txn.commit();
             }
         });
+
+        let after_transaction_sub = doc
+            .observe_after_transaction(move |txn| {
+                let updated = std::mem::take(&mut *updated.lock());
+                if updated.is_empty() {
+                    return;
+                }
+                let Some(document) = document.upgrade() else {
+                    return;
+                };
+                let Some(mut document) = document.try_write() else {
+                    return;
+                };
+                for (id, data) in updated {
+                    let Some(mut node) = document.get_node_mut(id) else {
+                        continue;
+                    };
+                    // The characters follow the text's own deltas, which merge.
+                    let data = match node.text() {
+                        Some(text) if texts.contains_key(txn, &id.to_string()) => {
+                            let Ok(mut data) = serde_json::from_str::<serde_json::Value>(&data)
+                            else {
+                                log::warn!("Ignoring invalid remote node {}", id);
+                                continue;
+                            };
+                            data["text"] = text.into();
+                            data.to_string()
+                        }
+                        _ => data,
+                    };
+                    node.replace(&data);
+                }
+            })
+            .ok()?;
+
+        Some(Self {
+            _root_sub: root_sub,
+            _texts_sub: texts_sub,
+            _after_transaction_sub: after_transaction_sub,
+        })
     }
+}
 
-    fn on_node_changed(&mut self, id: Uuid, data: &str) {
-        let connection_clone = self.connection.clone();
+fn parse_node<N: serde::de::DeserializeOwned>(id: Uuid, value: &Value) -> Option<N> {
+    let Ok(node) = value.clone().cast::<String>() else {
+        log::warn!("Ignoring remote node {} that is not a string", id);
+        return None;
+    };
+    match serde_json::from_str(&node) {
+        Ok(node) => Some(node),
+        Err(error) => {
+            log::warn!("Ignoring invalid remote node {}: {}", id, error);
+            None
+        }
+    }
+}
+
+fn to_text_delta(delta: &Delta) -> Option<RadiantTextDelta> {
+    match delta {
+        Delta::Retain(units, _) => Some(RadiantTextDelta::Retain(*units as usize)),
+        Delta::Deleted(units) => Some(RadiantTextDelta::Delete(*units as usize)),
+        Delta::Inserted(Value::Any(Any::String(text)), _) => {
+            Some(RadiantTextDelta::Insert(text.to_string()))
+        }
+        Delta::Inserted(..) => None,
+    }
+}
+
+impl<N: RadiantNode> Collaborator<N> {
+    fn send(&self, change: NodeChange) {
         #[cfg(not(target_arch = "wasm32"))]
+        if self.changes.send(change).is_err() {
+            log::error!("Failed to send a node change");
+        }
+
+        #[cfg(target_arch = "wasm32")]
         {
-            let data = data.to_string();
-            tokio::spawn(async move {
-               handle_node_change(connection_clone, id, &data);
+            let connection = self.connection.read();
+            let awareness = connection.awareness();
+            let Some(awareness) = awareness.try_write() else {
+                log::error!("Failed to send a node change");
+                return;
+            };
+            send_node_change(awareness.doc(), &change);
+        }
+    }
+}
+
+impl<N: RadiantNode> RadiantDocumentListener<N> for Collaborator<N> {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn on_node_added(&mut self, document: &RadiantDocumentNode<N>, id: Uuid) {
+        if let Some(node) = document.node(id) {
+            self.send(NodeChange::Added {
+                id,
+                data: serde_json::to_string(node).unwrap(),
+                text: node.read().text().map(str::to_string),
             });
         }
-        
-        #[cfg(target_arch = "wasm32")]
-        handle_node_change(connection_clone, id, data);
+    }
+
+    fn on_node_removed(&mut self, id: Uuid) {
+        self.send(NodeChange::Removed { id });
+    }
+
+    fn on_node_changed(&mut self, id: Uuid, data: &str) {
+        let (deltas, pending) = std::mem::take(&mut self.pending_text)
+            .into_iter()
+            .partition(|(node_id, _)| *node_id == id);
+        self.pending_text = pending;
+        self.send(NodeChange::Changed {
+            id,
+            deltas: deltas.into_iter().map(|(_, delta)| delta).collect(),
+            data: data.to_string(),
+        });
+    }
+
+    fn on_text_changed(&mut self, id: Uuid, delta: &[RadiantTextDelta]) {
+        self.pending_text.push((id, delta.to_vec()));
     }
 }
 
-fn handle_node_change(connection: Arc<RwLock<Connection>>, id: Uuid, data: &str) {
-    let connection = connection.write();
-    let awareness = connection.awareness();
-    #[cfg(not(target_arch = "wasm32"))]
-    let Ok(awareness) = awareness.try_write() else { return };
-    #[cfg(target_arch = "wasm32")]
-    let Some(awareness) = awareness.try_write() else { return };
-    let doc = awareness.doc();
-    let Ok(mut txn) = doc.try_transact_mut() else {
+/// Applies `change` to the shared document. The text edits of a changed node are sent before
+/// the node itself, in the same transaction.
+fn send_node_change(doc: &Doc, change: &NodeChange) {
+    let Ok(mut txn) = doc.try_transact_mut_with(LOCAL_ORIGIN) else {
         log::error!("Failed to transact");
         return;
     };
-    if let Some(root) = txn.get_map("radiantkit-root") {
-        root.insert(
-            &mut txn,
-            id.to_string(),
-            data,
-        );
+    match change {
+        NodeChange::Added { id, data, text } => {
+            if let Some(root) = txn.get_map(ROOT) {
+                root.insert(&mut txn, id.to_string(), data.as_str());
+            }
+            if let (Some(text), Some(texts)) = (text, txn.get_map(TEXTS)) {
+                texts.insert(&mut txn, id.to_string(), TextPrelim::new(text.as_str()));
+            }
+        }
+        NodeChange::Removed { id } => {
+            if let Some(root) = txn.get_map(ROOT) {
+                root.remove(&mut txn, &id.to_string());
+            }
+            if let Some(texts) = txn.get_map(TEXTS) {
+                texts.remove(&mut txn, &id.to_string());
+            }
+        }
+        NodeChange::Changed { id, deltas, data } => {
+            let text = txn
+                .get_map(TEXTS)
+                .and_then(|texts| texts.get(&txn, &id.to_string()));
+            if let Some(Value::YText(text)) = text {
+                for delta in deltas {
+                    apply_text_delta(&text, &mut txn, delta);
+                }
+            }
+            if let Some(root) = txn.get_map(ROOT) {
+                root.insert(&mut txn, id.to_string(), data.as_str());
+            }
+        }
     }
     txn.commit();
 }
+
+fn apply_text_delta(text: &TextRef, txn: &mut TransactionMut, delta: &[RadiantTextDelta]) {
+    let mut index = 0;
+    for step in delta {
+        match step {
+            RadiantTextDelta::Retain(units) => index += *units as u32,
+            RadiantTextDelta::Insert(chunk) => {
+                text.insert(txn, index, chunk);
+                index += chunk.encode_utf16().count() as u32;
+            }
+            RadiantTextDelta::Delete(units) => text.remove_range(txn, index, *units as u32),
+        }
+    }
+}
@@ -2,7 +2,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::{RadiantComponent, Vec3};
 
-const MIN_SIZE: f32 = 8.0;
+/// Smallest size a transform can be scaled to.
+pub const MIN_SIZE: f32 = 8.0;
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen::prelude::wasm_bindgen)]
 #[cfg_attr(not(target_arch = "wasm32"), radiantkit_macros::radiant_wasm_bindgen)]
@@ -1,23 +1,75 @@
-use std::{collections::BTreeMap, cell::RefCell, rc::Rc, sync::Arc};
-use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::{collections::{BTreeMap, BTreeSet, HashMap}, cell::RefCell, rc::Rc, sync::Arc};
+use parking_lot::{Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use crate::{
-    RadiantGroupNode, RadiantNode, RadiantSelectable, RadiantTessellatable, ScreenDescriptor,
-    SelectionComponent, SubscriptionId,
+    RadiantDrawList, RadiantExportTarget, RadiantFrameStats, RadiantGroupNode, RadiantNode, RadiantPickingIds,
+    RadiantSelectable, RadiantSpatialIndex, RadiantTextDelta, RadiantVectorNode,
+    RadiantTessellatable, ScreenDescriptor, SelectionComponent, SubscriptionId,
+    VIEWPORT_CULLING_MARGIN,
 };
 use epaint::ClippedPrimitive;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use uuid::Uuid;
 
+/// Bounds reported by nodes that the spatial index has not picked up yet.
+type PendingBounds = Arc<Mutex<Vec<(Uuid, [f32; 4])>>>;
+
 #[derive(Serialize, Deserialize)]
+#[serde(from = "RadiantDocumentData<N>", bound(deserialize = "N: DeserializeOwned"))]
 pub struct RadiantDocumentNode<N: RadiantNode> {
     pub counter: u64,
     pub artboards: BTreeMap<Uuid, RadiantGroupNode<N>>,
     pub active_artboard_id: Uuid,
     pub selected_node_id: Option<Uuid>,
+    /// Named font families used by the document's text. Saved so that they can be loaded before
+    /// the document is drawn.
+    #[serde(default)]
+    pub font_families: BTreeSet<String>,
+    /// Named font families of each node that uses any, so that edits only revisit that node.
+    #[serde(skip)]
+    node_font_families: HashMap<Uuid, BTreeSet<String>>,
+    /// How many nodes use each of `font_families`.
+    #[serde(skip)]
+    font_family_users: BTreeMap<String, usize>,
     #[serde(skip)]
     listeners: Rc<RefCell<Vec<Box<dyn RadiantDocumentListener<N>>>>>,
     #[serde(skip)]
     subscriptions: Vec<SubscriptionId>,
+    #[serde(skip)]
+    node_artboards: HashMap<Uuid, Uuid>,
+    #[serde(skip)]
+    spatial_index: Mutex<RadiantSpatialIndex>,
+    #[serde(skip)]
+    pending_bounds: PendingBounds,
+    #[serde(skip)]
+    frame_stats: RadiantFrameStats,
+    #[serde(skip)]
+    picking_ids: RadiantPickingIds,
+}
+
+/// The serialized fields of a [`RadiantDocumentNode`], from which the rest is rebuilt.
+#[derive(Deserialize)]
+struct RadiantDocumentData<N: RadiantNode> {
+    counter: u64,
+    artboards: BTreeMap<Uuid, RadiantGroupNode<N>>,
+    active_artboard_id: Uuid,
+    selected_node_id: Option<Uuid>,
+    #[serde(default)]
+    font_families: BTreeSet<String>,
+}
+
+impl<N: RadiantNode> From<RadiantDocumentData<N>> for RadiantDocumentNode<N> {
+    fn from(data: RadiantDocumentData<N>) -> Self {
+        let mut document = Self {
+            counter: data.counter,
+            artboards: data.artboards,
+            active_artboard_id: data.active_artboard_id,
+            selected_node_id: data.selected_node_id,
+            font_families: data.font_families,
+            ..Self::new()
+        };
+        document.index_nodes();
+        document
+    }
 }
 
 unsafe impl<N: RadiantNode> Send for RadiantDocumentNode<N> {}
@@ -33,8 +85,16 @@ impl<N: RadiantNode> RadiantDocumentNode<N> {
             artboards,
             active_artboard_id: artboard_id,
             selected_node_id: None,
+            font_families: BTreeSet::new(),
+            node_font_families: HashMap::new(),
+            font_family_users: BTreeMap::new(),
             listeners: Rc::new(Vec::new().into()),
             subscriptions: Vec::new(),
+            node_artboards: HashMap::new(),
+            spatial_index: Mutex::new(RadiantSpatialIndex::new()),
+            pending_bounds: Arc::new(Mutex::new(Vec::new())),
+            frame_stats: RadiantFrameStats::default(),
+            picking_ids: RadiantPickingIds::new(),
         }
     }
 
@@ -61,15 +121,29 @@ impl<N: RadiantNode> RadiantDocumentNode<N> {
     }
 
     fn add_node(&mut self, mut node: N, exclude_listener_id: Option<Uuid>) {
+        if !self.artboards.contains_key(&self.active_artboard_id) {
+            return;
+        }
+        self.observe_bounds(&mut node);
         if let Some(artboard) = self.artboards.get_mut(&self.active_artboard_id) {
             let id = node.get_id();
+            let font_families = node.font_families();
             let listeners = self.listeners.clone();
             let subscription = node.observe(move |data| {
                 listeners.borrow_mut().iter_mut().for_each(|listener| {
                     listener.on_node_changed(id, data);
                 });
             });
+            let listeners = self.listeners.clone();
+            node.base_mut().text_observer = Some(Arc::new(move |id, delta| {
+                listeners.borrow_mut().iter_mut().for_each(|listener| {
+                    listener.on_text_changed(id, delta);
+                });
+            }));
+            node.base_mut().set_pick_id(self.picking_ids.insert(id));
+            self.node_artboards.insert(id, self.active_artboard_id);
             artboard.add(node);
+            self.track_font_families(id, font_families);
 
             let listeners = self.listeners.clone();
             listeners.borrow_mut()
@@ -87,6 +161,167 @@ impl<N: RadiantNode> RadiantDocumentNode<N> {
         }
     }
 
+    /// Removes the node from its artboard and releases its picking id.
+    pub fn remove(&mut self, id: Uuid) -> Option<Arc<RwLock<N>>> {
+        self.remove_node(id, None)
+    }
+
+    pub fn remove_excluding_listener(
+        &mut self,
+        id: Uuid,
+        listener_id: Uuid,
+    ) -> Option<Arc<RwLock<N>>> {
+        self.remove_node(id, Some(listener_id))
+    }
+
+    fn remove_node(
+        &mut self,
+        id: Uuid,
+        exclude_listener_id: Option<Uuid>,
+    ) -> Option<Arc<RwLock<N>>> {
+        let node = match self.node_artboards.remove(&id) {
+            Some(artboard_id) => self.artboards.get_mut(&artboard_id)?.remove(id),
+            None => self
+                .artboards
+                .values_mut()
+                .find_map(|artboard| artboard.remove(id)),
+        }?;
+        node.write().detach();
+        self.spatial_index().remove(id);
+        self.picking_ids.remove(id);
+        if self.selected_node_id == Some(id) {
+            self.selected_node_id = None;
+        }
+        self.track_font_families(id, Vec::new());
+
+        self.listeners
+            .borrow_mut()
+            .iter_mut()
+            .filter(|l| match exclude_listener_id {
+                Some(id) => l.get_id() != id,
+                None => true,
+            })
+            .for_each(|listener| {
+                listener.on_node_removed(id);
+            });
+
+        self.counter += 1;
+        Some(node)
+    }
+
+    fn observe_bounds(&self, node: &mut N) {
+        let pending_bounds = self.pending_bounds.clone();
+        node.base_mut().bounds_observer = Some(Arc::new(move |id, bounding_rect| {
+            pending_bounds.lock().push((id, bounding_rect));
+        }));
+        self.spatial_index
+            .lock()
+            .update(node.get_id(), node.get_bounding_rect());
+    }
+
+    /// Tracks the artboard and bounds of every node, e.g. after deserialization.
+    fn index_nodes(&mut self) {
+        let mut node_font_families = Vec::new();
+        for (artboard_id, artboard) in &self.artboards {
+            for (id, node) in &artboard.nodes {
+                let mut node = node.write();
+                self.observe_bounds(&mut node);
+                // Bounds aren't saved, so the node works them out again.
+                node.set_needs_tessellation(false);
+                self.node_artboards.insert(*id, *artboard_id);
+                node_font_families.push((*id, node.font_families()));
+            }
+        }
+        // The saved families may be stale, so the nodes decide which ones are used.
+        self.font_families.clear();
+        for (id, families) in node_font_families {
+            self.track_font_families(id, families);
+        }
+    }
+
+    /// Applies the bounds changes reported by nodes since the last query.
+    fn spatial_index(&self) -> MutexGuard<RadiantSpatialIndex> {
+        let mut index = self.spatial_index.lock();
+        for (id, bounding_rect) in self.pending_bounds.lock().drain(..) {
+            if self.node_artboards.contains_key(&id) {
+                index.update(id, bounding_rect);
+            }
+        }
+        index
+    }
+
+    /// Ids of the nodes to draw in `viewport` by artboard, looked up in the spatial index.
+    /// Nodes which have not computed their bounds yet are included.
+    fn visible_nodes(&self, viewport: [f32; 4]) -> HashMap<Uuid, BTreeSet<Uuid>> {
+        let index = self.spatial_index();
+        let mut visible: HashMap<Uuid, BTreeSet<Uuid>> = HashMap::new();
+        for id in index.query_rect(viewport).into_iter().chain(index.unplaced()) {
+            if let Some(artboard_id) = self.node_artboards.get(&id) {
+                visible.entry(*artboard_id).or_default().insert(id);
+            }
+        }
+        visible
+    }
+
+    /// Ids of the nodes whose bounds intersect `rect` (`[min_x, min_y, max_x, max_y]`).
+    pub fn query_rect(&self, rect: [f32; 4]) -> Vec<Uuid> {
+        self.spatial_index().query_rect(rect)
+    }
+
+    /// Ids of the nodes whose bounds, grown by `tolerance`, contain `point`.
+    pub fn query_point(&self, point: [f32; 2], tolerance: f32) -> Vec<Uuid> {
+        self.spatial_index().query_point(point, tolerance)
+    }
+
+    pub fn nearest_node(&self, point: [f32; 2]) -> Option<Uuid> {
+        self.spatial_index().nearest(point)
+    }
+
+    /// Ids of the nodes within `max_distance` of `point`, closest first.
+    pub fn nearest_nodes(&self, point: [f32; 2], max_distance: f32) -> Vec<Uuid> {
+        self.spatial_index().nearest_within(point, max_distance)
+    }
+
+    pub fn picking_ids(&self) -> &RadiantPickingIds {
+        &self.picking_ids
+    }
+
+    pub fn picking_ids_mut(&mut self) -> &mut RadiantPickingIds {
+        &mut self.picking_ids
+    }
+
+    /// Culling statistics of the last (non-selection) tessellation pass.
+    pub fn frame_stats(&self) -> RadiantFrameStats {
+        self.frame_stats
+    }
+
+    /// Fills `draw_list` with the visible nodes, tessellating only the ones whose mesh the
+    /// renderer does not hold yet.
+    pub fn collect(
+        &mut self,
+        selection: bool,
+        screen_descriptor: &ScreenDescriptor,
+        fonts_manager: &epaint::text::Fonts,
+        draw_list: &mut RadiantDrawList,
+    ) {
+        let visible = self.visible_nodes(screen_descriptor.viewport(VIEWPORT_CULLING_MARGIN));
+        let none = BTreeSet::new();
+        let mut stats = RadiantFrameStats::default();
+        for (artboard_id, artboard) in self.artboards.iter_mut() {
+            artboard.collect_visible(
+                selection,
+                screen_descriptor,
+                fonts_manager,
+                visible.get(artboard_id).unwrap_or(&none),
+                &mut stats,
+                draw_list,
+            );
+        }
+        if !selection {
+            self.frame_stats = stats;
+        }
+    }
+
     pub fn set_active_artboard(&mut self, id: Uuid) {
         self.active_artboard_id = id;
     }
@@ -120,7 +355,59 @@ impl<N: RadiantNode> RadiantDocumentNode<N> {
         self.selected_node_id = id
     }
 
+    /// Nodes covered by `target`, in draw order.
+    pub fn export_nodes(&self, target: RadiantExportTarget) -> Vec<Arc<RwLock<N>>> {
+        match target {
+            RadiantExportTarget::Document => self
+                .artboards
+                .values()
+                .flat_map(|artboard| artboard.nodes.values().cloned())
+                .collect(),
+            RadiantExportTarget::Artboard { id } => self
+                .artboards
+                .get(&id)
+                .map(|artboard| artboard.nodes.values().cloned().collect())
+                .unwrap_or_default(),
+            RadiantExportTarget::Selection => self
+                .selected_node_id
+                .and_then(|id| self.node(id))
+                .cloned()
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// Vector descriptions of the nodes covered by `target`. Each artboard becomes a group,
+    /// and artboards with nothing to export are left out.
+    pub fn vector_nodes(&self, target: RadiantExportTarget) -> Vec<RadiantVectorNode> {
+        let artboard = |(id, artboard): (&Uuid, &RadiantGroupNode<N>)| {
+            let children: Vec<_> = artboard
+                .nodes
+                .values()
+                .filter_map(|node| node.read().to_vector())
+                .collect();
+            (!children.is_empty()).then(|| RadiantVectorNode::group(*id, children))
+        };
+        match target {
+            RadiantExportTarget::Document => self.artboards.iter().filter_map(artboard).collect(),
+            RadiantExportTarget::Artboard { id } => self
+                .artboards
+                .get_key_value(&id)
+                .and_then(artboard)
+                .into_iter()
+                .collect(),
+            RadiantExportTarget::Selection => self
+                .export_nodes(target)
+                .iter()
+                .filter_map(|node| node.read().to_vector())
+                .collect(),
+        }
+    }
+
     pub fn node(&self, id: Uuid) -> Option<&Arc<RwLock<N>>> {
+        if let Some(artboard_id) = self.node_artboards.get(&id) {
+            return self.artboards.get(artboard_id)?.node(id);
+        }
         for artboard in &self.artboards {
             if let Some(node) = artboard.1.node(id) {
                 return Some(node);
@@ -130,28 +417,103 @@ impl<N: RadiantNode> RadiantDocumentNode<N> {
     }
 
     pub fn get_node(&self, id: Uuid) -> Option<RwLockReadGuard<N>> {
-        for artboard in &self.artboards {
-            if let Some(node) = artboard.1.get_node(id) {
-                return Some(node);
-            }
-        }
-        None
+        self.node(id).map(|node| node.read())
     }
 
     pub fn get_node_mut(&mut self, id: Uuid) -> Option<RwLockWriteGuard<N>> {
-        for artboard in &mut self.artboards {
-            if let Some(node) = artboard.1.get_node_mut(id) {
-                return Some(node);
+        self.node(id).map(|node| node.write())
+    }
+
+    /// Geometric hit test against every artboard, topmost node first. Artboards are drawn in
+    /// order, so later artboards sit on top of earlier ones.
+    pub fn hit_stack(&self, position: [f32; 2], tolerance: f32) -> Vec<Uuid> {
+        let mut hits: Vec<(Uuid, Uuid)> = self
+            .query_point(position, tolerance)
+            .into_iter()
+            .filter_map(|id| {
+                let artboard_id = *self.node_artboards.get(&id)?;
+                let node = self.artboards.get(&artboard_id)?.get_node(id)?;
+                node.hit_test(position, tolerance)
+                    .then_some((artboard_id, id))
+            })
+            .collect();
+        // Nodes are drawn in id order within an artboard, and artboards in id order.
+        hits.sort_unstable_by(|a, b| b.cmp(a));
+        hits.into_iter().map(|(_, id)| id).collect()
+    }
+
+    pub fn hit_test(&self, position: [f32; 2], tolerance: f32) -> Option<Uuid> {
+        self.hit_stack(position, tolerance).first().copied()
+    }
+
+    /// The node drawn last among `ids`.
+    pub fn topmost(&self, ids: impl IntoIterator<Item = Uuid>) -> Option<Uuid> {
+        ids.into_iter()
+            .filter_map(|id| Some((*self.node_artboards.get(&id)?, id)))
+            .max()
+            .map(|(_, id)| id)
+    }
+
+    pub fn replace_node(&mut self, id: Uuid, mut node: N) {
+        let Some(artboard_id) = self
+            .artboards
+            .iter()
+            .find(|artboard| artboard.1.node(id).is_some())
+            .map(|artboard| *artboard.0)
+        else {
+            return;
+        };
+        self.observe_bounds(&mut node);
+        node.base_mut().set_pick_id(self.picking_ids.insert(id));
+        self.node_artboards.insert(id, artboard_id);
+        let font_families = node.font_families();
+        if let Some(artboard) = self.artboards.get_mut(&artboard_id) {
+            artboard.replace_node(id, node);
+        }
+        self.track_font_families(id, font_families);
+    }
+
+    /// Updates `font_families` after the node `id` was edited, and returns whether they changed.
+    pub fn update_font_families(&mut self, id: Uuid) -> bool {
+        let font_families = match self.get_node(id) {
+            Some(node) => node.font_families(),
+            None => Vec::new(),
+        };
+        self.track_font_families(id, font_families)
+    }
+
+    /// Records the families node `id` uses now, and returns whether `font_families` changed.
+    fn track_font_families(&mut self, id: Uuid, font_families: Vec<String>) -> bool {
+        let font_families: BTreeSet<String> = font_families.into_iter().collect();
+        let previous = if font_families.is_empty() {
+            self.node_font_families.remove(&id)
+        } else {
+            self.node_font_families.insert(id, font_families.clone())
+        }
+        .unwrap_or_default();
+
+        let mut changed = false;
+        for family in previous.difference(&font_families) {
+            if let Some(users) = self.font_family_users.get_mut(family) {
+                *users -= 1;
+                if *users == 0 {
+                    self.font_family_users.remove(family);
+                    changed |= self.font_families.remove(family);
+                }
             }
         }
-        None
+        for family in font_families.difference(&previous) {
+            *self.font_family_users.entry(family.clone()).or_default() += 1;
+            changed |= self.font_families.insert(family.clone());
+        }
+        changed
     }
 
-    pub fn replace_node(&mut self, id: Uuid, node: N) {
-        for artboard in &mut self.artboards {
-            if artboard.1.get_node_mut(id).is_some() {
-                artboard.1.replace_node(id, node);
-                return;
+    /// Makes every node tessellate again, e.g. after the fonts changed.
+    pub fn invalidate(&mut self) {
+        for artboard in self.artboards.values() {
+            for node in artboard.nodes.values() {
+                node.write().set_needs_tessellation(false);
             }
         }
     }
@@ -178,16 +540,26 @@ impl<N: RadiantNode> RadiantTessellatable for RadiantDocumentNode<N> {
         screen_descriptor: &ScreenDescriptor,
         fonts_manager: &epaint::text::Fonts,
     ) -> Vec<ClippedPrimitive> {
-        self.artboards
+        let visible = self.visible_nodes(screen_descriptor.viewport(VIEWPORT_CULLING_MARGIN));
+        let none = BTreeSet::new();
+        let mut stats = RadiantFrameStats::default();
+        let primitives = self
+            .artboards
             .iter_mut()
             .fold(Vec::new(), |mut primitives, artboard| {
-                primitives.append(&mut artboard.1.tessellate(
+                primitives.append(&mut artboard.1.tessellate_visible(
                     selection,
                     screen_descriptor,
                     fonts_manager,
+                    visible.get(artboard.0).unwrap_or(&none),
+                    &mut stats,
                 ));
                 primitives
-            })
+            });
+        if !selection {
+            self.frame_stats = stats;
+        }
+        primitives
     }
 }
 
@@ -195,4 +567,7 @@ pub trait RadiantDocumentListener<N: RadiantNode> {
     fn get_id(&self) -> Uuid;
     fn on_node_added(&mut self, document: &RadiantDocumentNode<N>, node: Uuid);
     fn on_node_changed(&mut self, id: Uuid, data: &str);
+    /// Characters of a text node were edited, before the change is reported as a whole.
+    fn on_text_changed(&mut self, _id: Uuid, _delta: &[RadiantTextDelta]) {}
+    fn on_node_removed(&mut self, id: Uuid);
 }
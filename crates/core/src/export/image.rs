@@ -0,0 +1,110 @@
+use crate::{RadiantExportError, RadiantExportTarget, RadiantPixmap};
+use epaint::{ClippedPrimitive, Color32, Primitive, Rect};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum RadiantExportBackground {
+    Transparent,
+    Filled { color: Color32 },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum RadiantImageFormat {
+    Png,
+    /// JPEG has no alpha channel, so a transparent background is exported as white.
+    Jpeg {
+        quality: u8,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RadiantImageExportOptions {
+    pub target: RadiantExportTarget,
+    /// Pixels per point of the exported image, e.g. 1, 2 or 3.
+    pub scale: f32,
+    pub background: RadiantExportBackground,
+    pub format: RadiantImageFormat,
+}
+
+impl Default for RadiantImageExportOptions {
+    fn default() -> Self {
+        Self {
+            target: RadiantExportTarget::Document,
+            scale: 1.0,
+            background: RadiantExportBackground::Transparent,
+            format: RadiantImageFormat::Png,
+        }
+    }
+}
+
+/// Smallest rect, in points, covering every vertex of `primitives`.
+pub fn primitives_bounds(primitives: &[ClippedPrimitive]) -> Option<Rect> {
+    let mut bounds = Rect::NOTHING;
+    for primitive in primitives {
+        if let Primitive::Mesh(mesh) = &primitive.primitive {
+            for vertex in &mesh.vertices {
+                bounds.extend_with(vertex.pos);
+            }
+        }
+    }
+    bounds.is_positive().then_some(bounds)
+}
+
+/// Moves `primitives` so that `origin` ends up at the top left corner of the image.
+pub fn translate_primitives(primitives: &mut [ClippedPrimitive], origin: epaint::Pos2) {
+    let delta = -origin.to_vec2();
+    for primitive in primitives {
+        primitive.clip_rect = primitive.clip_rect.translate(delta);
+        if let Primitive::Mesh(mesh) = &mut primitive.primitive {
+            mesh.translate(delta);
+        }
+    }
+}
+
+/// Encodes a premultiplied pixmap as PNG or JPEG.
+pub fn encode_pixmap(
+    pixmap: &RadiantPixmap,
+    format: RadiantImageFormat,
+) -> Result<Vec<u8>, RadiantExportError> {
+    let [width, height] = pixmap.size;
+    let mut bytes = Vec::new();
+    let mut cursor = std::io::Cursor::new(&mut bytes);
+    let result = match format {
+        RadiantImageFormat::Png => {
+            let data = pixmap
+                .pixels
+                .iter()
+                .flat_map(|color| color.to_srgba_unmultiplied())
+                .collect();
+            image::RgbaImage::from_raw(width, height, data)
+                .expect("pixmap size matches its pixels")
+                .write_to(&mut cursor, image::ImageOutputFormat::Png)
+        }
+        RadiantImageFormat::Jpeg { quality } => {
+            let data = pixmap
+                .pixels
+                .iter()
+                .flat_map(|color| {
+                    // Composite the premultiplied color over white.
+                    let [r, g, b, a] = color.to_array();
+                    let white = 255 - a;
+                    [
+                        r.saturating_add(white),
+                        g.saturating_add(white),
+                        b.saturating_add(white),
+                    ]
+                })
+                .collect();
+            image::RgbImage::from_raw(width, height, data)
+                .expect("pixmap size matches its pixels")
+                .write_to(
+                    &mut cursor,
+                    image::ImageOutputFormat::Jpeg(quality.clamp(1, 100)),
+                )
+        }
+    };
+    result.map_err(|error| RadiantExportError::Encoding {
+        reason: error.to_string(),
+    })?;
+    Ok(bytes)
+}
@@ -0,0 +1,57 @@
+pub mod image;
+pub mod pdf;
+pub mod svg;
+pub mod svg_import;
+pub mod vector;
+
+pub use self::image::*;
+pub use pdf::*;
+pub use svg::*;
+pub use svg_import::*;
+pub use vector::*;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Largest width or height, in pixels, an export may have.
+pub const MAX_EXPORT_SIDE: u32 = 8192;
+
+/// What part of the document to export.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum RadiantExportTarget {
+    Document,
+    Artboard { id: Uuid },
+    Selection,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum RadiantExportError {
+    /// The target has nothing to draw.
+    EmptyTarget,
+    InvalidScale {
+        scale: f32,
+    },
+    TooLarge {
+        size: [u32; 2],
+    },
+    Encoding {
+        reason: String,
+    },
+}
+
+impl std::fmt::Display for RadiantExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EmptyTarget => write!(f, "nothing to export"),
+            Self::InvalidScale { scale } => write!(f, "invalid export scale {scale}"),
+            Self::TooLarge { size } => write!(
+                f,
+                "export of {}x{} pixels exceeds {MAX_EXPORT_SIDE} pixels per side",
+                size[0], size[1]
+            ),
+            Self::Encoding { reason } => write!(f, "failed to encode export: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for RadiantExportError {}
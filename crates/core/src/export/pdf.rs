@@ -0,0 +1,565 @@
+use crate::{
+    bold_family, RadiantExportError, RadiantFillRule, RadiantPathCommand, RadiantPixmap,
+    RadiantVectorGeometry, RadiantVectorNode, RadiantVectorTextRun,
+};
+use epaint::{text::FontDefinitions, Color32, FontFamily, TextureId};
+use pdf_writer::{
+    types::{CidFontType, FontFlags, SystemInfo, UnicodeCmap},
+    Content, Filter, Finish, Name, Pdf, Rect, Ref, Str,
+};
+use std::collections::BTreeMap;
+
+const COMPRESSION_LEVEL: u8 = 6;
+/// Horizontal shift of the top of italic glyphs per unit of height, as epaint slants them.
+const ITALIC_SKEW: f32 = 0.25;
+
+/// Writes each of `pages` as a PDF page sized to its bounds. Shapes and paths stay vectors,
+/// text is set in the fonts its families have in `font_definitions` so that it can be
+/// selected, and `texture` provides the pixels of image nodes.
+pub fn write_pdf(
+    pages: &[RadiantVectorNode],
+    font_definitions: &FontDefinitions,
+    texture: impl Fn(TextureId) -> Option<RadiantPixmap>,
+) -> Result<Vec<u8>, RadiantExportError> {
+    if pages.is_empty() {
+        return Err(RadiantExportError::EmptyTarget);
+    }
+
+    let mut writer = PdfWriter {
+        pdf: Pdf::new(),
+        next_id: 1,
+        font_definitions,
+        fonts: parse_fonts(font_definitions)?,
+        graphics_states: Vec::new(),
+        texture,
+    };
+    let catalog_id = writer.alloc();
+    let page_tree_id = writer.alloc();
+    let page_ids: Vec<Ref> = pages.iter().map(|_| writer.alloc()).collect();
+
+    for (page, page_id) in pages.iter().zip(&page_ids) {
+        writer.write_page(page, *page_id, page_tree_id);
+    }
+    writer.write_fonts();
+
+    writer.pdf.catalog(catalog_id).pages(page_tree_id);
+    writer
+        .pdf
+        .pages(page_tree_id)
+        .kids(page_ids.iter().copied())
+        .count(page_ids.len() as i32);
+    Ok(writer.pdf.finish())
+}
+
+fn parse_fonts(
+    font_definitions: &FontDefinitions,
+) -> Result<BTreeMap<&str, PdfFont<'_>>, RadiantExportError> {
+    let mut fonts = BTreeMap::new();
+    for (name, font_data) in &font_definitions.font_data {
+        let face = ttf_parser::Face::parse(&font_data.font, font_data.index).map_err(|error| {
+            RadiantExportError::Encoding {
+                reason: format!("{name}: {error}"),
+            }
+        })?;
+        fonts.insert(
+            name.as_str(),
+            PdfFont {
+                face,
+                data: &font_data.font,
+                id: None,
+                glyphs: BTreeMap::new(),
+            },
+        );
+    }
+    Ok(fonts)
+}
+
+/// Names of the fonts `run` is set in, in the order they are tried for each character. As in
+/// the text layout, bold runs use the bold faces of their family where it has any, and
+/// families that are not loaded are set in the proportional one.
+fn run_fonts<'a>(
+    font_definitions: &'a FontDefinitions,
+    run: &RadiantVectorTextRun,
+) -> &'a [String] {
+    let family = match &run.family {
+        Some(name) if run.bold && font_definitions.families.contains_key(&bold_family(name)) => {
+            bold_family(name)
+        }
+        Some(name) => FontFamily::Name(name.as_str().into()),
+        None => FontFamily::Proportional,
+    };
+    font_definitions
+        .families
+        .get(&family)
+        .or_else(|| font_definitions.families.get(&FontFamily::Proportional))
+        .map_or(&[], Vec::as_slice)
+}
+
+/// Font embedded whole once text is set in it, with the glyphs in use mapped back to text
+/// for copying.
+struct PdfFont<'a> {
+    face: ttf_parser::Face<'a>,
+    data: &'a [u8],
+    /// Object of the font, allocated when it is first used.
+    id: Option<Ref>,
+    glyphs: BTreeMap<u16, char>,
+}
+
+impl PdfFont<'_> {
+    /// Glyph ids of `text` as big endian pairs, for the Identity-H encoding. Characters the
+    /// font lacks are left out.
+    fn encode(&mut self, text: &str) -> Vec<u8> {
+        let mut encoded = Vec::with_capacity(text.len() * 2);
+        for c in text.chars() {
+            let Some(glyph) = self.face.glyph_index(c) else {
+                continue;
+            };
+            self.glyphs.entry(glyph.0).or_insert(c);
+            encoded.extend(glyph.0.to_be_bytes());
+        }
+        encoded
+    }
+
+    /// Width of `text` set at `size`.
+    fn width(&self, text: &str, size: f32) -> f32 {
+        let units_per_em = self.face.units_per_em() as f32;
+        text.chars()
+            .filter_map(|c| self.face.glyph_index(c))
+            .filter_map(|glyph| self.face.glyph_hor_advance(glyph))
+            .map(|advance| advance as f32 * size / units_per_em)
+            .sum()
+    }
+}
+
+/// Resource name of the font written as `id`.
+fn font_name(id: Ref) -> String {
+    format!("F{}", id.get())
+}
+
+struct PdfWriter<'a, T> {
+    pdf: Pdf,
+    next_id: i32,
+    font_definitions: &'a FontDefinitions,
+    /// Every font of `font_definitions`, by name.
+    fonts: BTreeMap<&'a str, PdfFont<'a>>,
+    /// Fill and stroke alphas of the graphics states written so far.
+    graphics_states: Vec<([u8; 2], Ref)>,
+    texture: T,
+}
+
+impl<'a, T: Fn(TextureId) -> Option<RadiantPixmap>> PdfWriter<'a, T> {
+    fn alloc(&mut self) -> Ref {
+        let id = Ref::new(self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    fn write_page(&mut self, page: &RadiantVectorNode, page_id: Ref, page_tree_id: Ref) {
+        let margin = page.max_stroke_width() / 2.0;
+        let [min_x, min_y, max_x, max_y] = [
+            page.bounds[0] - margin,
+            page.bounds[1] - margin,
+            page.bounds[2] + margin,
+            page.bounds[3] + margin,
+        ];
+
+        let mut content = Content::new();
+        // PDF pages grow upwards, so flip to the document's coordinates.
+        content.transform([1.0, 0.0, 0.0, -1.0, -min_x, max_y]);
+        let mut images = Vec::new();
+        self.write_node(&mut content, page, &mut images);
+        let content_id = self.alloc();
+        self.pdf.stream(content_id, &content.finish());
+
+        let mut pdf_page = self.pdf.page(page_id);
+        pdf_page
+            .media_box(Rect::new(0.0, 0.0, max_x - min_x, max_y - min_y))
+            .parent(page_tree_id)
+            .contents(content_id);
+        let mut resources = pdf_page.resources();
+        // Fonts are written once every page is, so list all those used so far.
+        let mut fonts = resources.fonts();
+        for id in self.fonts.values().filter_map(|font| font.id) {
+            fonts.pair(Name(font_name(id).as_bytes()), id);
+        }
+        fonts.finish();
+        let mut x_objects = resources.x_objects();
+        for (name, id) in &images {
+            x_objects.pair(Name(name.as_bytes()), *id);
+        }
+        x_objects.finish();
+        let mut graphics_states = resources.ext_g_states();
+        for (index, (_, id)) in self.graphics_states.iter().enumerate() {
+            graphics_states.pair(Name(format!("Gs{index}").as_bytes()), *id);
+        }
+    }
+
+    fn write_node(
+        &mut self,
+        content: &mut Content,
+        node: &RadiantVectorNode,
+        images: &mut Vec<(String, Ref)>,
+    ) {
+        content.save_state();
+        let (sin, cos) = node.rotation.sin_cos();
+        content.transform([cos, sin, -sin, cos, node.position[0], node.position[1]]);
+        match &node.geometry {
+            RadiantVectorGeometry::Group { children } => {
+                for child in children {
+                    self.write_node(content, child, images);
+                }
+            }
+            RadiantVectorGeometry::Rect { size } => {
+                content.rect(0.0, 0.0, size[0], size[1]);
+                self.paint(content, node, RadiantFillRule::NonZero);
+            }
+            RadiantVectorGeometry::Line { end } => {
+                content.move_to(0.0, 0.0);
+                content.line_to(end[0], end[1]);
+                self.paint(content, node, RadiantFillRule::NonZero);
+            }
+            RadiantVectorGeometry::Path {
+                commands,
+                fill_rule,
+            } => {
+                write_commands(content, commands);
+                self.paint(content, node, *fill_rule);
+            }
+            RadiantVectorGeometry::Text { runs } => {
+                self.write_text(content, runs);
+            }
+            RadiantVectorGeometry::Image { size, texture_id } => {
+                if let Some(pixmap) = (self.texture)(*texture_id) {
+                    let name = format!("Im{}", images.len());
+                    let id = self.write_image(&pixmap);
+                    // Images fill the unit square, bottom row first.
+                    content.transform([size[0], 0.0, 0.0, -size[1], 0.0, size[1]]);
+                    content.x_object(Name(name.as_bytes()));
+                    images.push((name, id));
+                }
+            }
+        }
+        content.restore_state();
+    }
+
+    fn paint(
+        &mut self,
+        content: &mut Content,
+        node: &RadiantVectorNode,
+        fill_rule: RadiantFillRule,
+    ) {
+        self.set_alpha(
+            content,
+            node.fill.map_or(255, |fill| fill.a()),
+            node.stroke.map_or(255, |stroke| stroke.color.a()),
+        );
+        if let Some(fill) = node.fill {
+            let [r, g, b] = rgb(fill);
+            content.set_fill_rgb(r, g, b);
+        }
+        if let Some(stroke) = node.stroke {
+            let [r, g, b] = rgb(stroke.color);
+            content.set_stroke_rgb(r, g, b);
+            content.set_line_width(stroke.width);
+        }
+        match (node.fill.is_some(), node.stroke.is_some(), fill_rule) {
+            (true, true, RadiantFillRule::NonZero) => content.fill_nonzero_and_stroke(),
+            (true, true, RadiantFillRule::EvenOdd) => content.fill_even_odd_and_stroke(),
+            (true, false, RadiantFillRule::NonZero) => content.fill_nonzero(),
+            (true, false, RadiantFillRule::EvenOdd) => content.fill_even_odd(),
+            (false, true, _) => content.stroke(),
+            (false, false, _) => content.end_path(),
+        };
+    }
+
+    /// Sets `runs` with their tops on each line aligned, like the SVG export does. Each
+    /// character is set in the first font of its run that has it, and characters none of
+    /// them has are left out. Italic runs are slanted, as the fonts have no italic faces.
+    fn write_text(&mut self, content: &mut Content, runs: &[RadiantVectorTextRun]) {
+        let mut top = 0.0;
+        for line in RadiantVectorTextRun::lines(runs) {
+            let mut x = 0.0;
+            for run in &line {
+                let names = run_fonts(self.font_definitions, run);
+                let Some(font) = names.first().and_then(|name| self.fonts.get(name.as_str()))
+                else {
+                    continue;
+                };
+                let size = run.font_size;
+                let ascender = font.face.ascender() as f32 / font.face.units_per_em() as f32;
+                let baseline = top + ascender * size;
+                let skew = if run.italic { ITALIC_SKEW } else { 0.0 };
+                self.set_alpha(content, run.color.a(), 255);
+                let [r, g, b] = rgb(run.color);
+                content.set_fill_rgb(r, g, b);
+
+                let start = x;
+                for (name, text) in self.split_by_font(names, &run.text) {
+                    let id = self.font_id(name);
+                    let Some(font) = self.fonts.get_mut(name) else {
+                        continue;
+                    };
+                    content.begin_text();
+                    content.set_font(Name(font_name(id).as_bytes()), size);
+                    // Flip the glyphs back upright on the flipped page.
+                    content.set_text_matrix([1.0, 0.0, skew, -1.0, x, baseline]);
+                    content.show(Str(&font.encode(&text)));
+                    content.end_text();
+                    x += font.width(&text, size);
+                }
+
+                let thickness = (size / 16.0).max(1.0);
+                let decorations = [
+                    (run.underline, baseline + size * 0.1),
+                    (run.strikethrough, baseline - size * 0.3),
+                ];
+                for (_, y) in decorations.into_iter().filter(|(enabled, _)| *enabled) {
+                    content.rect(start, y - thickness / 2.0, x - start, thickness);
+                    content.fill_nonzero();
+                }
+            }
+            top += RadiantVectorTextRun::line_height(&line);
+        }
+    }
+
+    /// `text` split into pieces set in one font each, the first of `names` with the glyph.
+    fn split_by_font(&self, names: &'a [String], text: &str) -> Vec<(&'a str, String)> {
+        let mut pieces: Vec<(&'a str, String)> = Vec::new();
+        for c in text.chars() {
+            let Some(name) = names.iter().find(|name| {
+                self.fonts
+                    .get(name.as_str())
+                    .is_some_and(|font| font.face.glyph_index(c).is_some())
+            }) else {
+                continue;
+            };
+            match pieces.last_mut() {
+                Some((last, piece)) if *last == name.as_str() => piece.push(c),
+                _ => pieces.push((name.as_str(), c.to_string())),
+            }
+        }
+        pieces
+    }
+
+    /// Object of the font `name`, allocated on first use.
+    fn font_id(&mut self, name: &str) -> Ref {
+        if let Some(id) = self.fonts.get(name).and_then(|font| font.id) {
+            return id;
+        }
+        let id = self.alloc();
+        if let Some(font) = self.fonts.get_mut(name) {
+            font.id = Some(id);
+        }
+        id
+    }
+
+    fn set_alpha(&mut self, content: &mut Content, fill: u8, stroke: u8) {
+        if fill == 255 && stroke == 255 {
+            return;
+        }
+        let index = match self
+            .graphics_states
+            .iter()
+            .position(|(alphas, _)| *alphas == [fill, stroke])
+        {
+            Some(index) => index,
+            None => {
+                let id = self.alloc();
+                self.pdf
+                    .ext_graphics(id)
+                    .non_stroking_alpha(fill as f32 / 255.0)
+                    .stroking_alpha(stroke as f32 / 255.0);
+                self.graphics_states.push(([fill, stroke], id));
+                self.graphics_states.len() - 1
+            }
+        };
+        content.set_parameters(Name(format!("Gs{index}").as_bytes()));
+    }
+
+    fn write_image(&mut self, pixmap: &RadiantPixmap) -> Ref {
+        let mut color = Vec::with_capacity(pixmap.pixels.len() * 3);
+        let mut alpha = Vec::with_capacity(pixmap.pixels.len());
+        for pixel in &pixmap.pixels {
+            let [r, g, b, a] = pixel.to_srgba_unmultiplied();
+            color.extend([r, g, b]);
+            alpha.push(a);
+        }
+        let [width, height] = [pixmap.size[0] as i32, pixmap.size[1] as i32];
+
+        let color_data = compress(&color);
+        let alpha_data = compress(&alpha);
+        let id = self.alloc();
+        let mask_id = self.alloc();
+        let mut image = self.pdf.image_xobject(id, &color_data);
+        image.filter(Filter::FlateDecode);
+        image.width(width);
+        image.height(height);
+        image.color_space().device_rgb();
+        image.bits_per_component(8);
+        image.s_mask(mask_id);
+        image.finish();
+
+        let mut mask = self.pdf.image_xobject(mask_id, &alpha_data);
+        mask.filter(Filter::FlateDecode);
+        mask.width(width);
+        mask.height(height);
+        mask.color_space().device_gray();
+        mask.bits_per_component(8);
+        id
+    }
+
+    fn write_fonts(&mut self) {
+        for (name, font) in std::mem::take(&mut self.fonts) {
+            if let Some(font_id) = font.id {
+                self.write_font(name, font, font_id);
+            }
+        }
+    }
+
+    fn write_font(&mut self, name: &str, font: PdfFont, font_id: Ref) {
+        let cid_font_id = self.alloc();
+        let descriptor_id = self.alloc();
+        let font_file_id = self.alloc();
+        let cmap_id = self.alloc();
+        // Base font names may only hold regular characters.
+        let base_font: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let base_font = Name(base_font.as_bytes());
+        let system_info = SystemInfo {
+            registry: Str(b"Adobe"),
+            ordering: Str(b"Identity"),
+            supplement: 0,
+        };
+        // PDF font metrics are in thousandths of the font size.
+        let scale = 1000.0 / font.face.units_per_em() as f32;
+
+        self.pdf
+            .type0_font(font_id)
+            .base_font(base_font)
+            .encoding_predefined(Name(b"Identity-H"))
+            .descendant_font(cid_font_id)
+            .to_unicode(cmap_id);
+
+        let mut cid_font = self.pdf.cid_font(cid_font_id);
+        cid_font
+            .subtype(CidFontType::Type2)
+            .base_font(base_font)
+            .system_info(system_info)
+            .font_descriptor(descriptor_id)
+            .default_width(0.0)
+            .cid_to_gid_map_predefined(Name(b"Identity"));
+        let mut widths = cid_font.widths();
+        for glyph in font.glyphs.keys() {
+            let advance = font
+                .face
+                .glyph_hor_advance(ttf_parser::GlyphId(*glyph))
+                .unwrap_or_default();
+            widths.consecutive(*glyph, [advance as f32 * scale]);
+        }
+        widths.finish();
+        cid_font.finish();
+
+        let bbox = font.face.global_bounding_box();
+        let ascent = font.face.ascender() as f32 * scale;
+        self.pdf
+            .font_descriptor(descriptor_id)
+            .name(base_font)
+            .flags(FontFlags::NON_SYMBOLIC)
+            .bbox(Rect::new(
+                bbox.x_min as f32 * scale,
+                bbox.y_min as f32 * scale,
+                bbox.x_max as f32 * scale,
+                bbox.y_max as f32 * scale,
+            ))
+            .italic_angle(0.0)
+            .ascent(ascent)
+            .descent(font.face.descender() as f32 * scale)
+            .cap_height(
+                font.face
+                    .capital_height()
+                    .map_or(ascent, |height| height as f32 * scale),
+            )
+            .stem_v(80.0)
+            .font_file2(font_file_id);
+
+        self.pdf
+            .stream(font_file_id, &compress(font.data))
+            .filter(Filter::FlateDecode)
+            .pair(Name(b"Length1"), font.data.len() as i32);
+
+        let mut cmap = UnicodeCmap::new(Name(b"Radiant-UTF16"), system_info);
+        for (glyph, c) in &font.glyphs {
+            cmap.pair(*glyph, *c);
+        }
+        self.pdf.cmap(cmap_id, &cmap.finish());
+    }
+}
+
+fn write_commands(content: &mut Content, commands: &[RadiantPathCommand]) {
+    let mut pen = [0.0, 0.0];
+    let mut start = pen;
+    for command in commands {
+        match *command {
+            RadiantPathCommand::MoveTo { to } => {
+                content.move_to(to[0], to[1]);
+                start = to;
+            }
+            RadiantPathCommand::LineTo { to } => {
+                content.line_to(to[0], to[1]);
+            }
+            RadiantPathCommand::QuadTo { control, to } => {
+                // PDF has no quadratic curves, so raise them to cubic ones.
+                let lerp = |from: [f32; 2]| {
+                    [
+                        from[0] + 2.0 / 3.0 * (control[0] - from[0]),
+                        from[1] + 2.0 / 3.0 * (control[1] - from[1]),
+                    ]
+                };
+                let [control1, control2] = [lerp(pen), lerp(to)];
+                content.cubic_to(
+                    control1[0],
+                    control1[1],
+                    control2[0],
+                    control2[1],
+                    to[0],
+                    to[1],
+                );
+            }
+            RadiantPathCommand::CubicTo {
+                control1,
+                control2,
+                to,
+            } => {
+                content.cubic_to(
+                    control1[0],
+                    control1[1],
+                    control2[0],
+                    control2[1],
+                    to[0],
+                    to[1],
+                );
+            }
+            RadiantPathCommand::Close => {
+                content.close_path();
+            }
+        }
+        pen = command.end_point().unwrap_or(start);
+    }
+}
+
+fn rgb(color: Color32) -> [f32; 3] {
+    let [r, g, b, _] = color.to_srgba_unmultiplied();
+    [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0]
+}
+
+fn compress(data: &[u8]) -> Vec<u8> {
+    miniz_oxide::deflate::compress_to_vec_zlib(data, COMPRESSION_LEVEL)
+}
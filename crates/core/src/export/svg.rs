@@ -0,0 +1,247 @@
+use crate::{
+    encode_pixmap, RadiantExportError, RadiantFillRule, RadiantImageFormat, RadiantPathCommand,
+    RadiantPixmap, RadiantVectorGeometry, RadiantVectorNode, RadiantVectorTextRun,
+};
+use base64::Engine;
+use epaint::{Color32, TextureId};
+use std::fmt::Write;
+
+const FONT_FAMILY: &str = "sans-serif";
+
+/// Writes `nodes` as a standalone SVG document sized to their bounds. `texture` provides the
+/// pixels of image nodes, which are embedded as PNG data.
+pub fn write_svg(
+    nodes: &[RadiantVectorNode],
+    texture: impl Fn(TextureId) -> Option<RadiantPixmap>,
+) -> Result<String, RadiantExportError> {
+    let bounds = nodes
+        .iter()
+        .map(|node| node.bounds)
+        .reduce(|a, b| {
+            [
+                a[0].min(b[0]),
+                a[1].min(b[1]),
+                a[2].max(b[2]),
+                a[3].max(b[3]),
+            ]
+        })
+        .ok_or(RadiantExportError::EmptyTarget)?;
+    let margin = nodes
+        .iter()
+        .map(|node| node.max_stroke_width())
+        .fold(0.0, f32::max)
+        / 2.0;
+    let [min_x, min_y] = [bounds[0] - margin, bounds[1] - margin];
+    let [width, height] = [
+        bounds[2] - bounds[0] + 2.0 * margin,
+        bounds[3] - bounds[1] + 2.0 * margin,
+    ];
+
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="{min_x} {min_y} {width} {height}">"#
+    );
+    for node in nodes {
+        write_node(&mut svg, node, &texture)?;
+    }
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+fn write_node(
+    svg: &mut String,
+    node: &RadiantVectorNode,
+    texture: &impl Fn(TextureId) -> Option<RadiantPixmap>,
+) -> Result<(), RadiantExportError> {
+    let attributes = attributes(node);
+    match &node.geometry {
+        RadiantVectorGeometry::Group { children } => {
+            let _ = write!(svg, "<g{attributes}>");
+            for child in children {
+                write_node(svg, child, texture)?;
+            }
+            svg.push_str("</g>");
+        }
+        RadiantVectorGeometry::Rect { size } => {
+            let _ = write!(
+                svg,
+                r#"<rect width="{}" height="{}"{attributes}/>"#,
+                size[0], size[1]
+            );
+        }
+        RadiantVectorGeometry::Line { end } => {
+            let _ = write!(
+                svg,
+                r#"<line x1="0" y1="0" x2="{}" y2="{}"{attributes}/>"#,
+                end[0], end[1]
+            );
+        }
+        RadiantVectorGeometry::Path {
+            commands,
+            fill_rule,
+        } => {
+            let fill_rule = match fill_rule {
+                RadiantFillRule::NonZero => "",
+                RadiantFillRule::EvenOdd => r#" fill-rule="evenodd""#,
+            };
+            let _ = write!(
+                svg,
+                r#"<path d="{}"{fill_rule}{attributes}/>"#,
+                path_data(commands)
+            );
+        }
+        RadiantVectorGeometry::Text { runs } => {
+            let _ = write!(
+                svg,
+                r#"<text font-family="{FONT_FAMILY}" dominant-baseline="text-before-edge" xml:space="preserve"{attributes}>"#
+            );
+            // Each line starts over at the left, below the tallest run of the previous one.
+            let mut top = 0.0;
+            for line in RadiantVectorTextRun::lines(runs) {
+                for (index, run) in line.iter().enumerate() {
+                    if index > 0 && run.text.is_empty() {
+                        continue;
+                    }
+                    let position = if index == 0 {
+                        format!(r#" x="0" y="{top}""#)
+                    } else {
+                        String::new()
+                    };
+                    let _ = write!(
+                        svg,
+                        "<tspan{position}{}>{}</tspan>",
+                        run_attributes(run),
+                        escape(&run.text)
+                    );
+                }
+                top += RadiantVectorTextRun::line_height(&line);
+            }
+            svg.push_str("</text>");
+        }
+        RadiantVectorGeometry::Image { size, texture_id } => {
+            // Images whose texture has not been uploaded yet have nothing to embed.
+            if let Some(pixmap) = texture(*texture_id) {
+                let png = encode_pixmap(&pixmap, RadiantImageFormat::Png)?;
+                let data = base64::engine::general_purpose::STANDARD.encode(png);
+                let _ = write!(
+                    svg,
+                    r#"<image width="{}" height="{}" preserveAspectRatio="none" href="data:image/png;base64,{data}"{attributes}/>"#,
+                    size[0], size[1]
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn attributes(node: &RadiantVectorNode) -> String {
+    let mut attributes = String::new();
+    let prefix = match node.geometry {
+        RadiantVectorGeometry::Group { .. } => "group",
+        _ => "node",
+    };
+    let _ = write!(attributes, r#" id="{prefix}-{}""#, node.id);
+
+    let [x, y] = node.position;
+    if x != 0.0 || y != 0.0 || node.rotation != 0.0 {
+        let _ = write!(attributes, r#" transform="translate({x} {y})"#);
+        if node.rotation != 0.0 {
+            let _ = write!(attributes, " rotate({})", node.rotation.to_degrees());
+        }
+        attributes.push('"');
+    }
+
+    if !matches!(node.geometry, RadiantVectorGeometry::Group { .. }) {
+        match node.fill {
+            Some(color) => write_color(&mut attributes, "fill", color),
+            None => attributes.push_str(r#" fill="none""#),
+        }
+    }
+    if let Some(stroke) = node.stroke {
+        write_color(&mut attributes, "stroke", stroke.color);
+        let _ = write!(attributes, r#" stroke-width="{}""#, stroke.width);
+    }
+    attributes
+}
+
+fn run_attributes(run: &RadiantVectorTextRun) -> String {
+    let mut attributes = String::new();
+    if let Some(family) = &run.family {
+        let family = escape(family);
+        let _ = write!(attributes, r#" font-family="'{family}', {FONT_FAMILY}""#);
+    }
+    let _ = write!(attributes, r#" font-size="{}""#, run.font_size);
+    write_color(&mut attributes, "fill", run.color);
+    if run.bold {
+        attributes.push_str(r#" font-weight="bold""#);
+    }
+    if run.italic {
+        attributes.push_str(r#" font-style="italic""#);
+    }
+    let decorations = [
+        (run.underline, "underline"),
+        (run.strikethrough, "line-through"),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, decoration)| enabled.then_some(decoration))
+    .collect::<Vec<_>>();
+    if !decorations.is_empty() {
+        let _ = write!(
+            attributes,
+            r#" text-decoration="{}""#,
+            decorations.join(" ")
+        );
+    }
+    attributes
+}
+
+fn write_color(attributes: &mut String, name: &str, color: Color32) {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    let _ = write!(attributes, r##" {name}="#{r:02x}{g:02x}{b:02x}""##);
+    if a < 255 {
+        let _ = write!(attributes, r#" {name}-opacity="{}""#, a as f32 / 255.0);
+    }
+}
+
+fn path_data(commands: &[RadiantPathCommand]) -> String {
+    let mut data = String::new();
+    for command in commands {
+        if !data.is_empty() {
+            data.push(' ');
+        }
+        let _ = match command {
+            RadiantPathCommand::MoveTo { to } => write!(data, "M {} {}", to[0], to[1]),
+            RadiantPathCommand::LineTo { to } => write!(data, "L {} {}", to[0], to[1]),
+            RadiantPathCommand::QuadTo { control, to } => {
+                write!(data, "Q {} {} {} {}", control[0], control[1], to[0], to[1])
+            }
+            RadiantPathCommand::CubicTo {
+                control1,
+                control2,
+                to,
+            } => write!(
+                data,
+                "C {} {} {} {} {} {}",
+                control1[0], control1[1], control2[0], control2[1], to[0], to[1]
+            ),
+            RadiantPathCommand::Close => write!(data, "Z"),
+        };
+    }
+    data
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
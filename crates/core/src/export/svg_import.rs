@@ -0,0 +1,276 @@
+use crate::{
+    commands_bounds, RadiantFillRule, RadiantPathCommand, RadiantVectorGeometry, RadiantVectorNode,
+    RadiantVectorTextRun,
+};
+use epaint::{Color32, Stroke};
+use macro_magic::export_tokens;
+use serde::{Deserialize, Serialize};
+use usvg::{tiny_skia_path::PathSegment, NodeKind, TreeParsing};
+use uuid::Uuid;
+
+/// Share of the font size between the top of a line and its baseline, used to place
+/// imported text by its top left corner.
+const ASCENT: f32 = 0.8;
+
+#[export_tokens]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RadiantImportMessage {
+    ImportSvg { svg: String, position: [f32; 2] },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum RadiantImportError {
+    Parse { reason: String },
+}
+
+impl std::fmt::Display for RadiantImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse { reason } => write!(f, "could not parse the SVG: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for RadiantImportError {}
+
+/// Nodes read from an SVG document, in document coordinates, and the features of the
+/// document that could not be represented.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RadiantSvgImport {
+    pub nodes: Vec<RadiantVectorNode>,
+    pub unsupported: Vec<String>,
+}
+
+impl RadiantSvgImport {
+    pub fn report(&mut self, feature: &str) {
+        if !self.unsupported.iter().any(|reported| reported == feature) {
+            self.unsupported.push(feature.to_string());
+        }
+    }
+}
+
+/// Reads an SVG document. Transforms are applied to the geometry, so every node is positioned
+/// in document coordinates and groups only carry their children. Axis aligned rectangles are
+/// read as rectangles, every other shape as a path.
+pub fn read_svg(svg: &str) -> Result<RadiantSvgImport, RadiantImportError> {
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).map_err(|error| {
+        RadiantImportError::Parse {
+            reason: error.to_string(),
+        }
+    })?;
+    let mut import = RadiantSvgImport::default();
+    import.nodes = read_children(&mut import, &tree.root, usvg::Transform::default());
+    Ok(import)
+}
+
+fn read_children(
+    import: &mut RadiantSvgImport,
+    node: &usvg::Node,
+    transform: usvg::Transform,
+) -> Vec<RadiantVectorNode> {
+    node.children()
+        .filter_map(|child| read_node(import, &child, transform))
+        .collect()
+}
+
+fn read_node(
+    import: &mut RadiantSvgImport,
+    node: &usvg::Node,
+    transform: usvg::Transform,
+) -> Option<RadiantVectorNode> {
+    match &*node.borrow() {
+        NodeKind::Group(group) => {
+            if group.opacity.get() < 1.0 {
+                import.report("group opacity");
+            }
+            if group.clip_path.is_some() {
+                import.report("clip path");
+            }
+            if group.mask.is_some() {
+                import.report("mask");
+            }
+            if !group.filters.is_empty() {
+                import.report("filter");
+            }
+            let children = read_children(import, node, transform.pre_concat(group.transform));
+            (!children.is_empty()).then(|| RadiantVectorNode::group(Uuid::new_v4(), children))
+        }
+        NodeKind::Path(path) => read_path(import, path, transform.pre_concat(path.transform)),
+        NodeKind::Text(text) => read_text(import, text, transform.pre_concat(text.transform)),
+        NodeKind::Image(_) => {
+            import.report("image");
+            None
+        }
+    }
+}
+
+fn read_path(
+    import: &mut RadiantSvgImport,
+    path: &usvg::Path,
+    transform: usvg::Transform,
+) -> Option<RadiantVectorNode> {
+    if path.visibility != usvg::Visibility::Visible {
+        return None;
+    }
+    let fill = path
+        .fill
+        .as_ref()
+        .and_then(|fill| read_paint(import, &fill.paint, fill.opacity.get()));
+    let fill_rule = match path.fill.as_ref().map(|fill| fill.rule) {
+        Some(usvg::FillRule::EvenOdd) => RadiantFillRule::EvenOdd,
+        _ => RadiantFillRule::NonZero,
+    };
+    let stroke = path.stroke.as_ref().and_then(|stroke| {
+        if stroke.dasharray.is_some() {
+            import.report("dashed stroke");
+        }
+        let color = read_paint(import, &stroke.paint, stroke.opacity.get())?;
+        // Strokes scale with the average scale of the transform.
+        let scale = (transform.sx * transform.sy - transform.kx * transform.ky)
+            .abs()
+            .sqrt();
+        Some(Stroke::new(stroke.width.get() * scale, color))
+    });
+
+    let point = |point: usvg::tiny_skia_path::Point| {
+        let mut point = point;
+        transform.map_point(&mut point);
+        [point.x, point.y]
+    };
+    let commands: Vec<RadiantPathCommand> = path
+        .data
+        .segments()
+        .map(|segment| match segment {
+            PathSegment::MoveTo(to) => RadiantPathCommand::MoveTo { to: point(to) },
+            PathSegment::LineTo(to) => RadiantPathCommand::LineTo { to: point(to) },
+            PathSegment::QuadTo(control, to) => RadiantPathCommand::QuadTo {
+                control: point(control),
+                to: point(to),
+            },
+            PathSegment::CubicTo(control1, control2, to) => RadiantPathCommand::CubicTo {
+                control1: point(control1),
+                control2: point(control2),
+                to: point(to),
+            },
+            PathSegment::Close => RadiantPathCommand::Close,
+        })
+        .collect();
+
+    let bounds = commands_bounds(&commands)?;
+    let geometry = if rect_commands(&commands) {
+        RadiantVectorGeometry::Rect {
+            size: [bounds[2] - bounds[0], bounds[3] - bounds[1]],
+        }
+    } else {
+        let commands = commands
+            .into_iter()
+            .map(|command| command.map(|[x, y]| [x - bounds[0], y - bounds[1]]))
+            .collect();
+        RadiantVectorGeometry::Path {
+            commands,
+            fill_rule,
+        }
+    };
+    Some(RadiantVectorNode {
+        id: Uuid::new_v4(),
+        position: [bounds[0], bounds[1]],
+        rotation: 0.0,
+        bounds,
+        fill,
+        stroke,
+        geometry,
+    })
+}
+
+fn read_text(
+    import: &mut RadiantSvgImport,
+    text: &usvg::Text,
+    transform: usvg::Transform,
+) -> Option<RadiantVectorNode> {
+    if transform.has_scale() || transform.has_skew() {
+        import.report("transformed text");
+    }
+    let chunk = text.chunks.first()?;
+    let span = chunk.spans.first()?;
+    if text.chunks.iter().any(|chunk| chunk.spans.len() > 1) {
+        import.report("styled text spans");
+    }
+    if chunk.anchor != usvg::TextAnchor::Start {
+        import.report("text anchor");
+    }
+    let font_size = span.font_size.get();
+    let fill = span
+        .fill
+        .as_ref()
+        .and_then(|fill| read_paint(import, &fill.paint, fill.opacity.get()));
+
+    let mut origin = usvg::tiny_skia_path::Point::from_xy(
+        chunk.x.unwrap_or_default(),
+        chunk.y.unwrap_or_default() - font_size * ASCENT,
+    );
+    transform.map_point(&mut origin);
+    let text = text
+        .chunks
+        .iter()
+        .map(|chunk| chunk.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let lines = text.lines().count().max(1) as f32;
+    let run = RadiantVectorTextRun {
+        text,
+        family: None,
+        font_size,
+        color: fill.unwrap_or(Color32::BLACK),
+        bold: false,
+        italic: false,
+        underline: false,
+        strikethrough: false,
+    };
+    Some(RadiantVectorNode {
+        id: Uuid::new_v4(),
+        position: [origin.x, origin.y],
+        rotation: 0.0,
+        bounds: [origin.x, origin.y, origin.x, origin.y + font_size * lines],
+        fill,
+        stroke: None,
+        geometry: RadiantVectorGeometry::Text { runs: vec![run] },
+    })
+}
+
+fn read_paint(import: &mut RadiantSvgImport, paint: &usvg::Paint, opacity: f32) -> Option<Color32> {
+    match paint {
+        usvg::Paint::Color(color) => Some(Color32::from_rgba_unmultiplied(
+            color.red,
+            color.green,
+            color.blue,
+            (opacity * 255.0).round() as u8,
+        )),
+        usvg::Paint::LinearGradient(_) | usvg::Paint::RadialGradient(_) => {
+            import.report("gradient");
+            None
+        }
+        usvg::Paint::Pattern(_) => {
+            import.report("pattern");
+            None
+        }
+    }
+}
+
+/// Whether `commands` outline a single axis aligned rectangle.
+fn rect_commands(commands: &[RadiantPathCommand]) -> bool {
+    use RadiantPathCommand::{Close, LineTo, MoveTo};
+
+    let [a, b, c, d] = match commands {
+        [MoveTo { to: a }, LineTo { to: b }, LineTo { to: c }, LineTo { to: d }, Close] => {
+            [a, b, c, d]
+        }
+        [MoveTo { to: a }, LineTo { to: b }, LineTo { to: c }, LineTo { to: d }, LineTo { to: e }, Close]
+            if e == a =>
+        {
+            [a, b, c, d]
+        }
+        _ => return false,
+    };
+    (a[1] == b[1] && b[0] == c[0] && c[1] == d[1] && d[0] == a[0])
+        || (a[0] == b[0] && b[1] == c[1] && c[0] == d[0] && d[1] == a[1])
+}
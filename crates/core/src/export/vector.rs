@@ -0,0 +1,176 @@
+use crate::{BaseNode, RadiantFillRule, RadiantPathCommand};
+use epaint::{Color32, Stroke, TextureId};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Distance between the lines of vector text, relative to the font size.
+pub const TEXT_LINE_HEIGHT: f32 = 1.2;
+
+/// Format-independent vector description of a node, shared by the vector exporters.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RadiantVectorNode {
+    pub id: Uuid,
+    /// Offset from the parent's origin. The geometry is relative to it.
+    pub position: [f32; 2],
+    /// Rotation around `position`, in radians.
+    pub rotation: f32,
+    /// Bounds in document coordinates, without the stroke.
+    pub bounds: [f32; 4],
+    pub fill: Option<Color32>,
+    pub stroke: Option<Stroke>,
+    pub geometry: RadiantVectorGeometry,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum RadiantVectorGeometry {
+    Group {
+        children: Vec<RadiantVectorNode>,
+    },
+    Rect {
+        size: [f32; 2],
+    },
+    Line {
+        end: [f32; 2],
+    },
+    Path {
+        commands: Vec<RadiantPathCommand>,
+        fill_rule: RadiantFillRule,
+    },
+    /// Text laid out from its top left corner, one line per `\n`.
+    Text {
+        runs: Vec<RadiantVectorTextRun>,
+    },
+    Image {
+        size: [f32; 2],
+        texture_id: TextureId,
+    },
+}
+
+/// Characters of vector text sharing one style.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RadiantVectorTextRun {
+    pub text: String,
+    /// Named font family, or `None` for the default one.
+    pub family: Option<String>,
+    pub font_size: f32,
+    pub color: Color32,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strikethrough: bool,
+}
+
+impl RadiantVectorTextRun {
+    /// `runs` split at their line breaks, which are dropped. Every line keeps at least one
+    /// run, possibly empty, so that its height is known.
+    pub fn lines(runs: &[RadiantVectorTextRun]) -> Vec<Vec<RadiantVectorTextRun>> {
+        let mut lines = vec![Vec::new()];
+        for run in runs {
+            for (index, text) in run.text.split('\n').enumerate() {
+                if index > 0 {
+                    lines.push(Vec::new());
+                }
+                if let Some(line) = lines.last_mut() {
+                    line.push(RadiantVectorTextRun {
+                        text: text.to_string(),
+                        ..run.clone()
+                    });
+                }
+            }
+        }
+        lines
+    }
+
+    /// Height of a line of `runs`, from the largest of them.
+    pub fn line_height(runs: &[RadiantVectorTextRun]) -> f32 {
+        runs.iter().map(|run| run.font_size).fold(0.0, f32::max) * TEXT_LINE_HEIGHT
+    }
+}
+
+impl RadiantVectorNode {
+    /// A node placed at the top left of `base`'s bounds, painted with its colors and outlined
+    /// `stroke_width` wide. Nodes that draw no outline pass a zero width.
+    pub fn new(base: &BaseNode, geometry: RadiantVectorGeometry, stroke_width: f32) -> Self {
+        let bounds = base.bounding_rect;
+        let fill = base.color.fill_color();
+        let stroke = base.color.stroke_color();
+        Self {
+            id: base.id,
+            position: [bounds[0], bounds[1]],
+            rotation: base.transform.get_rotation(),
+            bounds,
+            fill: (fill != Color32::TRANSPARENT).then_some(fill),
+            stroke: (stroke != Color32::TRANSPARENT && stroke_width > 0.0)
+                .then_some(Stroke::new(stroke_width, stroke)),
+            geometry,
+        }
+    }
+
+    /// The filled outline of the node in document coordinates, for rectangles and paths.
+    /// Unfilled nodes, like freehand strokes, cover no area and have none.
+    pub fn outline(&self) -> Option<(Vec<RadiantPathCommand>, RadiantFillRule)> {
+        self.fill?;
+        let (commands, fill_rule) = match &self.geometry {
+            RadiantVectorGeometry::Rect { size } => (
+                vec![
+                    RadiantPathCommand::MoveTo { to: [0.0, 0.0] },
+                    RadiantPathCommand::LineTo { to: [size[0], 0.0] },
+                    RadiantPathCommand::LineTo { to: *size },
+                    RadiantPathCommand::LineTo { to: [0.0, size[1]] },
+                    RadiantPathCommand::Close,
+                ],
+                RadiantFillRule::NonZero,
+            ),
+            RadiantVectorGeometry::Path {
+                commands,
+                fill_rule,
+            } => (commands.clone(), *fill_rule),
+            _ => return None,
+        };
+        let (sin, cos) = self.rotation.sin_cos();
+        let [x, y] = self.position;
+        let commands = commands
+            .into_iter()
+            .map(|command| {
+                command.map(|[px, py]| [x + px * cos - py * sin, y + px * sin + py * cos])
+            })
+            .collect();
+        Some((commands, fill_rule))
+    }
+
+    pub fn group(id: Uuid, children: Vec<RadiantVectorNode>) -> Self {
+        let bounds = children
+            .iter()
+            .map(|child| child.bounds)
+            .reduce(|a, b| {
+                [
+                    a[0].min(b[0]),
+                    a[1].min(b[1]),
+                    a[2].max(b[2]),
+                    a[3].max(b[3]),
+                ]
+            })
+            .unwrap_or_default();
+        Self {
+            id,
+            position: [0.0, 0.0],
+            rotation: 0.0,
+            bounds,
+            fill: None,
+            stroke: None,
+            geometry: RadiantVectorGeometry::Group { children },
+        }
+    }
+
+    /// Widest stroke in this node and its children.
+    pub fn max_stroke_width(&self) -> f32 {
+        let own = self.stroke.map_or(0.0, |stroke| stroke.width);
+        match &self.geometry {
+            RadiantVectorGeometry::Group { children } => children
+                .iter()
+                .map(|child| child.max_stroke_width())
+                .fold(own, f32::max),
+            _ => own,
+        }
+    }
+}
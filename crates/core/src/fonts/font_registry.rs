@@ -0,0 +1,170 @@
+use epaint::{
+    text::{FontData, FontDefinitions},
+    FontFamily,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Lightest weight counted as bold, that of semibold faces.
+const BOLD_WEIGHT: u16 = 600;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RadiantFontError {
+    /// The bytes are not a TrueType or OpenType font.
+    InvalidFont {
+        name: String,
+        reason: String,
+    },
+    UnknownFamily {
+        family: String,
+    },
+}
+
+impl std::fmt::Display for RadiantFontError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidFont { name, reason } => write!(f, "invalid font {name}: {reason}"),
+            Self::UnknownFamily { family } => write!(f, "unknown font family {family}"),
+        }
+    }
+}
+
+impl std::error::Error for RadiantFontError {}
+
+/// Fonts loaded at runtime, grouped into named families on top of epaint's built-in ones.
+#[derive(Debug, Clone, Default)]
+pub struct RadiantFontRegistry {
+    fonts: BTreeMap<String, FontData>,
+    /// Fonts of each family, in the order they were loaded.
+    families: BTreeMap<String, Vec<String>>,
+    /// Families whose fonts are tried, in order, for glyphs a family lacks.
+    fallbacks: BTreeMap<String, Vec<String>>,
+    /// Fonts whose faces are bold.
+    bold: BTreeSet<String>,
+}
+
+/// The family bold text in `family` is laid out in. It exists once a bold face is loaded into
+/// `family`, and prefers the bold faces over the others.
+pub fn bold_family(family: &str) -> FontFamily {
+    FontFamily::Name(format!("{family} (Bold)").into())
+}
+
+impl RadiantFontRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the TrueType or OpenType font in `data` to `family` as `name`. A font already
+    /// loaded as `name` is replaced. Bold faces are told apart by their weight.
+    pub fn load(
+        &mut self,
+        family: &str,
+        name: &str,
+        data: Vec<u8>,
+    ) -> Result<(), RadiantFontError> {
+        let face =
+            ttf_parser::Face::parse(&data, 0).map_err(|error| RadiantFontError::InvalidFont {
+                name: name.to_string(),
+                reason: error.to_string(),
+            })?;
+        if face.is_bold() || face.weight().to_number() >= BOLD_WEIGHT {
+            self.bold.insert(name.to_string());
+        } else {
+            self.bold.remove(name);
+        }
+        self.fonts
+            .insert(name.to_string(), FontData::from_owned(data));
+        for names in self.families.values_mut() {
+            names.retain(|font| font != name);
+        }
+        self.families
+            .entry(family.to_string())
+            .or_default()
+            .push(name.to_string());
+        Ok(())
+    }
+
+    /// Sets the families tried, in order, for glyphs missing from `family`. The built-in
+    /// proportional fonts always come last.
+    pub fn set_fallbacks(
+        &mut self,
+        family: &str,
+        fallbacks: Vec<String>,
+    ) -> Result<(), RadiantFontError> {
+        if !self.families.contains_key(family) {
+            return Err(RadiantFontError::UnknownFamily {
+                family: family.to_string(),
+            });
+        }
+        self.fallbacks.insert(family.to_string(), fallbacks);
+        Ok(())
+    }
+
+    pub fn contains_family(&self, family: &str) -> bool {
+        self.families.contains_key(family)
+    }
+
+    pub fn families(&self) -> impl Iterator<Item = &str> {
+        self.families.keys().map(String::as_str)
+    }
+
+    /// epaint's default fonts, with every family bound to its fonts followed by those of its
+    /// fallbacks. Regular faces come first, and families with bold faces get a
+    /// [`bold_family`] that puts those first.
+    pub fn font_definitions(&self) -> FontDefinitions {
+        let mut definitions = FontDefinitions::default();
+        let defaults = definitions
+            .families
+            .get(&FontFamily::Proportional)
+            .cloned()
+            .unwrap_or_default();
+        for (name, data) in &self.fonts {
+            definitions.font_data.insert(name.clone(), data.clone());
+        }
+        for (family, fonts) in &self.families {
+            let has_bold = fonts.iter().any(|name| self.bold.contains(name));
+            for bold in [false, true] {
+                if bold && !has_bold {
+                    continue;
+                }
+                let mut names = Vec::new();
+                self.collect_fonts(family, bold, &mut BTreeSet::new(), &mut names);
+                for name in &defaults {
+                    if !names.contains(name) {
+                        names.push(name.clone());
+                    }
+                }
+                let family = if bold {
+                    bold_family(family)
+                } else {
+                    FontFamily::Name(family.as_str().into())
+                };
+                definitions.families.insert(family, names);
+            }
+        }
+        definitions
+    }
+
+    /// Fonts of `family` and its fallbacks, each family's faces of the weight asked for
+    /// ahead of its others.
+    fn collect_fonts<'a>(
+        &'a self,
+        family: &'a str,
+        bold: bool,
+        visited: &mut BTreeSet<&'a str>,
+        names: &mut Vec<String>,
+    ) {
+        if !visited.insert(family) {
+            return;
+        }
+        let mut fonts: Vec<&String> = self.families.get(family).into_iter().flatten().collect();
+        fonts.sort_by_key(|name| self.bold.contains(*name) != bold);
+        for name in fonts {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+        for fallback in self.fallbacks.get(family).into_iter().flatten() {
+            self.collect_fonts(fallback, bold, visited, names);
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod font_registry;
+
+pub use font_registry::*;
@@ -1,6 +1,7 @@
 use crate::{
-    RadiantInteraction, RadiantLineNode, RadiantNode, RadiantRectangleNode, RadiantSceneMessage,
-    RadiantTessellatable, ScreenDescriptor, TransformComponent,
+    RadiantCursor, RadiantInteraction, RadiantLineNode, RadiantNode, RadiantPickingIds,
+    RadiantRectangleNode, RadiantSceneMessage, RadiantTessellatable, ScreenDescriptor,
+    TransformComponent,
 };
 use epaint::ClippedPrimitive;
 use once_cell::sync::Lazy;
@@ -113,6 +114,60 @@ impl BoundingBoxInteraction {
         }
     }
 
+    pub fn assign_pick_ids(&mut self, picking_ids: &mut RadiantPickingIds) {
+        for node in &mut self.nodes {
+            let pick_id = picking_ids.insert(node.get_id());
+            node.base_mut().set_pick_id(pick_id);
+        }
+        for node in &mut self.corner_nodes {
+            let pick_id = picking_ids.insert(node.get_id());
+            node.base_mut().set_pick_id(pick_id);
+        }
+    }
+
+    /// Corners are drawn above the edges, so they win when both are under the cursor.
+    pub fn hit_test(&self, position: [f32; 2], tolerance: f32) -> Option<Uuid> {
+        self.active_node_id?;
+        self.corner_nodes
+            .iter()
+            .rev()
+            .find(|node| node.hit_test(position, tolerance))
+            .map(|node| node.get_id())
+            .or_else(|| {
+                self.nodes
+                    .iter()
+                    .rev()
+                    .find(|node| node.hit_test(position, tolerance))
+                    .map(|node| node.get_id())
+            })
+    }
+
+    /// The handle drawn last among `ids`, if any of them belongs to this interaction.
+    pub fn topmost(&self, ids: &[Uuid]) -> Option<Uuid> {
+        self.active_node_id?;
+        self.corner_nodes
+            .iter()
+            .rev()
+            .map(|node| node.get_id())
+            .chain(self.nodes.iter().rev().map(|node| node.get_id()))
+            .find(|id| ids.contains(id))
+    }
+
+    /// Resize cursor matching the edge or corner `id` drags.
+    pub fn cursor(&self, id: Uuid) -> Option<RadiantCursor> {
+        match id {
+            _id if id == *BOUNDING_BOX_TOP_ID => Some(RadiantCursor::ResizeNorth),
+            _id if id == *BOUNDING_BOX_RIGHT_ID => Some(RadiantCursor::ResizeEast),
+            _id if id == *BOUNDING_BOX_BOTTOM_ID => Some(RadiantCursor::ResizeSouth),
+            _id if id == *BOUNDING_BOX_LEFT_ID => Some(RadiantCursor::ResizeWest),
+            _id if id == *BOUNDING_BOX_TOP_RIGHT_ID => Some(RadiantCursor::ResizeNorthEast),
+            _id if id == *BOUNDING_BOX_BOTTOM_RIGHT_ID => Some(RadiantCursor::ResizeSouthEast),
+            _id if id == *BOUNDING_BOX_BOTTOM_LEFT_ID => Some(RadiantCursor::ResizeSouthWest),
+            _id if id == *BOUNDING_BOX_TOP_LEFT_ID => Some(RadiantCursor::ResizeNorthWest),
+            _ => None,
+        }
+    }
+
     pub fn disable(&mut self) {
         self.active_node_id = None;
     }
@@ -0,0 +1,110 @@
+use crate::{
+    RadiantInteraction, RadiantLineNode, RadiantNode, RadiantTessellatable, ScreenDescriptor,
+};
+use epaint::ClippedPrimitive;
+use once_cell::sync::Lazy;
+use uuid::Uuid;
+
+static HOVER_TOP_ID: Lazy<Uuid> = Lazy::new(Uuid::new_v4);
+static HOVER_RIGHT_ID: Lazy<Uuid> = Lazy::new(Uuid::new_v4);
+static HOVER_BOTTOM_ID: Lazy<Uuid> = Lazy::new(Uuid::new_v4);
+static HOVER_LEFT_ID: Lazy<Uuid> = Lazy::new(Uuid::new_v4);
+
+/// Outline drawn around the node under the cursor. It has no pick ids, so it never shows up
+/// in the picking pass.
+#[derive(Debug, Clone)]
+pub struct HoverInteraction {
+    pub hovered_node_id: Option<Uuid>,
+    pub nodes: Vec<RadiantLineNode>,
+    pub primitives: Vec<ClippedPrimitive>,
+}
+
+impl HoverInteraction {
+    pub fn new() -> Self {
+        let nodes = vec![
+            RadiantLineNode::new(*HOVER_TOP_ID, [0.0, 0.0], [0.0, 0.0]),
+            RadiantLineNode::new(*HOVER_RIGHT_ID, [0.0, 0.0], [0.0, 0.0]),
+            RadiantLineNode::new(*HOVER_BOTTOM_ID, [0.0, 0.0], [0.0, 0.0]),
+            RadiantLineNode::new(*HOVER_LEFT_ID, [0.0, 0.0], [0.0, 0.0]),
+        ];
+
+        Self {
+            hovered_node_id: None,
+            nodes,
+            primitives: Vec::new(),
+        }
+    }
+}
+
+impl Default for HoverInteraction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HoverInteraction {
+    pub fn enable(&mut self, node: &impl RadiantNode) {
+        let rect = node.get_bounding_rect();
+
+        self.nodes[0].start = [rect[0], rect[1]].into();
+        self.nodes[0].end = [rect[2], rect[1]].into();
+
+        self.nodes[1].start = [rect[2], rect[1]].into();
+        self.nodes[1].end = [rect[2], rect[3]].into();
+
+        self.nodes[2].start = [rect[2], rect[3]].into();
+        self.nodes[2].end = [rect[0], rect[3]].into();
+
+        self.nodes[3].start = [rect[0], rect[3]].into();
+        self.nodes[3].end = [rect[0], rect[1]].into();
+
+        for node in &mut self.nodes {
+            node.set_needs_tessellation(false);
+        }
+
+        self.hovered_node_id = Some(node.get_id());
+    }
+
+    pub fn disable(&mut self) {
+        self.hovered_node_id = None;
+    }
+
+    /// Follows the hovered node when it moves or resizes.
+    pub fn update(&mut self, node: &impl RadiantNode) {
+        if self.hovered_node_id == Some(node.get_id()) {
+            self.enable(node);
+        }
+    }
+}
+
+impl HoverInteraction {
+    pub fn tessellate(
+        &mut self,
+        selection: bool,
+        screen_descriptor: &ScreenDescriptor,
+        fonts_manager: &epaint::text::Fonts,
+    ) -> Vec<ClippedPrimitive> {
+        if selection || self.hovered_node_id.is_none() {
+            return Vec::new();
+        }
+
+        self.primitives = self
+            .nodes
+            .iter_mut()
+            .fold(Vec::new(), |mut primitives, node| {
+                primitives.append(&mut node.tessellate(false, screen_descriptor, fonts_manager));
+                primitives
+            });
+        self.primitives.clone()
+    }
+}
+
+impl RadiantInteraction for HoverInteraction {
+    fn get_primitives(&self, selection: bool) -> Vec<ClippedPrimitive> {
+        if selection {
+            Vec::new()
+        } else {
+            self.primitives.clone()
+        }
+    }
+}
@@ -1,10 +1,15 @@
-use crate::{BoundingBoxInteraction, RadiantNode, RadiantSceneMessage, ScreenDescriptor};
+use crate::{
+    BoundingBoxInteraction, HoverInteraction, KeyCode, PathEditInteraction, RadiantCursor,
+    RadiantNode, RadiantPathEdit, RadiantPickingIds, RadiantSceneMessage, ScreenDescriptor,
+};
 use epaint::ClippedPrimitive;
 use parking_lot::RwLockWriteGuard;
 use uuid::Uuid;
 
 pub struct RadiantInteractionManager<M> {
     pub bounding_box_interaction: BoundingBoxInteraction,
+    pub hover_interaction: HoverInteraction,
+    pub path_edit_interaction: PathEditInteraction,
     _phantom: std::marker::PhantomData<M>,
 }
 
@@ -12,12 +17,39 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>> RadiantInterac
     pub fn new() -> Self {
         Self {
             bounding_box_interaction: BoundingBoxInteraction::new(),
+            hover_interaction: HoverInteraction::new(),
+            path_edit_interaction: PathEditInteraction::new(),
             _phantom: std::marker::PhantomData,
         }
     }
 
     pub fn is_interaction(&self, id: Uuid) -> bool {
-        self.bounding_box_interaction.contains(id)
+        self.bounding_box_interaction.contains(id) || self.path_edit_interaction.contains(id)
+    }
+
+    /// Interaction handles take part in GPU picking like regular nodes.
+    pub fn assign_pick_ids(&mut self, picking_ids: &mut RadiantPickingIds) {
+        self.bounding_box_interaction.assign_pick_ids(picking_ids);
+        self.path_edit_interaction.assign_pick_ids(picking_ids);
+    }
+
+    pub fn hit_test(&self, position: [f32; 2], tolerance: f32) -> Option<Uuid> {
+        self.path_edit_interaction
+            .hit_test(position, tolerance)
+            .or_else(|| self.bounding_box_interaction.hit_test(position, tolerance))
+    }
+
+    pub fn topmost(&self, ids: &[Uuid]) -> Option<Uuid> {
+        self.path_edit_interaction
+            .topmost(ids)
+            .or_else(|| self.bounding_box_interaction.topmost(ids))
+    }
+
+    pub fn cursor(&self, id: Uuid) -> Option<RadiantCursor> {
+        if self.path_edit_interaction.contains(id) {
+            return Some(RadiantCursor::Move);
+        }
+        self.bounding_box_interaction.cursor(id)
     }
 
     pub fn enable_interactions(
@@ -25,12 +57,67 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>> RadiantInterac
         node: RwLockWriteGuard<impl RadiantNode>,
         screen_descriptor: &ScreenDescriptor,
     ) {
+        // Selecting the path being edited keeps it in edit mode.
+        if self.path_edit_interaction.active_node_id == Some(node.get_id()) {
+            self.path_edit_interaction.update(&*node);
+            return;
+        }
+        self.path_edit_interaction.disable();
         self.bounding_box_interaction
             .enable(node, screen_descriptor);
     }
 
     pub fn disable_interactions(&mut self) {
         self.bounding_box_interaction.disable();
+        self.path_edit_interaction.disable();
+    }
+
+    /// Swaps the bounding box of `node` for handles on its anchors. Returns false for nodes
+    /// without an editable outline.
+    pub fn enable_path_edit(&mut self, node: &impl RadiantNode) -> bool {
+        if !self.path_edit_interaction.enable(node) {
+            return false;
+        }
+        self.bounding_box_interaction.disable();
+        true
+    }
+
+    pub fn path_edit_node_id(&self) -> Option<Uuid> {
+        self.path_edit_interaction.active_node_id
+    }
+
+    /// Notes the interaction handle `id` was clicked.
+    pub fn select_interaction(&mut self, id: Uuid) {
+        self.path_edit_interaction.select(id);
+    }
+
+    /// Delete removes the selected anchor of the edited path, and Escape stops editing.
+    pub fn handle_key(&mut self, key: &KeyCode) -> Option<M> {
+        let id = self.path_edit_interaction.active_node_id?;
+        match key {
+            KeyCode::Delete | KeyCode::Backspace => {
+                let index = self.path_edit_interaction.selected_anchor.take()?;
+                let edit = RadiantPathEdit::DeletePoint { index };
+                Some(RadiantSceneMessage::EditPath { id, edit }.into())
+            }
+            KeyCode::Escape => {
+                self.path_edit_interaction.disable();
+                Some(RadiantSceneMessage::SelectNode { id: Some(id) }.into())
+            }
+            _ => None,
+        }
+    }
+
+    pub fn hovered_node_id(&self) -> Option<Uuid> {
+        self.hover_interaction.hovered_node_id
+    }
+
+    pub fn enable_hover(&mut self, node: &impl RadiantNode) {
+        self.hover_interaction.enable(node);
+    }
+
+    pub fn disable_hover(&mut self) {
+        self.hover_interaction.disable();
     }
 
     pub fn update_interactions(
@@ -38,8 +125,13 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>> RadiantInterac
         node: RwLockWriteGuard<impl RadiantNode>,
         screen_descriptor: &ScreenDescriptor,
     ) {
-        self.bounding_box_interaction
-            .update(node, screen_descriptor);
+        self.hover_interaction.update(&*node);
+        if self.path_edit_interaction.active_node_id == Some(node.get_id()) {
+            self.path_edit_interaction.update(&*node);
+        } else {
+            self.bounding_box_interaction
+                .update(node, screen_descriptor);
+        }
     }
 
     pub fn handle_interaction(&mut self, message: M) -> Option<M> {
@@ -47,7 +139,11 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>> RadiantInterac
             Ok(RadiantSceneMessage::TransformNode { id, position, .. })
                 if self.is_interaction(id) =>
             {
-                if let Some(m) = self.bounding_box_interaction.handle(id, position) {
+                if let Some(m) = self
+                    .path_edit_interaction
+                    .handle(id, position)
+                    .or_else(|| self.bounding_box_interaction.handle(id, position))
+                {
                     Some(m.into())
                 } else {
                     None
@@ -63,7 +159,27 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>> RadiantInterac
         screen_descriptor: &ScreenDescriptor,
         fonts_manager: &epaint::text::Fonts,
     ) -> Vec<ClippedPrimitive> {
-        self.bounding_box_interaction
-            .tessellate(selection, screen_descriptor, fonts_manager)
+        // The selected node already has its bounding box or path handles, so it gets no hover
+        // outline.
+        let hovered = self.hover_interaction.hovered_node_id;
+        let mut primitives = if hovered != self.bounding_box_interaction.active_node_id
+            && hovered != self.path_edit_interaction.active_node_id
+        {
+            self.hover_interaction
+                .tessellate(selection, screen_descriptor, fonts_manager)
+        } else {
+            Vec::new()
+        };
+        primitives.append(&mut self.bounding_box_interaction.tessellate(
+            selection,
+            screen_descriptor,
+            fonts_manager,
+        ));
+        primitives.append(&mut self.path_edit_interaction.tessellate(
+            selection,
+            screen_descriptor,
+            fonts_manager,
+        ));
+        primitives
     }
 }
@@ -1,8 +1,12 @@
 pub mod bounding_box;
+pub mod hover;
 pub mod interaction_manager;
+pub mod path_edit;
 
 pub use bounding_box::*;
+pub use hover::*;
 pub use interaction_manager::*;
+pub use path_edit::*;
 
 use epaint::ClippedPrimitive;
 
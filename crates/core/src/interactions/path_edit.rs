@@ -0,0 +1,288 @@
+use crate::{
+    path_anchors, path_handle, RadiantInteraction, RadiantLineNode, RadiantNode,
+    RadiantPathCommand, RadiantPathEdit, RadiantPathHandle, RadiantPickingIds,
+    RadiantRectangleNode, RadiantSceneMessage, RadiantTessellatable, ScreenDescriptor,
+};
+use epaint::ClippedPrimitive;
+use uuid::Uuid;
+
+/// Side of the squares drawn on anchors and handles, in document units. The view does not
+/// zoom, so these are points on screen, like the corners of the bounding box.
+const ANCHOR_SIZE: f32 = 8.0;
+const HANDLE_SIZE: f32 = 6.0;
+
+/// What a path edit handle drags.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PathEditTarget {
+    Anchor(usize),
+    Handle(usize, RadiantPathHandle),
+}
+
+/// Shows the anchors and Bezier handles of the path being edited, and turns drags on them
+/// into [`RadiantSceneMessage::EditPath`]. Anchors and handles take part in GPU picking, and
+/// the lines to the handles do not.
+#[derive(Debug, Clone)]
+pub struct PathEditInteraction {
+    pub active_node_id: Option<Uuid>,
+    /// Command index of the anchor last clicked, which Delete removes.
+    pub selected_anchor: Option<usize>,
+    commands: Vec<RadiantPathCommand>,
+    /// Ids handed out per anchor slot, so a handle keeps its id while it is dragged.
+    ids: Vec<Uuid>,
+    targets: Vec<(Uuid, PathEditTarget)>,
+    nodes: Vec<RadiantRectangleNode>,
+    lines: Vec<RadiantLineNode>,
+    primitives: Vec<ClippedPrimitive>,
+    selection_primitives: Vec<ClippedPrimitive>,
+}
+
+impl PathEditInteraction {
+    pub fn new() -> Self {
+        Self {
+            active_node_id: None,
+            selected_anchor: None,
+            commands: Vec::new(),
+            ids: Vec::new(),
+            targets: Vec::new(),
+            nodes: Vec::new(),
+            lines: Vec::new(),
+            primitives: Vec::new(),
+            selection_primitives: Vec::new(),
+        }
+    }
+}
+
+impl Default for PathEditInteraction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PathEditInteraction {
+    pub fn contains(&self, id: Uuid) -> bool {
+        self.active_node_id.is_some() && self.target(id).is_some()
+    }
+
+    fn target(&self, id: Uuid) -> Option<PathEditTarget> {
+        self.targets
+            .iter()
+            .find(|(target_id, _)| *target_id == id)
+            .map(|(_, target)| *target)
+    }
+
+    /// Starts editing `node`. Returns false for nodes without an editable outline.
+    pub fn enable(&mut self, node: &impl RadiantNode) -> bool {
+        let Some(commands) = node.path_commands() else {
+            return false;
+        };
+        if self.active_node_id != Some(node.get_id()) {
+            self.selected_anchor = None;
+        }
+        self.active_node_id = Some(node.get_id());
+        self.commands = commands;
+        self.rebuild();
+        true
+    }
+
+    pub fn disable(&mut self) {
+        self.active_node_id = None;
+        self.selected_anchor = None;
+        self.targets.clear();
+        self.nodes.clear();
+        self.lines.clear();
+    }
+
+    /// Follows the edited node when its outline changes.
+    pub fn update(&mut self, node: &impl RadiantNode) {
+        if self.active_node_id == Some(node.get_id()) {
+            self.enable(node);
+        }
+    }
+
+    /// Remembers the anchor behind `id`, if it is one.
+    pub fn select(&mut self, id: Uuid) {
+        if let Some(PathEditTarget::Anchor(index)) = self.target(id) {
+            self.selected_anchor = Some(index);
+            self.rebuild();
+        }
+    }
+
+    fn slot_id(&mut self, slot: usize) -> Uuid {
+        while self.ids.len() <= slot {
+            self.ids.push(Uuid::new_v4());
+        }
+        self.ids[slot]
+    }
+
+    fn rebuild(&mut self) {
+        let anchors = path_anchors(&self.commands);
+        if self
+            .selected_anchor
+            .is_some_and(|index| !anchors.contains(&index))
+        {
+            self.selected_anchor = None;
+        }
+        self.targets.clear();
+        self.nodes.clear();
+        self.lines.clear();
+
+        for (slot, index) in anchors.into_iter().enumerate() {
+            let Some(point) = self.commands[index].end_point() else {
+                continue;
+            };
+            for (k, handle) in [RadiantPathHandle::In, RadiantPathHandle::Out]
+                .into_iter()
+                .enumerate()
+            {
+                let Some(position) = path_handle(&self.commands, index, handle) else {
+                    continue;
+                };
+                let id = self.slot_id(slot * 3 + 1 + k);
+                self.lines
+                    .push(RadiantLineNode::new(Uuid::new_v4(), point, position));
+                self.nodes
+                    .push(square(id, position, HANDLE_SIZE, epaint::Color32::BLUE));
+                self.targets
+                    .push((id, PathEditTarget::Handle(index, handle)));
+            }
+
+            let id = self.slot_id(slot * 3);
+            let color = if self.selected_anchor == Some(index) {
+                epaint::Color32::BLUE
+            } else {
+                epaint::Color32::WHITE
+            };
+            self.nodes.push(square(id, point, ANCHOR_SIZE, color));
+            self.targets.push((id, PathEditTarget::Anchor(index)));
+        }
+
+        for node in &mut self.lines {
+            node.set_needs_tessellation(false);
+        }
+        for node in &mut self.nodes {
+            node.set_needs_tessellation(false);
+        }
+    }
+
+    /// Gives the anchors and handles pick ids. They are rebuilt whenever the path changes, so
+    /// this runs before every picking pass. Ids are kept per anchor slot, and so are their pick
+    /// ids.
+    pub fn assign_pick_ids(&mut self, picking_ids: &mut RadiantPickingIds) {
+        for node in &mut self.nodes {
+            let pick_id = picking_ids.insert(node.get_id());
+            node.base_mut().set_pick_id(pick_id);
+        }
+    }
+
+    /// Anchors win over handles when both are under the cursor.
+    pub fn hit_test(&self, position: [f32; 2], tolerance: f32) -> Option<Uuid> {
+        self.active_node_id?;
+        let hits = |anchor: bool| {
+            self.nodes
+                .iter()
+                .rev()
+                .filter(move |node| self.anchor_index(node.get_id()).is_some() == anchor)
+                .find(|node| node.hit_test(position, tolerance))
+                .map(|node| node.get_id())
+        };
+        hits(true).or_else(|| hits(false))
+    }
+
+    /// The anchor or handle among `ids` that [`Self::hit_test`] would pick.
+    pub fn topmost(&self, ids: &[Uuid]) -> Option<Uuid> {
+        self.active_node_id?;
+        let topmost = |anchor: bool| {
+            self.nodes
+                .iter()
+                .rev()
+                .map(|node| node.get_id())
+                .filter(move |id| self.anchor_index(*id).is_some() == anchor)
+                .find(|id| ids.contains(id))
+        };
+        topmost(true).or_else(|| topmost(false))
+    }
+
+    /// Command index of the anchor `id` drags, if it drags one.
+    pub fn anchor_index(&self, id: Uuid) -> Option<usize> {
+        match self.target(id)? {
+            PathEditTarget::Anchor(index) => Some(index),
+            PathEditTarget::Handle(..) => None,
+        }
+    }
+
+    pub fn handle(&mut self, id: Uuid, offset: [f32; 2]) -> Option<RadiantSceneMessage> {
+        let node_id = self.active_node_id?;
+        let edit = match self.target(id)? {
+            PathEditTarget::Anchor(index) => RadiantPathEdit::MovePoint { index, offset },
+            PathEditTarget::Handle(index, handle) => RadiantPathEdit::MoveHandle {
+                index,
+                handle,
+                offset,
+            },
+        };
+        Some(RadiantSceneMessage::EditPath { id: node_id, edit })
+    }
+}
+
+fn square(id: Uuid, center: [f32; 2], size: f32, color: epaint::Color32) -> RadiantRectangleNode {
+    let mut node = RadiantRectangleNode::new(
+        id,
+        [center[0] - size / 2.0, center[1] - size / 2.0],
+        [size, size],
+    );
+    node.color_mut().set_fill_color(color);
+    node
+}
+
+impl PathEditInteraction {
+    pub fn tessellate(
+        &mut self,
+        selection: bool,
+        screen_descriptor: &ScreenDescriptor,
+        fonts_manager: &epaint::text::Fonts,
+    ) -> Vec<ClippedPrimitive> {
+        if self.active_node_id.is_none() {
+            return Vec::new();
+        }
+        if selection {
+            self.selection_primitives =
+                self.nodes
+                    .iter_mut()
+                    .fold(Vec::new(), |mut primitives, node| {
+                        primitives.append(&mut node.tessellate(
+                            true,
+                            screen_descriptor,
+                            fonts_manager,
+                        ));
+                        primitives
+                    });
+            return self.selection_primitives.clone();
+        }
+
+        let primitives = self
+            .lines
+            .iter_mut()
+            .fold(Vec::new(), |mut primitives, node| {
+                primitives.append(&mut node.tessellate(false, screen_descriptor, fonts_manager));
+                primitives
+            });
+        self.primitives = self
+            .nodes
+            .iter_mut()
+            .fold(primitives, |mut primitives, node| {
+                primitives.append(&mut node.tessellate(false, screen_descriptor, fonts_manager));
+                primitives
+            });
+        self.primitives.clone()
+    }
+}
+
+impl RadiantInteraction for PathEditInteraction {
+    fn get_primitives(&self, selection: bool) -> Vec<ClippedPrimitive> {
+        if selection {
+            self.selection_primitives.clone()
+        } else {
+            self.primitives.clone()
+        }
+    }
+}
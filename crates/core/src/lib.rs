@@ -1,27 +1,29 @@
 pub mod components;
 pub mod document;
+pub mod export;
+pub mod fonts;
 pub mod interactions;
 pub mod message;
 pub mod nodes;
 pub mod render;
 pub mod scene;
+pub mod spatial_index;
 pub mod texture;
 pub mod tools;
 pub mod utils;
 
-use std::{
-    collections::HashMap,
-    sync::{RwLockReadGuard, RwLockWriteGuard},
-};
+use std::sync::{RwLockReadGuard, RwLockWriteGuard};
 
 pub use components::*;
 pub use document::*;
-use epaint::Color32;
+pub use export::*;
+pub use fonts::*;
 pub use interactions::*;
 pub use message::*;
 pub use nodes::*;
 pub use render::*;
 pub use scene::*;
+pub use spatial_index::*;
 pub use texture::*;
 pub use tools::*;
 pub use utils::*;
@@ -44,6 +46,12 @@ impl ScreenDescriptor {
             self.size_in_pixels[1] as f32 / self.pixels_per_point,
         ]
     }
+
+    /// Visible area in points (`[min_x, min_y, max_x, max_y]`), grown by `margin` on each side.
+    pub fn viewport(&self, margin: f32) -> [f32; 4] {
+        let [width, height] = self.screen_size_in_points();
+        [-margin, -margin, width + margin, height + margin]
+    }
 }
 
 pub trait View<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode> {
@@ -64,6 +72,10 @@ pub trait Runtime<
     fn view_mut(&mut self) -> &mut Self::View;
 
     fn handle_message(&mut self, message: M) -> Option<R>;
+    /// Responses to messages that completed after they were handled, e.g. images that loaded.
+    fn take_responses(&mut self) -> Vec<R> {
+        Vec::new()
+    }
 
     fn scene(&'a self) -> RwLockReadGuard<RadiantScene<M, N>> {
         self.view().scene()
@@ -76,39 +88,3 @@ pub trait Runtime<
         self.scene_mut().add(node);
     }
 }
-
-use once_cell::sync::Lazy;
-use std::sync::Mutex;
-use uuid::Uuid;
-
-static COUNTER: Lazy<Mutex<u64>> = Lazy::new(|| Mutex::new(0));
-static NODE_TO_COLORS: Lazy<Mutex<HashMap<Uuid, Color32>>> =
-    Lazy::new(|| Mutex::new(HashMap::new()));
-static COLOR_TO_NODES: Lazy<Mutex<HashMap<Color32, Uuid>>> =
-    Lazy::new(|| Mutex::new(HashMap::new()));
-
-pub fn get_color_for_node(node_id: Uuid) -> Color32 {
-    let mut colors = NODE_TO_COLORS.lock().unwrap();
-    if let Some(color) = colors.get(&node_id) {
-        return *color;
-    }
-    let mut counter = COUNTER.lock().unwrap();
-    *counter += 1;
-    let c = *counter;
-    let color = Color32::from_rgb(
-        (c >> 0) as u8 & 0xFF,
-        (c >> 8) as u8 & 0xFF,
-        (c >> 16) as u8 & 0xFF,
-    );
-    colors.insert(node_id, color);
-
-    let mut color_to_nodes = COLOR_TO_NODES.lock().unwrap();
-    color_to_nodes.insert(color, node_id);
-
-    color
-}
-
-pub fn get_node_for_color(color: Color32) -> Option<Uuid> {
-    let color_to_nodes = COLOR_TO_NODES.lock().unwrap();
-    color_to_nodes.get(&color).copied()
-}
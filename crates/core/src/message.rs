@@ -2,7 +2,10 @@ use macro_magic::export_tokens;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::{KeyCode, RadiantNode};
+use crate::{
+    KeyCode, RadiantExportTarget, RadiantImageExportOptions, RadiantImeEvent, RadiantKeyModifiers,
+    RadiantNode, RadiantPathEdit,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RadiantSceneMessage {
@@ -13,6 +16,25 @@ pub enum RadiantSceneMessage {
     SelectNode {
         id: Option<Uuid>,
     },
+    /// A press from the selection tool. The selected node may take it, see
+    /// [`RadiantNode::handle_press`]; otherwise `id` is selected.
+    PressNode {
+        id: Option<Uuid>,
+        position: [f32; 2],
+    },
+    RemoveNode {
+        id: Uuid,
+    },
+    HoverNode {
+        position: [f32; 2],
+    },
+    ExportImage {
+        options: RadiantImageExportOptions,
+    },
+    ExportSvg {
+        target: RadiantExportTarget,
+    },
+    ExportPdf {},
     TransformNode {
         id: Uuid,
         position: [f32; 2],
@@ -37,6 +59,34 @@ pub enum RadiantSceneMessage {
     HandleKey {
         id: Option<Uuid>,
         key: KeyCode,
+        #[serde(default)]
+        modifiers: RadiantKeyModifiers,
+    },
+    /// Input method composition for `id`, or the selected node.
+    HandleIme {
+        id: Option<Uuid>,
+        event: RadiantImeEvent,
+    },
+    /// Enters path edit mode on a path, inserts a point on the path being edited, or toggles
+    /// an anchor between corner and smooth.
+    DoubleClickNode {
+        id: Option<Uuid>,
+        position: [f32; 2],
+    },
+    EditPath {
+        id: Uuid,
+        edit: RadiantPathEdit,
+    },
+    /// Adds the TrueType or OpenType font in `data` to `family` under `name`.
+    LoadFont {
+        family: String,
+        name: String,
+        data: Vec<u8>,
+    },
+    /// Families tried, in order, for glyphs missing from `family`.
+    SetFontFallbacks {
+        family: String,
+        fallbacks: Vec<String>,
     },
 }
 
@@ -54,4 +104,29 @@ pub enum RadiantSceneResponse<M, N: RadiantNode> {
         position: [f32; 2],
         scale: [f32; 2],
     },
+    Removed {
+        id: uuid::Uuid,
+    },
+    Hovered {
+        id: Option<uuid::Uuid>,
+    },
+    ImageExported {
+        data: Vec<u8>,
+    },
+    SvgExported {
+        svg: String,
+    },
+    PdfExported {
+        data: Vec<u8>,
+    },
+    ExportFailed {
+        reason: String,
+    },
+    FontLoaded {
+        family: String,
+        name: String,
+    },
+    FontFailed {
+        reason: String,
+    },
 }
@@ -1,11 +1,14 @@
 use std::{any::{Any, TypeId}, sync::Arc, fmt::Debug};
 
 use crate::{ColorComponent, SelectionComponent, TransformComponent, Vec3, Observer};
+use crate::RadiantTextDelta;
 use epaint::ClippedPrimitive;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 pub type SubscriptionCallback = dyn Fn(&str)->() + 'static;
+pub type BoundsCallback = dyn Fn(Uuid, [f32; 4]) + Send + Sync + 'static;
+pub type TextCallback = dyn Fn(Uuid, &[RadiantTextDelta]) + 'static;
 
 #[derive(Serialize, Deserialize, Default)]
 pub struct BaseNode {
@@ -19,10 +22,22 @@ pub struct BaseNode {
     pub selection_primitives: Vec<ClippedPrimitive>,
     #[serde(skip)]
     pub needs_tessellation: bool,
+    /// Bumped whenever the node's primitives are invalidated, so that renderers holding on to
+    /// an older mesh know to upload it again.
+    #[serde(skip)]
+    pub revision: u64,
     #[serde(skip)]
     pub bounding_rect: [f32; 4],
+    /// Id written into the picking target, assigned by the document. 0 until then.
+    #[serde(skip)]
+    pub pick_id: u32,
     #[serde(skip)]
     pub observers: Observer<Arc<SubscriptionCallback>>,
+    #[serde(skip)]
+    pub bounds_observer: Option<Arc<BoundsCallback>>,
+    /// Told about each edit to the characters of a text node.
+    #[serde(skip)]
+    pub text_observer: Option<Arc<TextCallback>>,
 }
 
 impl Clone for BaseNode {
@@ -35,8 +50,12 @@ impl Clone for BaseNode {
             primitives: Vec::new(),
             selection_primitives: Vec::new(),
             needs_tessellation: true,
+            revision: self.revision + 1,
             bounding_rect: [0.0, 0.0, 0.0, 0.0],
+            pick_id: self.pick_id,
             observers: Observer::default(),
+            bounds_observer: None,
+            text_observer: None,
         }
     }
 }
@@ -51,7 +70,9 @@ impl Debug for BaseNode {
             .field("primitives", &self.primitives)
             .field("selection_primitives", &self.selection_primitives)
             .field("needs_tessellation", &self.needs_tessellation)
+            .field("revision", &self.revision)
             .field("bounding_rect", &self.bounding_rect)
+            .field("pick_id", &self.pick_id)
             .finish()
     }
 }
@@ -73,13 +94,50 @@ impl BaseNode {
             primitives: Vec::new(),
             selection_primitives: Vec::new(),
             needs_tessellation: true,
+            revision: 0,
             bounding_rect: [0.0, 0.0, 0.0, 0.0],
+            pick_id: 0,
             observers: Observer::default(),
+            bounds_observer: None,
+            text_observer: None,
         }
     }
 
     pub fn set_needs_tessellation(&mut self) {
+        self.invalidate();
+        self.notify_bounds();
+    }
+
+    pub fn set_pick_id(&mut self, pick_id: u32) {
+        if self.pick_id != pick_id {
+            self.pick_id = pick_id;
+            self.invalidate();
+        }
+    }
+
+    /// Marks the primitives as stale without touching the bounds.
+    pub fn invalidate(&mut self) {
         self.needs_tessellation = true;
+        self.revision += 1;
+    }
+
+    pub fn set_bounding_rect(&mut self, bounding_rect: [f32; 4]) {
+        if self.bounding_rect != bounding_rect {
+            self.bounding_rect = bounding_rect;
+            self.notify_bounds();
+        }
+    }
+
+    fn notify_bounds(&self) {
+        if let Some(observer) = &self.bounds_observer {
+            observer(self.id, self.bounding_rect);
+        }
+    }
+
+    pub fn notify_text(&self, delta: &[RadiantTextDelta]) {
+        if let Some(observer) = &self.text_observer {
+            observer(self.id, delta);
+        }
     }
 
     pub fn notify(&self, message: String) {
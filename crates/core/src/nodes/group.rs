@@ -1,10 +1,20 @@
-use crate::{BaseNode, RadiantNode, RadiantTessellatable, ScreenDescriptor};
+use crate::{
+    rects_intersect, BaseNode, RadiantDrawList, RadiantFrameStats, RadiantNode,
+    RadiantTessellatable, RadiantVectorNode, ScreenDescriptor,
+};
 use epaint::ClippedPrimitive;
 use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
 use uuid::Uuid;
 
+/// Nodes this close to the edge of the screen (in points) are still tessellated, so that they
+/// are ready when they scroll into view.
+pub const VIEWPORT_CULLING_MARGIN: f32 = 64.0;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RadiantGroupNode<N: RadiantNode> {
     pub base: BaseNode,
@@ -36,9 +46,94 @@ impl<N: RadiantNode> RadiantGroupNode<N> {
         self.nodes.get_mut(&id).map(|n| n.write())
     }
 
-    pub fn replace_node(&mut self, id: Uuid, node: N) {
+    pub fn replace_node(&mut self, id: Uuid, mut node: N) {
+        if let Some(previous) = self.nodes.get(&id) {
+            node.base_mut().revision = previous.read().base().revision + 1;
+        }
         self.nodes.insert(id, Arc::new(RwLock::new(node)));
     }
+
+    pub fn remove(&mut self, id: Uuid) -> Option<Arc<RwLock<N>>> {
+        self.nodes.remove(&id)
+    }
+
+    /// Ids of the nodes under `position`, topmost (last drawn) first.
+    pub fn hit_stack(&self, position: [f32; 2], tolerance: f32) -> Vec<Uuid> {
+        self.nodes
+            .iter()
+            .rev()
+            .filter(|(_, node)| node.read().hit_test(position, tolerance))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Ids of the nodes whose bounds intersect `viewport`, checking each node. Nodes which
+    /// have not computed their bounds yet are included.
+    pub fn visible_nodes(&self, viewport: [f32; 4]) -> BTreeSet<Uuid> {
+        self.nodes
+            .iter()
+            .filter(|(_, node)| {
+                let bounding_rect = node.read().get_bounding_rect();
+                bounding_rect == [0.0, 0.0, 0.0, 0.0] || rects_intersect(bounding_rect, viewport)
+            })
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Tessellates the nodes in `visible` and skips the rest.
+    pub fn tessellate_visible(
+        &mut self,
+        selection: bool,
+        screen_descriptor: &ScreenDescriptor,
+        fonts_manager: &epaint::text::Fonts,
+        visible: &BTreeSet<Uuid>,
+        stats: &mut RadiantFrameStats,
+    ) -> Vec<ClippedPrimitive> {
+        let mut primitives = Vec::new();
+        self.for_each_visible(visible, stats, |node| {
+            primitives.append(&mut node.tessellate(selection, screen_descriptor, fonts_manager));
+        });
+        primitives
+    }
+
+    /// Retained counterpart of [`Self::tessellate_visible`]: nodes whose current revision is
+    /// already held by the renderer are neither tessellated nor cloned.
+    pub fn collect_visible(
+        &mut self,
+        selection: bool,
+        screen_descriptor: &ScreenDescriptor,
+        fonts_manager: &epaint::text::Fonts,
+        visible: &BTreeSet<Uuid>,
+        stats: &mut RadiantFrameStats,
+        draw_list: &mut RadiantDrawList,
+    ) {
+        self.for_each_visible(visible, stats, |node| {
+            let id = node.get_id();
+            let revision = node.base().revision;
+            let primitives = draw_list
+                .needs_upload(id, revision)
+                .then(|| node.tessellate(selection, screen_descriptor, fonts_manager));
+            draw_list.push(id, revision, primitives);
+        });
+    }
+
+    /// Calls `f` on the nodes in `visible`, in drawing order.
+    fn for_each_visible<F: FnMut(&mut N)>(
+        &mut self,
+        visible: &BTreeSet<Uuid>,
+        stats: &mut RadiantFrameStats,
+        mut f: F,
+    ) {
+        let mut visible_nodes = 0;
+        for id in visible {
+            if let Some(node) = self.nodes.get(id) {
+                visible_nodes += 1;
+                f(&mut node.write());
+            }
+        }
+        stats.visible_nodes += visible_nodes;
+        stats.culled_nodes += self.nodes.len() - visible_nodes;
+    }
 }
 
 impl<N: RadiantNode> RadiantTessellatable for RadiantGroupNode<N> {
@@ -62,11 +157,20 @@ impl<N: RadiantNode> RadiantTessellatable for RadiantGroupNode<N> {
         screen_descriptor: &ScreenDescriptor,
         fonts_manager: &epaint::text::Fonts,
     ) -> Vec<ClippedPrimitive> {
-        let mut primitives = Vec::new();
-        for node in &mut self.nodes.values_mut() {
-            primitives.append(&mut node.write().tessellate(selection, screen_descriptor, fonts_manager));
+        // Groups inside an artboard are picked as a whole.
+        if self.base.pick_id != 0 {
+            for node in self.nodes.values() {
+                node.write().base_mut().set_pick_id(self.base.pick_id);
+            }
         }
-        primitives
+        let visible = self.visible_nodes(screen_descriptor.viewport(VIEWPORT_CULLING_MARGIN));
+        self.tessellate_visible(
+            selection,
+            screen_descriptor,
+            fonts_manager,
+            &visible,
+            &mut RadiantFrameStats::default(),
+        )
     }
 }
 
@@ -79,12 +183,54 @@ impl<N: RadiantNode> RadiantNode for RadiantGroupNode<N> {
         &mut self.base
     }
 
-    fn handle_key_down(&mut self, key: crate::KeyCode) -> bool {
+    /// Union of the bounds of the nodes in the group.
+    fn get_bounding_rect(&self) -> [f32; 4] {
+        self.nodes
+            .values()
+            .map(|node| node.read().get_bounding_rect())
+            .reduce(|a, b| {
+                [
+                    a[0].min(b[0]),
+                    a[1].min(b[1]),
+                    a[2].max(b[2]),
+                    a[3].max(b[3]),
+                ]
+            })
+            .unwrap_or_default()
+    }
+
+    fn hit_test(&self, position: [f32; 2], tolerance: f32) -> bool {
+        self.nodes
+            .values()
+            .any(|node| node.read().hit_test(position, tolerance))
+    }
+
+    fn handle_key_down(
+        &mut self,
+        key: crate::KeyCode,
+        modifiers: crate::RadiantKeyModifiers,
+    ) -> bool {
         for node in &mut self.nodes.values_mut() {
-            if node.write().handle_key_down(key.clone()) {
+            if node.write().handle_key_down(key.clone(), modifiers) {
                 return true;
             }
         }
         false
     }
+
+    fn to_vector(&self) -> Option<RadiantVectorNode> {
+        let children = self
+            .nodes
+            .values()
+            .filter_map(|node| node.read().to_vector())
+            .collect::<Vec<_>>();
+        (!children.is_empty()).then(|| RadiantVectorNode::group(self.base.id, children))
+    }
+
+    fn font_families(&self) -> Vec<String> {
+        self.nodes
+            .values()
+            .flat_map(|node| node.read().font_families())
+            .collect()
+    }
 }
@@ -1,11 +1,14 @@
 use crate::{
-    get_color_for_node, BaseNode, RadiantNode, RadiantTessellatable, ScreenDescriptor, Vec3,
+    distance_to_segment, pick_color, picking_tessellation_options, BaseNode, RadiantNode,
+    RadiantTessellatable, RadiantVectorGeometry, RadiantVectorNode, ScreenDescriptor, Vec3,
 };
 use epaint::{ClippedPrimitive, ClippedShape, Rect, TessellationOptions};
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use uuid::Uuid;
 
+const SELECTION_STROKE_WIDTH: f32 = 8.0;
+
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct RadiantLineNode {
     pub base: BaseNode,
@@ -49,17 +52,17 @@ impl RadiantLineNode {
             shapes,
         );
 
-        let color = get_color_for_node(self.base.id);
+        let color = pick_color(self.base.pick_id);
         let shapes = vec![ClippedShape(
             Rect::EVERYTHING,
             epaint::Shape::LineSegment {
                 points,
-                stroke: epaint::Stroke::new(8.0, color),
+                stroke: epaint::Stroke::new(SELECTION_STROKE_WIDTH, color),
             },
         )];
         self.base.selection_primitives = epaint::tessellator::tessellate_shapes(
             pixels_per_point,
-            TessellationOptions::default(),
+            picking_tessellation_options(),
             [1, 1],
             vec![],
             shapes,
@@ -78,6 +81,12 @@ impl RadiantTessellatable for RadiantLineNode {
     }
 
     fn set_needs_tessellation(&mut self, notify: bool) {
+        self.base.bounding_rect = [
+            self.start.x.min(self.end.x),
+            self.start.y.min(self.end.y),
+            self.start.x.max(self.end.x),
+            self.start.y.max(self.end.y),
+        ];
         self.base.set_needs_tessellation();
         if notify {
             self.base.notify(serde_json::to_string(self).unwrap());
@@ -107,4 +116,18 @@ impl RadiantNode for RadiantLineNode {
     fn base_mut(&mut self) -> &mut BaseNode {
         &mut self.base
     }
+
+    fn hit_test(&self, position: [f32; 2], tolerance: f32) -> bool {
+        distance_to_segment(position, self.start.into(), self.end.into())
+            <= SELECTION_STROKE_WIDTH / 2.0 + tolerance
+    }
+
+    fn to_vector(&self) -> Option<RadiantVectorNode> {
+        let end = [self.end.x - self.start.x, self.end.y - self.start.y];
+        let mut node = RadiantVectorNode::new(&self.base, RadiantVectorGeometry::Line { end }, 1.0);
+        node.position = [self.start.x, self.start.y];
+        node.fill = None;
+        node.stroke = Some(epaint::Stroke::new(1.0, epaint::Color32::BLUE));
+        Some(node)
+    }
 }
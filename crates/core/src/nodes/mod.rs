@@ -2,6 +2,7 @@ pub mod base_node;
 pub mod group;
 pub mod line;
 pub mod rectangle;
+pub mod text_delta;
 
 use std::sync::Arc;
 
@@ -9,11 +10,12 @@ pub use base_node::*;
 pub use group::*;
 pub use line::*;
 pub use rectangle::*;
+pub use text_delta::*;
 
 use serde::Serialize;
 use uuid::Uuid;
 
-use crate::{ColorComponent, ScreenDescriptor, TransformComponent, Subscription, SubscriptionId};
+use crate::{ColorComponent, RadiantCursor, RadiantPathCommand, RadiantVectorNode, ScreenDescriptor, TransformComponent, Subscription, SubscriptionId};
 use epaint::ClippedPrimitive;
 
 pub trait RadiantTessellatable {
@@ -44,6 +46,10 @@ pub trait RadiantNode: 'static + Serialize + Clone + RadiantTessellatable {
         self.base().bounding_rect
     }
 
+    fn hit_test(&self, position: [f32; 2], tolerance: f32) -> bool {
+        crate::rect_contains(self.get_bounding_rect(), position, tolerance)
+    }
+
     fn transform(&self) -> &TransformComponent {
         &self.base().transform
     }
@@ -58,7 +64,74 @@ pub trait RadiantNode: 'static + Serialize + Clone + RadiantTessellatable {
         &mut self.base_mut().color
     }
 
-    fn handle_key_down(&mut self, _key: crate::KeyCode) -> bool {
+    fn handle_key_down(
+        &mut self,
+        _key: crate::KeyCode,
+        _modifiers: crate::RadiantKeyModifiers,
+    ) -> bool {
+        false
+    }
+
+    fn handle_ime(&mut self, _event: crate::RadiantImeEvent) -> bool {
+        false
+    }
+
+    fn cursor(&self) -> RadiantCursor {
+        RadiantCursor::Move
+    }
+
+    /// Takes a press at `position` on the node while it is edited, e.g. to place a text
+    /// caret. `extend` is set while the press is dragged. Returns whether it was taken.
+    fn handle_press(&mut self, _position: [f32; 2], _extend: bool) -> bool {
+        false
+    }
+
+    /// Starts editing the node's content at `position`, on a double click. Returns whether
+    /// the node has content to edit.
+    fn begin_editing(&mut self, _position: [f32; 2]) -> bool {
+        false
+    }
+
+    /// Stops editing started with [`Self::begin_editing`], when the node is deselected.
+    fn end_editing(&mut self) {}
+
+    /// Caret of the node while it is edited, as `[x, y, width, height]`, next to which input
+    /// methods show their candidates.
+    fn ime_cursor_area(&self) -> Option<[f32; 4]> {
+        None
+    }
+
+    /// Vector description used by the SVG and PDF exporters. Nodes without one are skipped.
+    fn to_vector(&self) -> Option<RadiantVectorNode> {
+        None
+    }
+
+    /// Resizes nodes sized by their content, like auto sized text boxes, to fit it as laid out
+    /// with `fonts`. Returns whether the size changed, which observers are told about.
+    fn fit_content(&mut self, _fonts: &epaint::text::Fonts) -> bool {
+        false
+    }
+
+    /// Named font families the node draws text in.
+    fn font_families(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Characters of the node, for nodes whose text is edited collaboratively.
+    fn text(&self) -> Option<&str> {
+        None
+    }
+    /// Applies a change made to the text by someone else. Returns whether it was taken.
+    fn apply_text_delta(&mut self, _delta: &[RadiantTextDelta]) -> bool {
+        false
+    }
+
+    /// Outline in document coordinates, for nodes whose points can be edited one by one.
+    fn path_commands(&self) -> Option<Vec<RadiantPathCommand>> {
+        None
+    }
+    /// Replaces the outline from [`RadiantNode::path_commands`]. Returns whether it was taken.
+    fn set_path_commands(&mut self, _commands: Vec<RadiantPathCommand>) -> bool {
         false
     }
 
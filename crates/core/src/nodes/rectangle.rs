@@ -1,5 +1,6 @@
 use crate::{
-    get_color_for_node, BaseNode, RadiantNode, RadiantTessellatable, ScreenDescriptor, Vec3, Observer,
+    pick_color, picking_tessellation_options, BaseNode, Observer, RadiantNode,
+    RadiantTessellatable, RadiantVectorGeometry, RadiantVectorNode, ScreenDescriptor, Vec3,
 };
 use epaint::{ClippedPrimitive, ClippedShape, Rect, TessellationOptions};
 use serde::{Deserialize, Serialize};
@@ -14,14 +15,35 @@ pub struct RadiantRectangleNode {
 impl RadiantRectangleNode {
     pub fn new_wasm(id: Uuid, position: Vec3, scale: Vec3) -> Self {
         let base = BaseNode::new(id, position, scale);
-        Self { base }
+        let mut node = Self { base };
+        node.update_bounding_rect();
+        node
     }
 }
 
 impl RadiantRectangleNode {
     pub fn new(id: Uuid, position: [f32; 2], scale: [f32; 2]) -> Self {
         let base = BaseNode::new(id, position.into(), scale.into());
-        Self { base }
+        let mut node = Self { base };
+        node.update_bounding_rect();
+        node
+    }
+
+    fn update_bounding_rect(&mut self) {
+        let position = self.base.transform.position();
+        let scale = self.base.transform.scale();
+
+        let rect = epaint::Rect::from_two_pos(
+            position.into(),
+            Vec3::new_with_added(&position, &scale).into(),
+        );
+
+        self.base.bounding_rect = [
+            rect.left_top().x,
+            rect.left_top().y,
+            rect.right_bottom().x,
+            rect.right_bottom().y,
+        ];
     }
 
     fn tessellate(&mut self, screen_descriptor: &ScreenDescriptor) {
@@ -55,7 +77,7 @@ impl RadiantRectangleNode {
             shapes,
         );
 
-        let fill_color = get_color_for_node(self.base.id);
+        let fill_color = pick_color(self.base.pick_id);
         let rect_shape = epaint::RectShape::filled(rect, rounding, fill_color);
         let shapes = vec![ClippedShape(
             Rect::EVERYTHING,
@@ -63,7 +85,7 @@ impl RadiantRectangleNode {
         )];
         self.base.selection_primitives = epaint::tessellator::tessellate_shapes(
             pixels_per_point,
-            TessellationOptions::default(),
+            picking_tessellation_options(),
             [1, 1],
             vec![],
             shapes,
@@ -82,21 +104,7 @@ impl RadiantTessellatable for RadiantRectangleNode {
     }
 
     fn set_needs_tessellation(&mut self, notify: bool) {
-        let position = self.base.transform.position();
-        let scale = self.base.transform.scale();
-
-        let rect = epaint::Rect::from_two_pos(
-            position.into(),
-            Vec3::new_with_added(&position, &scale).into(),
-        );
-
-        self.base.bounding_rect = [
-            rect.left_top().x,
-            rect.left_top().y,
-            rect.right_bottom().x,
-            rect.right_bottom().y,
-        ];
-
+        self.update_bounding_rect();
         self.base.set_needs_tessellation();
         if notify {
             self.base.notify(serde_json::to_string(self).unwrap());
@@ -130,8 +138,25 @@ impl RadiantNode for RadiantRectangleNode {
     fn replace(&mut self, node: &str) {
         let node: Self = serde_json::from_str(node).unwrap();
         let observers = std::mem::replace(&mut self.base.observers, Observer::default());
+        let bounds_observer = self.base.bounds_observer.take();
+        let revision = self.base.revision;
+        let pick_id = self.base.pick_id;
         self.base = node.base;
         self.base.observers = observers;
+        self.base.bounds_observer = bounds_observer;
+        self.base.revision = revision;
+        self.base.pick_id = pick_id;
         self.set_needs_tessellation(false);
     }
+
+    fn to_vector(&self) -> Option<RadiantVectorNode> {
+        let rect = self.base.bounding_rect;
+        let size = [rect[2] - rect[0], rect[3] - rect[1]];
+        // Rectangles are drawn without an outline.
+        Some(RadiantVectorNode::new(
+            &self.base,
+            RadiantVectorGeometry::Rect { size },
+            0.0,
+        ))
+    }
 }
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// One step of a change to a text, walking it from its start. Lengths count UTF-16 code
+/// units, like collaborating peers do.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum RadiantTextDelta {
+    /// Skips over unchanged text.
+    Retain(usize),
+    Insert(String),
+    Delete(usize),
+}
@@ -0,0 +1,84 @@
+use crate::{
+    RadiantDrawItem, RadiantPickBuffer, RadiantRenderManager, RadiantSoftwareRenderer,
+    ScreenDescriptor,
+};
+use epaint::{textures::TexturesDelta, ClippedPrimitive};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Where the scene draws to: a wgpu surface, or a CPU pixmap for headless use.
+pub enum RadiantRenderBackend {
+    Gpu(Box<RadiantRenderManager>),
+    Software(Box<RadiantSoftwareRenderer>),
+}
+
+impl RadiantRenderBackend {
+    pub fn resize(&mut self, new_size: [u32; 2]) {
+        match self {
+            Self::Gpu(render_manager) => render_manager.resize(new_size),
+            Self::Software(renderer) => renderer.resize(new_size),
+        }
+    }
+
+    pub fn update_textures(&mut self, delta: TexturesDelta) {
+        match self {
+            Self::Gpu(render_manager) => render_manager.update_textures(delta),
+            Self::Software(renderer) => renderer.update_textures(delta),
+        }
+    }
+
+    /// Revision of each node mesh the color or picking pass already holds at
+    /// `pixels_per_point`.
+    pub fn uploaded_revisions(
+        &self,
+        selection: bool,
+        pixels_per_point: f32,
+    ) -> Option<&HashMap<Uuid, u64>> {
+        match self {
+            Self::Gpu(render_manager) if selection => render_manager
+                .offscreen_renderer
+                .uploaded_revisions(pixels_per_point),
+            Self::Gpu(render_manager) => {
+                render_manager.renderer.uploaded_revisions(pixels_per_point)
+            }
+            Self::Software(renderer) => renderer.uploaded_revisions(selection, pixels_per_point),
+        }
+    }
+
+    pub fn render(
+        &mut self,
+        items: Vec<RadiantDrawItem>,
+        primitives: Vec<ClippedPrimitive>,
+        screen_descriptor: &ScreenDescriptor,
+        selection: bool,
+    ) -> Result<(), wgpu::SurfaceError> {
+        match self {
+            Self::Gpu(render_manager) => {
+                render_manager.render(items, primitives, screen_descriptor, selection)
+            }
+            Self::Software(renderer) => {
+                renderer.render(items, primitives, screen_descriptor, selection);
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn render_picking(
+        &mut self,
+        items: Vec<RadiantDrawItem>,
+        primitives: Vec<ClippedPrimitive>,
+        screen_descriptor: &ScreenDescriptor,
+        rect: [u32; 4],
+    ) -> Result<RadiantPickBuffer, wgpu::SurfaceError> {
+        match self {
+            Self::Gpu(render_manager) => {
+                render_manager
+                    .render_picking(items, primitives, screen_descriptor, rect)
+                    .await
+            }
+            Self::Software(renderer) => {
+                Ok(renderer.render_picking(items, primitives, screen_descriptor, rect))
+            }
+        }
+    }
+}
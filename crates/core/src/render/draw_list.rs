@@ -0,0 +1,57 @@
+use epaint::ClippedPrimitive;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// A node's contribution to a frame. `primitives` is only set when the renderer does not hold
+/// this revision of the node's mesh yet.
+#[derive(Debug)]
+pub struct RadiantDrawItem {
+    pub id: Uuid,
+    pub revision: u64,
+    pub primitives: Option<Vec<ClippedPrimitive>>,
+}
+
+/// Nodes to draw this frame, in draw order.
+pub struct RadiantDrawList<'a> {
+    uploaded: Option<&'a HashMap<Uuid, u64>>,
+    items: Vec<RadiantDrawItem>,
+}
+
+impl<'a> RadiantDrawList<'a> {
+    /// `uploaded` maps node ids to the revision of their mesh currently held by the renderer.
+    /// `None` means none of them can be reused, e.g. after the scale changed.
+    pub fn new(uploaded: Option<&'a HashMap<Uuid, u64>>) -> Self {
+        Self {
+            uploaded,
+            items: Vec::new(),
+        }
+    }
+
+    pub fn needs_upload(&self, id: Uuid, revision: u64) -> bool {
+        self.uploaded.and_then(|uploaded| uploaded.get(&id)) != Some(&revision)
+    }
+
+    pub fn push(&mut self, id: Uuid, revision: u64, primitives: Option<Vec<ClippedPrimitive>>) {
+        self.items.push(RadiantDrawItem {
+            id,
+            revision,
+            primitives,
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn items(&self) -> &[RadiantDrawItem] {
+        &self.items
+    }
+
+    pub fn into_items(self) -> Vec<RadiantDrawItem> {
+        self.items
+    }
+}
@@ -0,0 +1,14 @@
+/// Counters collected while tessellating the last frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RadiantFrameStats {
+    /// Nodes that were inside the viewport and submitted for rendering.
+    pub visible_nodes: usize,
+    /// Nodes that were skipped because their bounds are outside the viewport.
+    pub culled_nodes: usize,
+}
+
+impl RadiantFrameStats {
+    pub fn total_nodes(&self) -> usize {
+        self.visible_nodes + self.culled_nodes
+    }
+}
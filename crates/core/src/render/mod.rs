@@ -1,5 +1,15 @@
+pub mod backend;
+pub mod draw_list;
+pub mod frame_stats;
+pub mod picking;
 pub mod render_manager;
 pub mod renderer;
+pub mod software;
 
+pub use backend::*;
+pub use draw_list::*;
+pub use frame_stats::*;
+pub use picking::*;
 pub use render_manager::*;
 pub use renderer::*;
+pub use software::*;
@@ -0,0 +1,141 @@
+use epaint::{Color32, TessellationOptions};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+pub const PICKING_TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Uint;
+
+/// Packs `pick_id` into a vertex color. The picking shader reads the color back as a `u32`.
+pub fn pick_color(pick_id: u32) -> Color32 {
+    let [r, g, b, a] = pick_id.to_le_bytes();
+    Color32::from_rgba_premultiplied(r, g, b, a)
+}
+
+/// Feathering would blend ids at the edges, so picking primitives are tessellated without it.
+pub fn picking_tessellation_options() -> TessellationOptions {
+    TessellationOptions {
+        feathering: false,
+        ..Default::default()
+    }
+}
+
+/// Maps node ids to the non-zero integers written into the picking target. Ids of removed
+/// nodes are reused.
+#[derive(Debug, Default)]
+pub struct RadiantPickingIds {
+    ids: HashMap<Uuid, u32>,
+    nodes: Vec<Option<Uuid>>,
+    free: Vec<u32>,
+}
+
+impl RadiantPickingIds {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Returns the pick id of `id`, allocating one if needed.
+    pub fn insert(&mut self, id: Uuid) -> u32 {
+        if let Some(pick_id) = self.ids.get(&id) {
+            return *pick_id;
+        }
+        let pick_id = match self.free.pop() {
+            Some(pick_id) => {
+                self.nodes[pick_id as usize - 1] = Some(id);
+                pick_id
+            }
+            None => {
+                self.nodes.push(Some(id));
+                self.nodes.len() as u32
+            }
+        };
+        self.ids.insert(id, pick_id);
+        pick_id
+    }
+
+    pub fn remove(&mut self, id: Uuid) -> Option<u32> {
+        let pick_id = self.ids.remove(&id)?;
+        self.nodes[pick_id as usize - 1] = None;
+        self.free.push(pick_id);
+        Some(pick_id)
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<u32> {
+        self.ids.get(&id).copied()
+    }
+
+    pub fn node(&self, pick_id: u32) -> Option<Uuid> {
+        if pick_id == 0 {
+            return None;
+        }
+        self.nodes.get(pick_id as usize - 1).copied().flatten()
+    }
+}
+
+/// Picking ids read back from a rectangle of the picking target, row by row.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RadiantPickBuffer {
+    pub origin: [u32; 2],
+    pub size: [u32; 2],
+    pub ids: Vec<u32>,
+}
+
+impl RadiantPickBuffer {
+    pub fn new(origin: [u32; 2], size: [u32; 2], ids: Vec<u32>) -> Self {
+        Self { origin, size, ids }
+    }
+
+    /// Id at the screen pixel `[x, y]`, 0 if it is outside the buffer or empty.
+    pub fn get(&self, pixel: [u32; 2]) -> u32 {
+        if pixel[0] < self.origin[0] || pixel[1] < self.origin[1] {
+            return 0;
+        }
+        let x = pixel[0] - self.origin[0];
+        let y = pixel[1] - self.origin[1];
+        if x >= self.size[0] || y >= self.size[1] {
+            return 0;
+        }
+        self.ids[(y * self.size[0] + x) as usize]
+    }
+
+    /// Distinct non-zero ids of the pixels within `radius` of `center`, closest first.
+    pub fn ids_within(&self, center: [u32; 2], radius: u32) -> Vec<u32> {
+        let radius_sq = (radius * radius) as i64;
+        let mut hits: Vec<(i64, u32)> = Vec::new();
+        for (index, id) in self.ids.iter().enumerate() {
+            if *id == 0 {
+                continue;
+            }
+            let x = self.origin[0] as i64 + (index as u32 % self.size[0]) as i64;
+            let y = self.origin[1] as i64 + (index as u32 / self.size[0]) as i64;
+            let dx = x - center[0] as i64;
+            let dy = y - center[1] as i64;
+            let distance_sq = dx * dx + dy * dy;
+            if distance_sq <= radius_sq {
+                hits.push((distance_sq, *id));
+            }
+        }
+        hits.sort_unstable();
+        let mut seen = HashSet::new();
+        hits.into_iter()
+            .map(|(_, id)| id)
+            .filter(|id| seen.insert(*id))
+            .collect()
+    }
+
+    /// Distinct non-zero ids, in the order they first appear.
+    pub fn unique_ids(&self) -> Vec<u32> {
+        let mut seen = HashSet::new();
+        self.ids
+            .iter()
+            .copied()
+            .filter(|id| *id != 0 && seen.insert(*id))
+            .collect()
+    }
+}
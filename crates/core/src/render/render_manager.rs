@@ -1,6 +1,7 @@
-use crate::{get_node_for_color, RadiantRenderer, ScreenDescriptor};
-use epaint::{textures::TexturesDelta, ClippedPrimitive, Color32, ImageDelta};
-use uuid::Uuid;
+use crate::{
+    RadiantDrawItem, RadiantPickBuffer, RadiantRenderer, ScreenDescriptor, PICKING_TEXTURE_FORMAT,
+};
+use epaint::{textures::TexturesDelta, ClippedPrimitive, ImageDelta};
 
 pub struct RadiantRenderManager {
     pub config: wgpu::SurfaceConfiguration,
@@ -13,7 +14,6 @@ pub struct RadiantRenderManager {
 
     offscreen_texture: Option<wgpu::Texture>,
     offscreen_texture_view: Option<wgpu::TextureView>,
-    offscreen_buffer: Option<wgpu::Buffer>,
 
     pub current_view: Option<wgpu::TextureView>,
     pub current_texture: Option<wgpu::SurfaceTexture>,
@@ -28,8 +28,7 @@ impl RadiantRenderManager {
         font_image_delta: Option<ImageDelta>,
     ) -> Self {
         let mut renderer = RadiantRenderer::new(&device, config.format, None, 1);
-        let mut offscreen_renderer =
-            RadiantRenderer::new(&device, wgpu::TextureFormat::Rgba8Unorm, None, 1);
+        let mut offscreen_renderer = RadiantRenderer::new(&device, PICKING_TEXTURE_FORMAT, None, 1);
 
         if let Some(image_delta) = font_image_delta {
             renderer.update_texture(&device, &queue, epaint::TextureId::default(), &image_delta);
@@ -52,7 +51,6 @@ impl RadiantRenderManager {
 
             offscreen_texture: None,
             offscreen_texture_view: None,
-            offscreen_buffer: None,
 
             current_view: None,
             current_texture: None,
@@ -77,7 +75,7 @@ impl RadiantRenderManager {
                 mip_level_count: 1,
                 sample_count: 1,
                 dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Rgba8Unorm,
+                format: PICKING_TEXTURE_FORMAT,
                 usage: wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::RENDER_ATTACHMENT,
                 label: None,
                 view_formats: &[],
@@ -85,22 +83,6 @@ impl RadiantRenderManager {
             let texture = self.device.create_texture(&texture_desc);
             self.offscreen_texture_view = Some(texture.create_view(&Default::default()));
             self.offscreen_texture = Some(texture);
-
-            // we need to store this for later
-            let u32_size = std::mem::size_of::<u32>() as u32;
-            let unpadded_bytes_per_row = u32_size * texture_width;
-            let padded_bytes_per_row =
-                wgpu::util::align_to(unpadded_bytes_per_row, wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
-            let output_buffer_size = (padded_bytes_per_row * texture_height) as wgpu::BufferAddress;
-            let output_buffer_desc = wgpu::BufferDescriptor {
-                size: output_buffer_size,
-                usage: wgpu::BufferUsages::COPY_DST
-                    // this tells wpgu that we want to read this buffer from the cpu
-                    | wgpu::BufferUsages::MAP_READ,
-                label: None,
-                mapped_at_creation: false,
-            };
-            self.offscreen_buffer = Some(self.device.create_buffer(&output_buffer_desc));
         }
     }
 
@@ -117,8 +99,11 @@ impl RadiantRenderManager {
         }
     }
 
+    /// Draws the retained node meshes in `items`, followed by the immediate `primitives`
+    /// (interactions and other overlays).
     pub fn render(
         &mut self,
+        items: Vec<RadiantDrawItem>,
         primitives: Vec<ClippedPrimitive>,
         screen_descriptor: &ScreenDescriptor,
         selection: bool,
@@ -129,7 +114,13 @@ impl RadiantRenderManager {
                 label: Some("Render Encoder"),
             });
 
-        self.render_internal(primitives, screen_descriptor, selection, &mut encoder)?;
+        self.render_internal(
+            items,
+            primitives,
+            screen_descriptor,
+            selection,
+            &mut encoder,
+        )?;
 
         // submit will accept anything that implements IntoIter
         self.queue.submit(std::iter::once(encoder.finish()));
@@ -145,6 +136,7 @@ impl RadiantRenderManager {
 
     fn render_internal(
         &mut self,
+        items: Vec<RadiantDrawItem>,
         primitives: Vec<ClippedPrimitive>,
         screen_descriptor: &ScreenDescriptor,
         selection: bool,
@@ -152,6 +144,13 @@ impl RadiantRenderManager {
     ) -> Result<(), wgpu::SurfaceError> {
         let view;
         if selection {
+            self.offscreen_renderer.update_retained(
+                &self.device,
+                &self.queue,
+                encoder,
+                screen_descriptor,
+                items,
+            );
             self.offscreen_renderer.update_buffers(
                 &self.device,
                 &self.queue,
@@ -160,6 +159,13 @@ impl RadiantRenderManager {
             );
             view = self.offscreen_texture_view.as_ref().unwrap();
         } else {
+            self.renderer.update_retained(
+                &self.device,
+                &self.queue,
+                encoder,
+                screen_descriptor,
+                items,
+            );
             self.renderer
                 .update_buffers(&self.device, &self.queue, screen_descriptor, &primitives);
 
@@ -203,69 +209,90 @@ impl RadiantRenderManager {
                 depth_stencil_attachment: None,
             });
 
-            if selection {
-                self.offscreen_renderer
-                    .render(&mut render_pass, screen_descriptor, &primitives);
+            let renderer = if selection {
+                &self.offscreen_renderer
             } else {
-                self.renderer
-                    .render(&mut render_pass, screen_descriptor, &primitives);
-            }
+                &self.renderer
+            };
+            renderer.render_retained(&mut render_pass, screen_descriptor);
+            renderer.render(&mut render_pass, screen_descriptor, &primitives);
         }
 
         Ok(())
     }
 
-    pub async fn render_offscreen(
+    /// Renders the picking pass and reads back the ids inside `rect` (`[min_x, min_y, max_x,
+    /// max_y]` in physical pixels, max exclusive), clamped to the screen.
+    pub async fn render_picking(
         &mut self,
+        items: Vec<RadiantDrawItem>,
         primitives: Vec<ClippedPrimitive>,
         screen_descriptor: &ScreenDescriptor,
-        selection: bool,
-        mouse_position: [f32; 2],
-    ) -> Result<Option<Uuid>, wgpu::SurfaceError> {
-        let texture_width = screen_descriptor.size_in_pixels[0];
-        let texture_height = screen_descriptor.size_in_pixels[1];
-
-        let u32_size = std::mem::size_of::<u32>() as u32;
-        let unpadded_bytes_per_row = u32_size * texture_width;
-        let padded_bytes_per_row =
-            wgpu::util::align_to(unpadded_bytes_per_row, wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+        rect: [u32; 4],
+    ) -> Result<RadiantPickBuffer, wgpu::SurfaceError> {
+        let [screen_width, screen_height] = screen_descriptor.size_in_pixels;
+        let min_x = rect[0].min(screen_width);
+        let min_y = rect[1].min(screen_height);
+        let width = rect[2].clamp(min_x, screen_width) - min_x;
+        let height = rect[3].clamp(min_y, screen_height) - min_y;
 
         let mut encoder = self
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
 
-        self.render_internal(primitives, screen_descriptor, selection, &mut encoder)?;
+        self.render_internal(items, primitives, screen_descriptor, true, &mut encoder)?;
+
+        if width == 0 || height == 0 {
+            self.queue.submit(Some(encoder.finish()));
+            return Ok(RadiantPickBuffer::new([min_x, min_y], [0, 0], Vec::new()));
+        }
+
+        let u32_size = std::mem::size_of::<u32>() as u32;
+        let unpadded_bytes_per_row = u32_size * width;
+        let padded_bytes_per_row =
+            wgpu::util::align_to(unpadded_bytes_per_row, wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            size: (padded_bytes_per_row * height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::COPY_DST
+                // this tells wpgu that we want to read this buffer from the cpu
+                | wgpu::BufferUsages::MAP_READ,
+            label: None,
+            mapped_at_creation: false,
+        });
 
         encoder.copy_texture_to_buffer(
             wgpu::ImageCopyTexture {
                 aspect: wgpu::TextureAspect::All,
-                texture: &self.offscreen_texture.as_ref().unwrap(),
+                texture: self.offscreen_texture.as_ref().unwrap(),
                 mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
+                origin: wgpu::Origin3d {
+                    x: min_x,
+                    y: min_y,
+                    z: 0,
+                },
             },
             wgpu::ImageCopyBuffer {
-                buffer: &self.offscreen_buffer.as_ref().unwrap(),
+                buffer: &buffer,
                 layout: wgpu::ImageDataLayout {
                     offset: 0,
                     bytes_per_row: Some(padded_bytes_per_row),
-                    rows_per_image: Some(texture_height),
+                    rows_per_image: Some(height),
                 },
             },
             wgpu::Extent3d {
-                width: texture_width,
-                height: texture_height,
+                width,
+                height,
                 depth_or_array_layers: 1,
             },
         );
 
         let submission_id = self.queue.submit(Some(encoder.finish()));
 
-        let color: Color32;
+        let mut ids = Vec::with_capacity((width * height) as usize);
 
         // We need to scope the mapping variables so that we can
         // unmap the buffer
         {
-            let buffer = self.offscreen_buffer.as_ref().unwrap();
             let buffer_slice = buffer.slice(..);
 
             let (sender, receiver) = std::sync::mpsc::channel();
@@ -277,28 +304,18 @@ impl RadiantRenderManager {
             receiver.recv().ok().unwrap().ok().unwrap();
 
             let data = buffer_slice.get_mapped_range();
-
-            let posx: u32 = (mouse_position[0] * screen_descriptor.pixels_per_point) as u32;
-            let posy: u32 = (mouse_position[1] * screen_descriptor.pixels_per_point) as u32;
-            let index = (posy * padded_bytes_per_row + posx * 4) as usize;
-
-            color = Color32::from_rgb(
-                *data.get(index).unwrap(),
-                *data.get(index + 1).unwrap(),
-                *data.get(index + 2).unwrap(),
-            );
-
-            // use image::{ImageBuffer, Rgba};
-            // let buffer =
-            //     ImageBuffer::<Rgba<u8>, _>::from_raw(texture_width, texture_height, data).unwrap();
-
-            // #[cfg(not(target_arch = "wasm32"))]
-            // buffer.save("image.png").unwrap();
+            for row in data.chunks(padded_bytes_per_row as usize) {
+                let row = &row[..unpadded_bytes_per_row as usize];
+                ids.extend(
+                    row.chunks_exact(u32_size as usize)
+                        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+                );
+            }
 
             drop(data);
             buffer.unmap();
         }
 
-        Ok(get_node_for_color(color))
+        Ok(RadiantPickBuffer::new([min_x, min_y], [width, height], ids))
     }
 }
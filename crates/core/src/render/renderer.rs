@@ -1,10 +1,11 @@
-use crate::ScreenDescriptor;
+use crate::{RadiantDrawItem, ScreenDescriptor, PICKING_TEXTURE_FORMAT};
 use epaint::emath::NumExt;
-use epaint::{Primitive, Vertex};
+use epaint::{ClippedPrimitive, Primitive, Vertex};
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::num::NonZeroU64;
 use std::ops::Range;
+use uuid::Uuid;
 use wgpu::util::DeviceExt;
 
 /// Uniform buffer used when rendering.
@@ -29,11 +30,327 @@ struct SlicedBuffer {
     capacity: wgpu::BufferAddress,
 }
 
+/// Byte range reserved for one node in a [`RetainedBuffer`].
+#[derive(Clone, Copy)]
+struct Region {
+    offset: wgpu::BufferAddress,
+    capacity: wgpu::BufferAddress,
+}
+
+/// Vertex or index buffer whose contents persist across frames, carved into per-node regions.
+struct RetainedBuffer {
+    buffer: wgpu::Buffer,
+    usage: wgpu::BufferUsages,
+    capacity: wgpu::BufferAddress,
+    end: wgpu::BufferAddress,
+}
+
+impl RetainedBuffer {
+    fn new(
+        device: &wgpu::Device,
+        usage: wgpu::BufferUsages,
+        capacity: wgpu::BufferAddress,
+    ) -> Self {
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("radiant_retained_buffer"),
+            usage: usage | wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC,
+            size: capacity,
+            mapped_at_creation: false,
+        });
+        Self {
+            buffer,
+            usage,
+            capacity,
+            end: 0,
+        }
+    }
+
+    fn allocate(&mut self, size: wgpu::BufferAddress) -> Region {
+        let region = Region {
+            offset: self.end,
+            capacity: size,
+        };
+        self.end += size;
+        region
+    }
+
+    /// Reuses `previous` if the new contents still fit, otherwise allocates at the end.
+    fn reserve(&mut self, previous: Option<Region>, size: wgpu::BufferAddress) -> Region {
+        match previous {
+            Some(region) if region.capacity >= size => region,
+            _ => self.allocate(size),
+        }
+    }
+
+    fn copy_from(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        source: &RetainedBuffer,
+        region: Region,
+    ) -> Region {
+        let new_region = self.allocate(region.capacity);
+        if region.capacity > 0 {
+            encoder.copy_buffer_to_buffer(
+                &source.buffer,
+                region.offset,
+                &self.buffer,
+                new_region.offset,
+                region.capacity,
+            );
+        }
+        new_region
+    }
+}
+
+struct RetainedDraw {
+    clip_rect: epaint::Rect,
+    texture_id: epaint::TextureId,
+    /// Byte ranges relative to the start of the node's regions.
+    vertices: Range<wgpu::BufferAddress>,
+    indices: Range<wgpu::BufferAddress>,
+    index_count: u32,
+}
+
+struct RetainedMesh {
+    vertices: Region,
+    indices: Region,
+    draws: Vec<RetainedDraw>,
+}
+
+/// A node's primitives flattened into one vertex and one index array.
+struct RetainedUpload {
+    id: Uuid,
+    revision: u64,
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+    draws: Vec<RetainedDraw>,
+}
+
+impl RetainedUpload {
+    fn new(id: Uuid, revision: u64, primitives: Vec<ClippedPrimitive>) -> Self {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        let mut draws = Vec::new();
+        for ClippedPrimitive {
+            clip_rect,
+            primitive,
+        } in primitives
+        {
+            let Primitive::Mesh(mesh) = primitive else {
+                continue;
+            };
+            if mesh.indices.is_empty() {
+                continue;
+            }
+            let vertex_start = std::mem::size_of_val(vertices.as_slice()) as u64;
+            let index_start = std::mem::size_of_val(indices.as_slice()) as u64;
+            vertices.extend_from_slice(&mesh.vertices);
+            indices.extend_from_slice(&mesh.indices);
+            draws.push(RetainedDraw {
+                clip_rect,
+                texture_id: mesh.texture_id,
+                vertices: vertex_start..std::mem::size_of_val(vertices.as_slice()) as u64,
+                indices: index_start..std::mem::size_of_val(indices.as_slice()) as u64,
+                index_count: mesh.indices.len() as u32,
+            });
+        }
+        Self {
+            id,
+            revision,
+            vertices,
+            indices,
+            draws,
+        }
+    }
+
+    fn vertex_size(&self) -> wgpu::BufferAddress {
+        std::mem::size_of_val(self.vertices.as_slice()) as _
+    }
+
+    fn index_size(&self) -> wgpu::BufferAddress {
+        std::mem::size_of_val(self.indices.as_slice()) as _
+    }
+}
+
+/// Per-node meshes kept on the GPU between frames. Only nodes whose revision changed are
+/// uploaded again.
+struct RetainedMeshes {
+    vertex_buffer: RetainedBuffer,
+    index_buffer: RetainedBuffer,
+    meshes: HashMap<Uuid, RetainedMesh>,
+    revisions: HashMap<Uuid, u64>,
+    /// Scale the retained meshes were tessellated at.
+    pixels_per_point: f32,
+    order: Vec<Uuid>,
+}
+
+impl RetainedMeshes {
+    fn new(
+        device: &wgpu::Device,
+        vertex_capacity: wgpu::BufferAddress,
+        index_capacity: wgpu::BufferAddress,
+    ) -> Self {
+        Self {
+            vertex_buffer: RetainedBuffer::new(device, wgpu::BufferUsages::VERTEX, vertex_capacity),
+            index_buffer: RetainedBuffer::new(device, wgpu::BufferUsages::INDEX, index_capacity),
+            meshes: HashMap::new(),
+            revisions: HashMap::new(),
+            pixels_per_point: 0.0,
+            order: Vec::new(),
+        }
+    }
+
+    fn update(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        items: Vec<RadiantDrawItem>,
+        pixels_per_point: f32,
+    ) {
+        // Meshes of another scale are overwritten once their nodes are drawn again.
+        if self.pixels_per_point != pixels_per_point {
+            self.pixels_per_point = pixels_per_point;
+            self.revisions.clear();
+        }
+        self.order.clear();
+        let mut uploads = Vec::new();
+        for item in items {
+            self.order.push(item.id);
+            if let Some(primitives) = item.primitives {
+                uploads.push(RetainedUpload::new(item.id, item.revision, primitives));
+            }
+        }
+        if uploads.is_empty() {
+            return;
+        }
+
+        let (vertex_growth, index_growth) = uploads.iter().fold((0, 0), |acc, upload| {
+            let mesh = self.meshes.get(&upload.id);
+            let vertex_fits =
+                mesh.is_some_and(|mesh| mesh.vertices.capacity >= upload.vertex_size());
+            let index_fits = mesh.is_some_and(|mesh| mesh.indices.capacity >= upload.index_size());
+            (
+                acc.0 + if vertex_fits { 0 } else { upload.vertex_size() },
+                acc.1 + if index_fits { 0 } else { upload.index_size() },
+            )
+        });
+        if self.vertex_buffer.end + vertex_growth > self.vertex_buffer.capacity
+            || self.index_buffer.end + index_growth > self.index_buffer.capacity
+        {
+            self.repack(device, encoder, &uploads);
+        }
+
+        for upload in uploads {
+            self.write(queue, upload);
+        }
+    }
+
+    /// Moves the meshes drawn this frame into fresh buffers, dropping everything else. Meshes
+    /// about to be uploaded get their space allocated by [`Self::write`] afterwards.
+    fn repack(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        uploads: &[RetainedUpload],
+    ) {
+        let uploading = uploads
+            .iter()
+            .map(|upload| upload.id)
+            .collect::<HashSet<_>>();
+        let kept = self
+            .order
+            .iter()
+            .filter(|id| !uploading.contains(id) && self.meshes.contains_key(id))
+            .copied()
+            .collect::<Vec<_>>();
+
+        let (vertex_size, index_size) =
+            kept.iter()
+                .map(|id| &self.meshes[id])
+                .fold((0, 0), |acc, mesh| {
+                    (
+                        acc.0 + mesh.vertices.capacity,
+                        acc.1 + mesh.indices.capacity,
+                    )
+                });
+        let (vertex_size, index_size) = uploads
+            .iter()
+            .fold((vertex_size, index_size), |acc, upload| {
+                (acc.0 + upload.vertex_size(), acc.1 + upload.index_size())
+            });
+
+        let mut vertex_buffer = RetainedBuffer::new(
+            device,
+            self.vertex_buffer.usage,
+            (vertex_size * 2).at_least(self.vertex_buffer.capacity),
+        );
+        let mut index_buffer = RetainedBuffer::new(
+            device,
+            self.index_buffer.usage,
+            (index_size * 2).at_least(self.index_buffer.capacity),
+        );
+
+        let mut meshes = HashMap::with_capacity(kept.len());
+        for id in kept {
+            if let Some(mut mesh) = self.meshes.remove(&id) {
+                mesh.vertices =
+                    vertex_buffer.copy_from(encoder, &self.vertex_buffer, mesh.vertices);
+                mesh.indices = index_buffer.copy_from(encoder, &self.index_buffer, mesh.indices);
+                meshes.insert(id, mesh);
+            }
+        }
+        self.revisions.retain(|id, _| meshes.contains_key(id));
+        self.meshes = meshes;
+        self.vertex_buffer = vertex_buffer;
+        self.index_buffer = index_buffer;
+    }
+
+    fn write(&mut self, queue: &wgpu::Queue, upload: RetainedUpload) {
+        let previous = self
+            .meshes
+            .get(&upload.id)
+            .map(|mesh| (mesh.vertices, mesh.indices));
+        let vertices = self
+            .vertex_buffer
+            .reserve(previous.map(|regions| regions.0), upload.vertex_size());
+        let indices = self
+            .index_buffer
+            .reserve(previous.map(|regions| regions.1), upload.index_size());
+
+        if !upload.vertices.is_empty() {
+            queue.write_buffer(
+                &self.vertex_buffer.buffer,
+                vertices.offset,
+                bytemuck::cast_slice(&upload.vertices),
+            );
+        }
+        if !upload.indices.is_empty() {
+            queue.write_buffer(
+                &self.index_buffer.buffer,
+                indices.offset,
+                bytemuck::cast_slice(&upload.indices),
+            );
+        }
+
+        self.revisions.insert(upload.id, upload.revision);
+        self.meshes.insert(
+            upload.id,
+            RetainedMesh {
+                vertices,
+                indices,
+                draws: upload.draws,
+            },
+        );
+    }
+}
+
 pub struct RadiantRenderer {
     pipeline: wgpu::RenderPipeline,
 
     index_buffer: SlicedBuffer,
     vertex_buffer: SlicedBuffer,
+    retained: RetainedMeshes,
 
     uniform_buffer: wgpu::Buffer,
     previous_uniform_buffer_content: UniformBuffer,
@@ -55,7 +372,13 @@ impl RadiantRenderer {
         output_depth_format: Option<wgpu::TextureFormat>,
         msaa_samples: u32,
     ) -> Self {
-        let module = device.create_shader_module(wgpu::include_wgsl!("egui.wgsl"));
+        // Integer targets receive picking ids rather than colors.
+        let picking = output_color_format == PICKING_TEXTURE_FORMAT;
+        let module = if picking {
+            device.create_shader_module(wgpu::include_wgsl!("picking.wgsl"))
+        } else {
+            device.create_shader_module(wgpu::include_wgsl!("egui.wgsl"))
+        };
 
         let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("egui_uniform_buffer"),
@@ -164,7 +487,9 @@ impl RadiantRenderer {
 
             fragment: Some(wgpu::FragmentState {
                 module: &module,
-                entry_point: if output_color_format.is_srgb() {
+                entry_point: if picking {
+                    "fs_main"
+                } else if output_color_format.is_srgb() {
                     log::warn!("Detected a linear (sRGBA aware) framebuffer {:?}. egui prefers Rgba8Unorm or Bgra8Unorm", output_color_format);
                     "fs_main_linear_framebuffer"
                 } else {
@@ -172,7 +497,7 @@ impl RadiantRenderer {
                 },
                 targets: &[Some(wgpu::ColorTargetState {
                     format: output_color_format,
-                    blend: Some(wgpu::BlendState {
+                    blend: (!picking).then_some(wgpu::BlendState {
                         color: wgpu::BlendComponent {
                             src_factor: wgpu::BlendFactor::One,
                             dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
@@ -207,6 +532,11 @@ impl RadiantRenderer {
                 slices: Vec::with_capacity(64),
                 capacity: INDEX_BUFFER_START_CAPACITY,
             },
+            retained: RetainedMeshes::new(
+                device,
+                VERTEX_BUFFER_START_CAPACITY,
+                INDEX_BUFFER_START_CAPACITY,
+            ),
             uniform_buffer,
             // Buffers on wgpu are zero initialized, so this is indeed its current state!
             previous_uniform_buffer_content: UniformBuffer {
@@ -228,21 +558,7 @@ impl RadiantRenderer {
         screen_descriptor: &ScreenDescriptor,
         paint_jobs: &[epaint::ClippedPrimitive],
     ) {
-        let screen_size_in_points = screen_descriptor.screen_size_in_points();
-
-        let uniform_buffer_content = UniformBuffer {
-            screen_size_in_points,
-            _padding: Default::default(),
-        };
-        if uniform_buffer_content != self.previous_uniform_buffer_content {
-            // crate::profile_scope!("update uniforms");
-            queue.write_buffer(
-                &self.uniform_buffer,
-                0,
-                bytemuck::cast_slice(&[uniform_buffer_content]),
-            );
-            self.previous_uniform_buffer_content = uniform_buffer_content;
-        }
+        self.update_uniforms(queue, screen_descriptor);
 
         let (vertex_count, index_count) = {
             // crate::profile_scope!("count_vertices_indices");
@@ -335,6 +651,50 @@ impl RadiantRenderer {
         }
     }
 
+    /// Revision of each node mesh currently held in the retained buffers, or `None` when they
+    /// were tessellated at another `pixels_per_point`.
+    pub fn uploaded_revisions(&self, pixels_per_point: f32) -> Option<&HashMap<Uuid, u64>> {
+        (self.retained.pixels_per_point == pixels_per_point).then_some(&self.retained.revisions)
+    }
+
+    /// Uploads the meshes of the items that carry primitives and remembers the draw order.
+    /// Items without primitives reuse what is already on the GPU.
+    pub fn update_retained(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        screen_descriptor: &ScreenDescriptor,
+        items: Vec<RadiantDrawItem>,
+    ) {
+        self.update_uniforms(queue, screen_descriptor);
+        self.retained.update(
+            device,
+            queue,
+            encoder,
+            items,
+            screen_descriptor.pixels_per_point,
+        );
+    }
+
+    fn update_uniforms(&mut self, queue: &wgpu::Queue, screen_descriptor: &ScreenDescriptor) {
+        let screen_size_in_points = screen_descriptor.screen_size_in_points();
+
+        let uniform_buffer_content = UniformBuffer {
+            screen_size_in_points,
+            _padding: Default::default(),
+        };
+        if uniform_buffer_content != self.previous_uniform_buffer_content {
+            // crate::profile_scope!("update uniforms");
+            queue.write_buffer(
+                &self.uniform_buffer,
+                0,
+                bytemuck::cast_slice(&[uniform_buffer_content]),
+            );
+            self.previous_uniform_buffer_content = uniform_buffer_content;
+        }
+    }
+
     pub fn update_texture(
         &mut self,
         device: &wgpu::Device,
@@ -447,6 +807,60 @@ impl RadiantRenderer {
         };
     }
 
+    /// Draws the retained node meshes in the order of the last [`Self::update_retained`].
+    pub fn render_retained<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        screen_descriptor: &ScreenDescriptor,
+    ) {
+        let pixels_per_point = screen_descriptor.pixels_per_point;
+        let size_in_pixels = screen_descriptor.size_in_pixels;
+
+        render_pass.set_viewport(
+            0.0,
+            0.0,
+            size_in_pixels[0] as f32,
+            size_in_pixels[1] as f32,
+            0.0,
+            1.0,
+        );
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+
+        let retained = &self.retained;
+        for mesh in retained
+            .order
+            .iter()
+            .filter_map(|id| retained.meshes.get(id))
+        {
+            for draw in &mesh.draws {
+                let rect = ScissorRect::new(&draw.clip_rect, pixels_per_point, size_in_pixels);
+                if rect.width == 0 || rect.height == 0 {
+                    continue;
+                }
+                render_pass.set_scissor_rect(rect.x, rect.y, rect.width, rect.height);
+
+                if let Some((_texture, bind_group)) = self.textures.get(&draw.texture_id) {
+                    let indices = mesh.indices.offset + draw.indices.start
+                        ..mesh.indices.offset + draw.indices.end;
+                    let vertices = mesh.vertices.offset + draw.vertices.start
+                        ..mesh.vertices.offset + draw.vertices.end;
+                    render_pass.set_bind_group(1, bind_group, &[]);
+                    render_pass.set_index_buffer(
+                        retained.index_buffer.buffer.slice(indices),
+                        wgpu::IndexFormat::Uint32,
+                    );
+                    render_pass.set_vertex_buffer(0, retained.vertex_buffer.buffer.slice(vertices));
+                    render_pass.draw_indexed(0..draw.index_count, 0, 0..1);
+                } else {
+                    log::warn!("Missing texture: {:?}", draw.texture_id);
+                }
+            }
+        }
+
+        render_pass.set_scissor_rect(0, 0, size_in_pixels[0], size_in_pixels[1]);
+    }
+
     pub fn render<'a>(
         &'a self,
         render_pass: &mut wgpu::RenderPass<'a>,
@@ -0,0 +1,427 @@
+use crate::{RadiantDrawItem, RadiantPickBuffer, ScreenDescriptor};
+use epaint::{
+    textures::{TextureFilter, TexturesDelta},
+    ClippedPrimitive, Color32, ImageDelta, Mesh, Primitive, Rect, Rgba, TextureId,
+};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// RGBA image produced by the [`RadiantSoftwareRenderer`]. Colors are premultiplied sRGB.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RadiantPixmap {
+    pub size: [u32; 2],
+    pub pixels: Vec<Color32>,
+}
+
+impl RadiantPixmap {
+    pub fn new(size: [u32; 2]) -> Self {
+        Self {
+            size,
+            pixels: vec![Color32::TRANSPARENT; (size[0] * size[1]) as usize],
+        }
+    }
+
+    /// Color at `[x, y]`, transparent if it is outside the pixmap.
+    pub fn get(&self, pixel: [u32; 2]) -> Color32 {
+        if pixel[0] >= self.size[0] || pixel[1] >= self.size[1] {
+            return Color32::TRANSPARENT;
+        }
+        self.pixels[(pixel[1] * self.size[0] + pixel[0]) as usize]
+    }
+
+    /// Copies `source` into this pixmap with its top left corner at `at`, cropping what falls
+    /// outside.
+    pub fn copy_from(&mut self, source: &RadiantPixmap, at: [u32; 2]) {
+        let width = source.size[0].min(self.size[0].saturating_sub(at[0])) as usize;
+        for row in 0..source.size[1].min(self.size[1].saturating_sub(at[1])) {
+            let from = (row * source.size[0]) as usize;
+            let to = ((at[1] + row) * self.size[0] + at[0]) as usize;
+            self.pixels[to..to + width].copy_from_slice(&source.pixels[from..from + width]);
+        }
+    }
+
+    /// Row-major RGBA bytes, four per pixel.
+    pub fn as_rgba8(&self) -> &[u8] {
+        bytemuck::cast_slice(&self.pixels)
+    }
+}
+
+struct SoftwareTexture {
+    size: [usize; 2],
+    texels: Vec<Rgba>,
+    filter: TextureFilter,
+}
+
+impl SoftwareTexture {
+    fn sample(&self, uv: [f32; 2]) -> Rgba {
+        let [width, height] = self.size;
+        if width == 0 || height == 0 {
+            return Rgba::TRANSPARENT;
+        }
+        let texel = |x: isize, y: isize| {
+            let x = x.clamp(0, width as isize - 1) as usize;
+            let y = y.clamp(0, height as isize - 1) as usize;
+            self.texels[y * width + x]
+        };
+        let x = uv[0] * width as f32;
+        let y = uv[1] * height as f32;
+        match self.filter {
+            TextureFilter::Nearest => texel(x.floor() as isize, y.floor() as isize),
+            TextureFilter::Linear => {
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (x0, y0) = (x.floor(), y.floor());
+                let (tx, ty) = (x - x0, y - y0);
+                let (x0, y0) = (x0 as isize, y0 as isize);
+                let top = texel(x0, y0) * (1.0 - tx) + texel(x0 + 1, y0) * tx;
+                let bottom = texel(x0, y0 + 1) * (1.0 - tx) + texel(x0 + 1, y0 + 1) * tx;
+                top * (1.0 - ty) + bottom * ty
+            }
+        }
+    }
+}
+
+/// Node meshes kept between frames, mirroring the retained GPU buffers.
+#[derive(Default)]
+struct RetainedPrimitives {
+    primitives: HashMap<Uuid, Vec<ClippedPrimitive>>,
+    revisions: HashMap<Uuid, u64>,
+    /// Scale the retained meshes were tessellated at.
+    pixels_per_point: f32,
+    order: Vec<Uuid>,
+}
+
+impl RetainedPrimitives {
+    fn update(&mut self, items: Vec<RadiantDrawItem>, pixels_per_point: f32) {
+        if self.pixels_per_point != pixels_per_point {
+            self.pixels_per_point = pixels_per_point;
+            self.revisions.clear();
+        }
+        self.order.clear();
+        for item in items {
+            self.order.push(item.id);
+            if let Some(primitives) = item.primitives {
+                self.primitives.insert(item.id, primitives);
+                self.revisions.insert(item.id, item.revision);
+            }
+        }
+        let drawn = self.order.iter().collect::<HashSet<_>>();
+        self.primitives.retain(|id, _| drawn.contains(id));
+        self.revisions.retain(|id, _| drawn.contains(id));
+    }
+
+    fn revisions(&self, pixels_per_point: f32) -> Option<&HashMap<Uuid, u64>> {
+        (self.pixels_per_point == pixels_per_point).then_some(&self.revisions)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &ClippedPrimitive> {
+        self.order
+            .iter()
+            .filter_map(|id| self.primitives.get(id))
+            .flatten()
+    }
+}
+
+/// Rasterizes the same primitives as the GPU renderer on the CPU, so scenes can be rendered
+/// without a surface or adapter.
+pub struct RadiantSoftwareRenderer {
+    pub clear_color: Rgba,
+
+    size: [u32; 2],
+    textures: HashMap<TextureId, SoftwareTexture>,
+    retained: RetainedPrimitives,
+    picking_retained: RetainedPrimitives,
+
+    color_buffer: Vec<Rgba>,
+    pixmap: RadiantPixmap,
+    ids: Vec<u32>,
+}
+
+impl RadiantSoftwareRenderer {
+    pub fn new(size: [u32; 2]) -> Self {
+        let mut renderer = Self {
+            clear_color: Rgba::from_rgb(0.1, 0.2, 0.3),
+
+            size: [0, 0],
+            textures: HashMap::new(),
+            retained: RetainedPrimitives::default(),
+            picking_retained: RetainedPrimitives::default(),
+
+            color_buffer: Vec::new(),
+            pixmap: RadiantPixmap::default(),
+            ids: Vec::new(),
+        };
+        renderer.resize(size);
+        renderer
+    }
+
+    pub fn resize(&mut self, new_size: [u32; 2]) {
+        if self.size == new_size {
+            return;
+        }
+        let len = (new_size[0] * new_size[1]) as usize;
+        self.size = new_size;
+        self.color_buffer = vec![self.clear_color; len];
+        self.pixmap = RadiantPixmap::new(new_size);
+        self.ids = vec![0; len];
+    }
+
+    /// The last frame drawn by [`Self::render`].
+    pub fn pixmap(&self) -> &RadiantPixmap {
+        &self.pixmap
+    }
+
+    /// Revision of each node mesh currently retained for the color or picking pass, or `None`
+    /// when they were tessellated at another `pixels_per_point`.
+    pub fn uploaded_revisions(
+        &self,
+        selection: bool,
+        pixels_per_point: f32,
+    ) -> Option<&HashMap<Uuid, u64>> {
+        if selection {
+            self.picking_retained.revisions(pixels_per_point)
+        } else {
+            self.retained.revisions(pixels_per_point)
+        }
+    }
+
+    /// Current contents of the texture `id`, if it has been uploaded.
+    pub fn texture_pixmap(&self, id: TextureId) -> Option<RadiantPixmap> {
+        let texture = self.textures.get(&id)?;
+        Some(RadiantPixmap {
+            size: [texture.size[0] as u32, texture.size[1] as u32],
+            pixels: texture.texels.iter().map(|texel| (*texel).into()).collect(),
+        })
+    }
+
+    pub fn update_textures(&mut self, delta: TexturesDelta) {
+        for (texture_id, image_delta) in delta.set {
+            self.update_texture(texture_id, &image_delta);
+        }
+        for texture_id in delta.free {
+            self.textures.remove(&texture_id);
+        }
+    }
+
+    pub fn update_texture(&mut self, id: TextureId, image_delta: &ImageDelta) {
+        let size = image_delta.image.size();
+        let texels: Vec<Rgba> = match &image_delta.image {
+            epaint::ImageData::Color(image) => image
+                .pixels
+                .iter()
+                .map(|color| Rgba::from(*color))
+                .collect(),
+            epaint::ImageData::Font(image) => image.srgba_pixels(None).map(Rgba::from).collect(),
+        };
+
+        if let Some(pos) = image_delta.pos {
+            let texture = self
+                .textures
+                .get_mut(&id)
+                .expect("Tried to update a texture that has not been allocated yet.");
+            for (row, chunk) in texels.chunks(size[0].max(1)).enumerate() {
+                let y = pos[1] + row;
+                if y >= texture.size[1] {
+                    break;
+                }
+                let start = y * texture.size[0] + pos[0];
+                let len = chunk.len().min(texture.size[0].saturating_sub(pos[0]));
+                texture.texels[start..start + len].copy_from_slice(&chunk[..len]);
+            }
+        } else {
+            self.textures.insert(
+                id,
+                SoftwareTexture {
+                    size,
+                    texels,
+                    filter: image_delta.options.magnification,
+                },
+            );
+        }
+    }
+
+    /// Draws the retained node meshes in `items`, followed by the immediate `primitives`.
+    /// The color pass ends up in [`Self::pixmap`], the picking pass in the id buffer read by
+    /// [`Self::render_picking`].
+    pub fn render(
+        &mut self,
+        items: Vec<RadiantDrawItem>,
+        primitives: Vec<ClippedPrimitive>,
+        screen_descriptor: &ScreenDescriptor,
+        selection: bool,
+    ) {
+        self.resize(screen_descriptor.size_in_pixels);
+        let pixels_per_point = screen_descriptor.pixels_per_point;
+
+        if selection {
+            self.picking_retained.update(items, pixels_per_point);
+            self.ids.fill(0);
+            let mut ids = std::mem::take(&mut self.ids);
+            for primitive in self.picking_retained.iter().chain(primitives.iter()) {
+                if let Primitive::Mesh(mesh) = &primitive.primitive {
+                    self.rasterize_ids(&mut ids, mesh, primitive.clip_rect, pixels_per_point);
+                }
+            }
+            self.ids = ids;
+        } else {
+            self.retained.update(items, pixels_per_point);
+            self.color_buffer.fill(self.clear_color);
+            let mut color_buffer = std::mem::take(&mut self.color_buffer);
+            for primitive in self.retained.iter().chain(primitives.iter()) {
+                if let Primitive::Mesh(mesh) = &primitive.primitive {
+                    self.rasterize_color(
+                        &mut color_buffer,
+                        mesh,
+                        primitive.clip_rect,
+                        pixels_per_point,
+                    );
+                }
+            }
+            for (pixel, color) in self.pixmap.pixels.iter_mut().zip(color_buffer.iter()) {
+                *pixel = (*color).into();
+            }
+            self.color_buffer = color_buffer;
+        }
+    }
+
+    /// Renders the picking pass and returns the ids inside `rect` (`[min_x, min_y, max_x,
+    /// max_y]` in physical pixels, max exclusive), clamped to the screen.
+    pub fn render_picking(
+        &mut self,
+        items: Vec<RadiantDrawItem>,
+        primitives: Vec<ClippedPrimitive>,
+        screen_descriptor: &ScreenDescriptor,
+        rect: [u32; 4],
+    ) -> RadiantPickBuffer {
+        self.render(items, primitives, screen_descriptor, true);
+
+        let [screen_width, screen_height] = self.size;
+        let min_x = rect[0].min(screen_width);
+        let min_y = rect[1].min(screen_height);
+        let width = rect[2].clamp(min_x, screen_width) - min_x;
+        let height = rect[3].clamp(min_y, screen_height) - min_y;
+
+        let mut ids = Vec::with_capacity((width * height) as usize);
+        for y in min_y..min_y + height {
+            let start = (y * screen_width + min_x) as usize;
+            ids.extend_from_slice(&self.ids[start..start + width as usize]);
+        }
+        RadiantPickBuffer::new([min_x, min_y], [width, height], ids)
+    }
+
+    fn rasterize_color(
+        &self,
+        buffer: &mut [Rgba],
+        mesh: &Mesh,
+        clip_rect: Rect,
+        pixels_per_point: f32,
+    ) {
+        let Some(texture) = self.textures.get(&mesh.texture_id) else {
+            return;
+        };
+        let clip = self.clip_in_pixels(clip_rect, pixels_per_point);
+        for triangle in mesh.indices.chunks_exact(3) {
+            let vertices = [
+                &mesh.vertices[triangle[0] as usize],
+                &mesh.vertices[triangle[1] as usize],
+                &mesh.vertices[triangle[2] as usize],
+            ];
+            let colors = vertices.map(|vertex| Rgba::from(vertex.color));
+            let positions = vertices.map(|vertex| {
+                [
+                    vertex.pos.x * pixels_per_point,
+                    vertex.pos.y * pixels_per_point,
+                ]
+            });
+            let width = self.size[0] as usize;
+            for_each_pixel(clip, positions, |[x, y], weights| {
+                let color =
+                    colors[0] * weights[0] + colors[1] * weights[1] + colors[2] * weights[2];
+                let uv = [
+                    vertices[0].uv.x * weights[0]
+                        + vertices[1].uv.x * weights[1]
+                        + vertices[2].uv.x * weights[2],
+                    vertices[0].uv.y * weights[0]
+                        + vertices[1].uv.y * weights[1]
+                        + vertices[2].uv.y * weights[2],
+                ];
+                let source = color * texture.sample(uv);
+                let destination = &mut buffer[y * width + x];
+                *destination = source + *destination * (1.0 - source.a());
+            });
+        }
+    }
+
+    /// Writes the flat pick id of each triangle, like the picking shader.
+    fn rasterize_ids(&self, ids: &mut [u32], mesh: &Mesh, clip_rect: Rect, pixels_per_point: f32) {
+        let clip = self.clip_in_pixels(clip_rect, pixels_per_point);
+        for triangle in mesh.indices.chunks_exact(3) {
+            let vertices = [
+                &mesh.vertices[triangle[0] as usize],
+                &mesh.vertices[triangle[1] as usize],
+                &mesh.vertices[triangle[2] as usize],
+            ];
+            let pick_id = u32::from_le_bytes(vertices[0].color.to_array());
+            if pick_id == 0 {
+                continue;
+            }
+            let positions = vertices.map(|vertex| {
+                [
+                    vertex.pos.x * pixels_per_point,
+                    vertex.pos.y * pixels_per_point,
+                ]
+            });
+            let width = self.size[0] as usize;
+            for_each_pixel(clip, positions, |[x, y], _weights| {
+                ids[y * width + x] = pick_id;
+            });
+        }
+    }
+
+    fn clip_in_pixels(&self, clip_rect: Rect, pixels_per_point: f32) -> [usize; 4] {
+        let to_pixel = |value: f32, max: u32| {
+            (value * pixels_per_point).round().clamp(0.0, max as f32) as usize
+        };
+        [
+            to_pixel(clip_rect.min.x, self.size[0]),
+            to_pixel(clip_rect.min.y, self.size[1]),
+            to_pixel(clip_rect.max.x, self.size[0]),
+            to_pixel(clip_rect.max.y, self.size[1]),
+        ]
+    }
+}
+
+/// Calls `f` with the barycentric weights of every pixel center inside the triangle and
+/// `clip` (`[min_x, min_y, max_x, max_y]`, max exclusive).
+fn for_each_pixel(
+    clip: [usize; 4],
+    positions: [[f32; 2]; 3],
+    mut f: impl FnMut([usize; 2], [f32; 3]),
+) {
+    let [a, b, c] = positions;
+    let edge = |p: [f32; 2], q: [f32; 2], r: [f32; 2]| {
+        (q[0] - p[0]) * (r[1] - p[1]) - (q[1] - p[1]) * (r[0] - p[0])
+    };
+    let area = edge(a, b, c);
+    if area == 0.0 || !area.is_finite() {
+        return;
+    }
+
+    let min_x = (a[0].min(b[0]).min(c[0]).floor().max(0.0) as usize).max(clip[0]);
+    let min_y = (a[1].min(b[1]).min(c[1]).floor().max(0.0) as usize).max(clip[1]);
+    let max_x = (a[0].max(b[0]).max(c[0]).ceil().max(0.0) as usize).min(clip[2]);
+    let max_y = (a[1].max(b[1]).max(c[1]).ceil().max(0.0) as usize).min(clip[3]);
+
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let point = [x as f32 + 0.5, y as f32 + 0.5];
+            let weights = [
+                edge(b, c, point) / area,
+                edge(c, a, point) / area,
+                edge(a, b, point) / area,
+            ];
+            if weights.iter().all(|weight| *weight >= 0.0) {
+                f([x, y], weights);
+            }
+        }
+    }
+}
@@ -1,24 +1,67 @@
 use std::sync::Arc;
 
 use crate::{
-    ColorComponent, RadiantDocumentNode, RadiantInteractionManager, RadiantNode,
-    RadiantRenderManager, RadiantSceneMessage, RadiantSceneResponse, RadiantTessellatable,
-    RadiantTextureManager, RadiantToolManager, ScreenDescriptor, SelectionTool, TransformComponent,
+    apply_path_edit, encode_pixmap, nearest_path_segment, primitives_bounds, translate_primitives,
+    write_pdf, write_svg, ColorComponent, RadiantCursor, RadiantDocumentNode, RadiantDrawItem,
+    RadiantDrawList, RadiantExportBackground, RadiantExportError, RadiantExportTarget,
+    RadiantFontError, RadiantFontRegistry, RadiantFrameStats, RadiantImageExportOptions,
+    RadiantInteractionManager, RadiantNode, RadiantPathEdit, RadiantPickBuffer, RadiantPixmap,
+    RadiantRenderBackend, RadiantRenderManager, RadiantSceneMessage, RadiantSceneResponse,
+    RadiantSoftwareRenderer, RadiantTextureManager, RadiantToolManager, RadiantVectorNode,
+    ScreenDescriptor, SelectionTool, TransformComponent, MAX_EXPORT_SIDE,
 };
-use epaint::{text::FontDefinitions, ClippedPrimitive, Fonts, TextureId};
+use epaint::{
+    text::FontDefinitions, textures::TexturesDelta, ClippedPrimitive, ClippedShape, Fonts,
+    Primitive, Rgba, TextureId,
+};
+use once_cell::sync::Lazy;
 use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use uuid::Uuid;
 
+pub const DEFAULT_PICK_RADIUS: f32 = 4.0;
+pub const DEFAULT_HOVER_THROTTLE: f32 = 2.0;
+/// Width and height, in pixels, of the tiles image exports are rendered in.
+const EXPORT_TILE_SIDE: u32 = 1024;
+
+static OVERLAY_ID: Lazy<Uuid> = Lazy::new(Uuid::new_v4);
+
 pub struct RadiantScene<M, N: RadiantNode> {
     pub document: Arc<RwLock<RadiantDocumentNode<N>>>,
 
     pub screen_descriptor: ScreenDescriptor,
 
     pub fonts_manager: epaint::Fonts,
-    pub render_manager: RadiantRenderManager,
+    /// Fonts the `fonts_manager` was built from, embedded in PDF exports.
+    font_definitions: FontDefinitions,
+    font_registry: RadiantFontRegistry,
+    /// Use [`Self::gpu_render_manager`] for the [`RadiantRenderManager`] of scenes on the GPU.
+    pub render_manager: RadiantRenderBackend,
+    /// Renders exports offscreen. Keeps a copy of every image texture for that.
+    export_renderer: RadiantSoftwareRenderer,
+    /// Fonts laid out at the scale of the last image export.
+    export_fonts: Option<Fonts>,
     pub tool_manager: RadiantToolManager<M>,
     pub interaction_manager: RadiantInteractionManager<M>,
     pub texture_manager: RadiantTextureManager,
+
+    /// How far (in points) from the cursor a node can be and still be picked.
+    pub pick_radius: f32,
+    /// How far (in points) the cursor has to travel before hover is picked again.
+    pub hover_throttle: f32,
+    last_hover_position: Option<[f32; 2]>,
+    /// Bumped whenever the scene handles a message or gets a node, so that picks cached
+    /// against it know to pick again.
+    revision: u64,
+    /// Cursor declared under the last position [`Self::cursor`] was asked for, and the
+    /// revision it was picked at.
+    last_cursor: Option<([f32; 2], u64, Option<RadiantCursor>)>,
+    /// Node that took the last press, and where the pointer has been dragged to since.
+    press: Option<(Uuid, [f32; 2])>,
+    /// Scale the document nodes were last tessellated at.
+    pixels_per_point: f32,
+    /// Last overlay of the active tool, retained like a node while it stays the same.
+    overlay: Vec<ClippedShape>,
+    overlay_revision: u64,
 }
 
 impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode>
@@ -38,30 +81,150 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
         device: wgpu::Device,
         queue: wgpu::Queue,
         screen_descriptor: ScreenDescriptor,
+    ) -> Self {
+        let render_manager = RadiantRenderManager::new(config, surface, device, queue, None);
+        Self::with_backend(
+            RadiantRenderBackend::Gpu(Box::new(render_manager)),
+            screen_descriptor,
+        )
+    }
+
+    /// A scene rendered on the CPU, for snapshot tests and server-side rendering.
+    pub fn new_headless(screen_descriptor: ScreenDescriptor) -> Self {
+        let renderer = RadiantSoftwareRenderer::new(screen_descriptor.size_in_pixels);
+        Self::with_backend(
+            RadiantRenderBackend::Software(Box::new(renderer)),
+            screen_descriptor,
+        )
+    }
+
+    pub fn with_backend(
+        render_backend: RadiantRenderBackend,
+        screen_descriptor: ScreenDescriptor,
     ) -> Self {
         let font_definitions = FontDefinitions::default();
-        let fonts_manager = Fonts::new(screen_descriptor.pixels_per_point, 1600, font_definitions);
+        let fonts_manager = Fonts::new(
+            screen_descriptor.pixels_per_point,
+            1600,
+            font_definitions.clone(),
+        );
         let texture_manager = RadiantTextureManager::default();
-        let render_manager = RadiantRenderManager::new(config, surface, device, queue, None);
+
+        let mut document = RadiantDocumentNode::new();
+        let mut interaction_manager = RadiantInteractionManager::new();
+        interaction_manager.assign_pick_ids(document.picking_ids_mut());
 
         Self {
-            document: Arc::new(RwLock::new(RadiantDocumentNode::new())),
+            document: Arc::new(RwLock::new(document)),
 
             screen_descriptor,
 
             fonts_manager,
-            render_manager,
+            font_definitions,
+            font_registry: RadiantFontRegistry::new(),
+            render_manager: render_backend,
+            export_renderer: RadiantSoftwareRenderer::new([0, 0]),
+            export_fonts: None,
             tool_manager: RadiantToolManager::new(0u32, Box::new(SelectionTool::new())),
-            interaction_manager: RadiantInteractionManager::new(),
+            interaction_manager,
             texture_manager,
+
+            pick_radius: DEFAULT_PICK_RADIUS,
+            hover_throttle: DEFAULT_HOVER_THROTTLE,
+            last_hover_position: None,
+            revision: 0,
+            last_cursor: None,
+            press: None,
+            pixels_per_point: screen_descriptor.pixels_per_point,
+            overlay: Vec::new(),
+            overlay_revision: 0,
+        }
+    }
+
+    /// The GPU render manager, unless the scene renders in software.
+    pub fn gpu_render_manager(&self) -> Option<&RadiantRenderManager> {
+        match &self.render_manager {
+            RadiantRenderBackend::Gpu(render_manager) => Some(render_manager),
+            RadiantRenderBackend::Software(_) => None,
+        }
+    }
+
+    pub fn gpu_render_manager_mut(&mut self) -> Option<&mut RadiantRenderManager> {
+        match &mut self.render_manager {
+            RadiantRenderBackend::Gpu(render_manager) => Some(render_manager),
+            RadiantRenderBackend::Software(_) => None,
+        }
+    }
+
+    /// The software renderer, whose pixmap holds the last rendered frame.
+    pub fn software_renderer(&self) -> Option<&RadiantSoftwareRenderer> {
+        match &self.render_manager {
+            RadiantRenderBackend::Gpu(_) => None,
+            RadiantRenderBackend::Software(renderer) => Some(renderer),
         }
     }
 
     pub fn add(&mut self, mut node: N) {
+        self.revision += 1;
         node.attach(&self.screen_descriptor);
+        node.fit_content(&self.fonts_manager);
         self.document_mut().add(node);
     }
 
+    pub fn font_registry(&self) -> &RadiantFontRegistry {
+        &self.font_registry
+    }
+
+    /// Adds a TrueType or OpenType font to `family` and redraws text with it.
+    pub fn load_font(
+        &mut self,
+        family: &str,
+        name: &str,
+        data: Vec<u8>,
+    ) -> Result<(), RadiantFontError> {
+        self.font_registry.load(family, name, data)?;
+        self.rebuild_fonts();
+        Ok(())
+    }
+
+    /// Sets the families tried for glyphs missing from `family` and redraws text with them.
+    pub fn set_font_fallbacks(
+        &mut self,
+        family: &str,
+        fallbacks: Vec<String>,
+    ) -> Result<(), RadiantFontError> {
+        self.font_registry.set_fallbacks(family, fallbacks)?;
+        self.rebuild_fonts();
+        Ok(())
+    }
+
+    /// Recreates the fonts from the registry, unless the fonts they define did not change.
+    fn rebuild_fonts(&mut self) {
+        let font_definitions = self.font_registry.font_definitions();
+        if font_definitions == self.font_definitions {
+            return;
+        }
+        self.font_definitions = font_definitions;
+        self.fonts_manager = Fonts::new(
+            self.screen_descriptor.pixels_per_point,
+            1600,
+            self.font_definitions.clone(),
+        );
+        self.export_fonts = None;
+        // Text is measured in the new fonts.
+        for node in self.document().export_nodes(RadiantExportTarget::Document) {
+            node.write().fit_content(&self.fonts_manager);
+        }
+        self.document_mut().invalidate();
+    }
+
+    /// Caret of the selected node while it is edited, for input methods to compose at.
+    pub fn ime_cursor_area(&self) -> Option<[f32; 4]> {
+        let document = self.document();
+        let node = document.get_node(document.selected_node_id?)?;
+        node.ime_cursor_area()
+    }
+
     pub fn resize(&mut self, new_size: [u32; 2]) {
         if new_size[0] > 0 && new_size[1] > 0 {
             self.screen_descriptor.size_in_pixels = new_size;
@@ -70,6 +233,14 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
     }
 
     pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
+        self.update_textures();
+
+        let (items, primitives) = self.get_draw_items(false);
+        self.render_manager
+            .render(items, primitives, &self.screen_descriptor, false)
+    }
+
+    fn update_textures(&mut self) {
         self.fonts_manager
             .begin_frame(self.screen_descriptor.pixels_per_point, 1024);
         if let Some(font_image_delta) = self.fonts_manager.font_image_delta() {
@@ -80,36 +251,335 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
         }
 
         let delta = self.texture_manager.0.write().take_delta();
+        // Exports lay out text with their own fonts, so they only need the images.
+        self.export_renderer.update_textures(TexturesDelta {
+            set: delta
+                .set
+                .iter()
+                .filter(|(id, _)| *id != TextureId::default())
+                .cloned()
+                .collect(),
+            free: delta.free.clone(),
+        });
         self.render_manager.update_textures(delta);
+    }
 
-        let primitives = self.get_primitives(false);
-        self.render_manager
-            .render(primitives, &self.screen_descriptor, false)
+    /// Renders `options.target` offscreen at `options.scale` pixels per point, cropped to
+    /// what it draws, and encodes it. The window size plays no part.
+    pub fn export_image(
+        &mut self,
+        options: &RadiantImageExportOptions,
+    ) -> Result<Vec<u8>, RadiantExportError> {
+        if !(options.scale > 0.0 && options.scale.is_finite()) {
+            return Err(RadiantExportError::InvalidScale {
+                scale: options.scale,
+            });
+        }
+        self.update_textures();
+
+        let mut primitives = self.tessellate_for_export(options.target, options.scale);
+        let bounds = primitives_bounds(&primitives).ok_or(RadiantExportError::EmptyTarget)?;
+        // Snap the origin to the pixel grid so that edges stay crisp.
+        let origin = epaint::pos2(
+            (bounds.min.x * options.scale).floor() / options.scale,
+            (bounds.min.y * options.scale).floor() / options.scale,
+        );
+        let size = [
+            ((bounds.max.x - origin.x) * options.scale).ceil() as u32,
+            ((bounds.max.y - origin.y) * options.scale).ceil() as u32,
+        ];
+        if size[0] > MAX_EXPORT_SIDE || size[1] > MAX_EXPORT_SIDE {
+            return Err(RadiantExportError::TooLarge { size });
+        }
+        translate_primitives(&mut primitives, origin);
+
+        self.export_renderer.clear_color = match options.background {
+            RadiantExportBackground::Transparent => Rgba::TRANSPARENT,
+            RadiantExportBackground::Filled { color } => color.into(),
+        };
+        // Rendering in tiles bounds the memory the renderer needs to a tile.
+        let mut pixmap = RadiantPixmap::new(size);
+        for y in (0..size[1]).step_by(EXPORT_TILE_SIDE as usize) {
+            for x in (0..size[0]).step_by(EXPORT_TILE_SIDE as usize) {
+                let tile_origin = epaint::pos2(x as f32 / options.scale, y as f32 / options.scale);
+                let tile_size = [
+                    EXPORT_TILE_SIDE.min(size[0] - x),
+                    EXPORT_TILE_SIDE.min(size[1] - y),
+                ];
+                let tile_rect = epaint::Rect::from_min_size(
+                    tile_origin,
+                    epaint::vec2(tile_size[0] as f32, tile_size[1] as f32) / options.scale,
+                );
+                let mut tile = primitives
+                    .iter()
+                    .filter(|primitive| match &primitive.primitive {
+                        Primitive::Mesh(mesh) => mesh.calc_bounds().intersects(tile_rect),
+                        Primitive::Callback(_) => false,
+                    })
+                    .cloned()
+                    .collect::<Vec<_>>();
+                translate_primitives(&mut tile, tile_origin);
+
+                let screen_descriptor = ScreenDescriptor {
+                    size_in_pixels: tile_size,
+                    pixels_per_point: options.scale,
+                };
+                self.export_renderer
+                    .render(Vec::new(), tile, &screen_descriptor, false);
+                pixmap.copy_from(self.export_renderer.pixmap(), [x, y]);
+            }
+        }
+        encode_pixmap(&pixmap, options.format)
+    }
+
+    /// Tessellates the nodes of `target` at `scale` pixels per point, with fonts laid out for
+    /// that scale. The nodes tessellate again at the screen scale when they are next drawn.
+    fn tessellate_for_export(
+        &mut self,
+        target: RadiantExportTarget,
+        scale: f32,
+    ) -> Vec<ClippedPrimitive> {
+        let fonts = match self.export_fonts.take() {
+            Some(fonts) if fonts.pixels_per_point() == scale => fonts,
+            _ => Fonts::new(scale, 1600, self.font_definitions.clone()),
+        };
+        fonts.begin_frame(scale, 1600);
+        let screen_descriptor = ScreenDescriptor {
+            size_in_pixels: self.screen_descriptor.size_in_pixels,
+            pixels_per_point: scale,
+        };
+
+        let mut primitives = Vec::new();
+        for node in self.document().export_nodes(target) {
+            let mut node = node.write();
+            node.set_needs_tessellation(false);
+            primitives.append(&mut node.tessellate(false, &screen_descriptor, &fonts));
+            node.set_needs_tessellation(false);
+        }
+
+        if let Some(font_image_delta) = fonts.font_image_delta() {
+            self.export_renderer
+                .update_texture(TextureId::default(), &font_image_delta);
+        }
+        self.export_fonts = Some(fonts);
+        primitives
+    }
+
+    /// Writes `target` as an SVG document, with each node as a native SVG element.
+    pub fn export_svg(
+        &mut self,
+        target: RadiantExportTarget,
+    ) -> Result<String, RadiantExportError> {
+        let nodes = self.vector_nodes(target);
+        write_svg(&nodes, |id| self.export_renderer.texture_pixmap(id))
+    }
+
+    /// Writes every artboard with content as a page of a PDF document.
+    pub fn export_pdf(&mut self) -> Result<Vec<u8>, RadiantExportError> {
+        let pages = self.vector_nodes(RadiantExportTarget::Document);
+        write_pdf(&pages, &self.font_definitions, |id| {
+            self.export_renderer.texture_pixmap(id)
+        })
+    }
+
+    fn vector_nodes(&mut self, target: RadiantExportTarget) -> Vec<RadiantVectorNode> {
+        self.update_textures();
+
+        // Tessellating brings lazily computed bounds, such as those of text, up to date.
+        for node in self.document().export_nodes(target) {
+            node.write()
+                .tessellate(false, &self.screen_descriptor, &self.fonts_manager);
+        }
+        self.document().vector_nodes(target)
+    }
+
+    pub fn set_pick_radius(&mut self, pick_radius: f32) {
+        self.pick_radius = pick_radius.max(0.0);
+    }
+
+    pub fn hovered_node_id(&self) -> Option<Uuid> {
+        self.interaction_manager.hovered_node_id()
     }
 
+    /// Updates the hovered node for a cursor at `position`. Returns the new hovered node when
+    /// it changed, or `None` when nothing changed or the move was throttled.
+    pub fn hover(&mut self, position: [f32; 2]) -> Option<Option<Uuid>> {
+        if let Some(last) = self.last_hover_position {
+            let dx = position[0] - last[0];
+            let dy = position[1] - last[1];
+            if dx * dx + dy * dy < self.hover_throttle * self.hover_throttle {
+                return None;
+            }
+        }
+        self.last_hover_position = Some(position);
+
+        let document = self.document.read();
+        let id = document.hit_test(position, self.pick_radius);
+        if id == self.interaction_manager.hovered_node_id() {
+            return None;
+        }
+        match id.and_then(|id| document.get_node(id)) {
+            Some(node) => self.interaction_manager.enable_hover(&*node),
+            None => self.interaction_manager.disable_hover(),
+        }
+        Some(id)
+    }
+
+    /// Number of nodes drawn and culled in the last rendered frame.
+    pub fn frame_stats(&self) -> RadiantFrameStats {
+        self.document().frame_stats()
+    }
+
+    /// Topmost node within [`RadiantScene::pick_radius`] of `mouse_position`.
     pub async fn select(&mut self, mouse_position: [f32; 2]) -> Option<Uuid> {
-        let primitives = self.get_primitives(true);
+        let pixels_per_point = self.screen_descriptor.pixels_per_point;
+        let x = (mouse_position[0] * pixels_per_point).max(0.0) as u32;
+        let y = (mouse_position[1] * pixels_per_point).max(0.0) as u32;
+        let radius = (self.pick_radius * pixels_per_point).ceil() as u32;
+        let pick_buffer = self
+            .pick([
+                x.saturating_sub(radius),
+                y.saturating_sub(radius),
+                x + radius + 1,
+                y + radius + 1,
+            ])
+            .await?;
+
+        let document = self.document();
+        let ids: Vec<Uuid> = pick_buffer
+            .ids_within([x, y], radius)
+            .into_iter()
+            .filter_map(|pick_id| document.picking_ids().node(pick_id))
+            .collect();
+        self.interaction_manager
+            .topmost(&ids)
+            .or_else(|| document.topmost(ids))
+    }
+
+    /// Nodes visible inside `rect` (`[min_x, min_y, max_x, max_y]` in points), for marquee
+    /// selection. Only the topmost node is seen at each pixel.
+    pub async fn select_rect(&mut self, rect: [f32; 4]) -> Vec<Uuid> {
+        let pixels_per_point = self.screen_descriptor.pixels_per_point;
+        let rect = [
+            (rect[0].min(rect[2]) * pixels_per_point).max(0.0) as u32,
+            (rect[1].min(rect[3]) * pixels_per_point).max(0.0) as u32,
+            (rect[0].max(rect[2]) * pixels_per_point).max(0.0).ceil() as u32,
+            (rect[1].max(rect[3]) * pixels_per_point).max(0.0).ceil() as u32,
+        ];
+        let Some(pick_buffer) = self.pick(rect).await else {
+            return Vec::new();
+        };
+        let document = self.document();
+        pick_buffer
+            .unique_ids()
+            .into_iter()
+            .filter_map(|pick_id| document.picking_ids().node(pick_id))
+            .collect()
+    }
+
+    async fn pick(&mut self, rect: [u32; 4]) -> Option<RadiantPickBuffer> {
+        let (items, primitives) = self.get_draw_items(true);
         self.render_manager
-            .render_offscreen(primitives, &self.screen_descriptor, true, mouse_position)
+            .render_picking(items, primitives, &self.screen_descriptor, rect)
             .await
-            .unwrap()
+            .ok()
     }
 
-    fn get_primitives(&mut self, selection: bool) -> Vec<ClippedPrimitive> {
-        let mut primitives = self.document.write().tessellate(
+    /// Geometric counterpart to [`RadiantScene::select`] that needs no GPU readback.
+    pub fn hit_test(&self, position: [f32; 2]) -> Option<Uuid> {
+        self.interaction_manager
+            .hit_test(position, self.pick_radius)
+            .or_else(|| self.document().hit_test(position, self.pick_radius))
+    }
+
+    /// Cursor for the active tool with the mouse at `position`. Interaction handles and nodes
+    /// under the mouse declare the cursor they want, and the tool decides whether to use it.
+    /// Only hit-tests again when the mouse moved or the scene changed since the last call.
+    pub fn cursor(&mut self, position: [f32; 2]) -> RadiantCursor {
+        let hovered = match self.last_cursor {
+            Some((last, revision, hovered)) if last == position && revision == self.revision => {
+                hovered
+            }
+            _ => {
+                let hovered = self.hit_test(position).and_then(|id| {
+                    self.interaction_manager
+                        .cursor(id)
+                        .or_else(|| self.document().get_node(id).map(|node| node.cursor()))
+                });
+                self.last_cursor = Some((position, self.revision, hovered));
+                hovered
+            }
+        };
+        self.tool_manager.active_tool().cursor(hovered)
+    }
+
+    /// Every node under `position`, interactions first, then document nodes topmost first.
+    pub fn hit_stack(&self, position: [f32; 2]) -> Vec<Uuid> {
+        let mut stack: Vec<Uuid> = self
+            .interaction_manager
+            .hit_test(position, self.pick_radius)
+            .into_iter()
+            .collect();
+        stack.append(&mut self.document().hit_stack(position, self.pick_radius));
+        stack
+    }
+
+    /// Document nodes and the tool overlay as retained draw items, plus the immediate
+    /// interaction primitives drawn on top of them.
+    fn get_draw_items(&mut self, selection: bool) -> (Vec<RadiantDrawItem>, Vec<ClippedPrimitive>) {
+        let pixels_per_point = self.screen_descriptor.pixels_per_point;
+        if self.pixels_per_point != pixels_per_point {
+            self.pixels_per_point = pixels_per_point;
+            self.document.write().invalidate();
+        }
+
+        if selection {
+            // Path edit handles are rebuilt with the path, so they get their pick ids here.
+            let mut document = self.document.write();
+            self.interaction_manager
+                .assign_pick_ids(document.picking_ids_mut());
+        }
+
+        let mut draw_list = RadiantDrawList::new(
+            self.render_manager
+                .uploaded_revisions(selection, pixels_per_point),
+        );
+        self.document.write().collect(
             selection,
             &self.screen_descriptor,
             &self.fonts_manager,
+            &mut draw_list,
         );
 
-        let mut p2 = self.interaction_manager.tessellate(
+        if !selection {
+            let overlay = self.tool_manager.active_tool().overlay();
+            if overlay != self.overlay {
+                self.overlay = overlay;
+                self.overlay_revision += 1;
+            }
+            if !self.overlay.is_empty() {
+                let primitives = draw_list
+                    .needs_upload(*OVERLAY_ID, self.overlay_revision)
+                    .then(|| {
+                        epaint::tessellator::tessellate_shapes(
+                            pixels_per_point,
+                            epaint::TessellationOptions::default(),
+                            [1, 1],
+                            vec![],
+                            self.overlay.clone(),
+                        )
+                    });
+                draw_list.push(*OVERLAY_ID, self.overlay_revision, primitives);
+            }
+        }
+
+        let primitives = self.interaction_manager.tessellate(
             selection,
             &self.screen_descriptor,
             &self.fonts_manager,
         );
-        primitives.append(&mut p2);
 
-        primitives
+        (draw_list.into_items(), primitives)
     }
 }
 
@@ -120,6 +590,7 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
         &mut self,
         message: RadiantSceneMessage,
     ) -> Option<RadiantSceneResponse<M, N>> {
+        self.revision += 1;
         match message {
             RadiantSceneMessage::AddArtboard {} => {
                 self.document_mut().add_artboard();
@@ -128,6 +599,16 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
                 self.document_mut().set_active_artboard(id);
             }
             RadiantSceneMessage::SelectNode { id } => {
+                self.press = None;
+                let previous = self
+                    .document()
+                    .selected_node_id
+                    .filter(|node| Some(*node) != id);
+                if let Some(previous) = previous {
+                    if let Some(mut node) = self.document.write().get_node_mut(previous) {
+                        node.end_editing();
+                    }
+                }
                 self.document_mut().select(id);
                 if let Some(id) = id {
                     if !self.interaction_manager.is_interaction(id) {
@@ -140,17 +621,40 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
                         } else {
                             self.interaction_manager.disable_interactions();
                         }
+                    } else {
+                        self.interaction_manager.select_interaction(id);
                     }
                 } else {
                     self.interaction_manager.disable_interactions();
                 }
             }
+            RadiantSceneMessage::PressNode { id, position } => {
+                let selected = self.document().selected_node_id;
+                if let Some(id) = id.filter(|id| selected == Some(*id)) {
+                    if let Some(mut node) = self.document.write().get_node_mut(id) {
+                        if node.handle_press(position, false) {
+                            self.press = Some((id, position));
+                            return None;
+                        }
+                    }
+                }
+                return self.handle_message(RadiantSceneMessage::SelectNode { id });
+            }
             RadiantSceneMessage::TransformNode {
                 id,
                 position,
                 scale,
             } => {
-                if self.interaction_manager.is_interaction(id) {
+                let press = self.press.as_mut().filter(|(press_id, _)| *press_id == id);
+                if let Some((_, pointer)) = press {
+                    // Dragging a press the node took moves the pointer within it, not the node.
+                    pointer[0] += position[0];
+                    pointer[1] += position[1];
+                    let pointer = *pointer;
+                    if let Some(mut node) = self.document.write().get_node_mut(id) {
+                        node.handle_press(pointer, true);
+                    }
+                } else if self.interaction_manager.is_interaction(id) {
                     if let Some(message) =
                         self.interaction_manager.handle_interaction(message.into())
                     {
@@ -160,14 +664,15 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
                     if let Some(component) = node.get_component_mut::<TransformComponent>() {
                         component.transform_xy(&position.into());
                         component.transform_scale(&scale.into());
+                        node.set_needs_tessellation(true);
+                        node.fit_content(&self.fonts_manager);
 
+                        let transform = node.transform();
                         let response = RadiantSceneResponse::TransformUpdated {
                             id,
-                            position: component.position().into(),
-                            scale: component.scale().into(),
+                            position: transform.position().into(),
+                            scale: transform.scale().into(),
                         };
-
-                        node.set_needs_tessellation(true);
                         self.interaction_manager
                             .update_interactions(node, &self.screen_descriptor);
 
@@ -175,6 +680,49 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
                     }
                 }
             }
+            RadiantSceneMessage::RemoveNode { id } => {
+                let selected = self.document().selected_node_id == Some(id)
+                    || self.interaction_manager.path_edit_node_id() == Some(id);
+                if self.document_mut().remove(id).is_some() {
+                    if selected {
+                        self.interaction_manager.disable_interactions();
+                    }
+                    if self.interaction_manager.hovered_node_id() == Some(id) {
+                        self.interaction_manager.disable_hover();
+                        self.last_hover_position = None;
+                    }
+                    return Some(RadiantSceneResponse::Removed { id });
+                }
+            }
+            RadiantSceneMessage::HoverNode { position } => {
+                if let Some(id) = self.hover(position) {
+                    return Some(RadiantSceneResponse::Hovered { id });
+                }
+            }
+            RadiantSceneMessage::ExportImage { options } => {
+                return Some(match self.export_image(&options) {
+                    Ok(data) => RadiantSceneResponse::ImageExported { data },
+                    Err(error) => RadiantSceneResponse::ExportFailed {
+                        reason: error.to_string(),
+                    },
+                });
+            }
+            RadiantSceneMessage::ExportPdf {} => {
+                return Some(match self.export_pdf() {
+                    Ok(data) => RadiantSceneResponse::PdfExported { data },
+                    Err(error) => RadiantSceneResponse::ExportFailed {
+                        reason: error.to_string(),
+                    },
+                });
+            }
+            RadiantSceneMessage::ExportSvg { target } => {
+                return Some(match self.export_svg(target) {
+                    Ok(svg) => RadiantSceneResponse::SvgExported { svg },
+                    Err(error) => RadiantSceneResponse::ExportFailed {
+                        reason: error.to_string(),
+                    },
+                });
+            }
             RadiantSceneMessage::SetTransform {
                 id,
                 position,
@@ -185,6 +733,7 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
                         component.set_position(&position.into());
                         component.set_scale(&scale.into());
                         node.set_needs_tessellation(true);
+                        node.fit_content(&self.fonts_manager);
 
                         self.interaction_manager
                             .update_interactions(node, &self.screen_descriptor);
@@ -208,15 +757,104 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
                 }
             }
             RadiantSceneMessage::SelectTool { id } => {
+                self.press = None;
                 self.tool_manager.activate_tool(id);
             }
-            RadiantSceneMessage::HandleKey { id, key } => {
+            RadiantSceneMessage::HandleKey { id, key, modifiers } => {
+                if id.is_none() || id == self.interaction_manager.path_edit_node_id() {
+                    if let Some(message) = self.interaction_manager.handle_key(&key) {
+                        return Some(RadiantSceneResponse::Message { message });
+                    }
+                }
                 if let Some(id) = match id {
                     Some(id) => Some(id),
                     None => self.document.read().selected_node_id,
                 } {
                     if let Some(mut node) = self.document.write().get_node_mut(id) {
-                        if node.handle_key_down(key) {
+                        if node.handle_key_down(key, modifiers) {
+                            node.fit_content(&self.fonts_manager);
+                            self.interaction_manager
+                                .update_interactions(node, &self.screen_descriptor);
+                        }
+                    }
+                }
+            }
+            RadiantSceneMessage::HandleIme { id, event } => {
+                let id = id.or(self.document().selected_node_id);
+                if let Some(id) = id {
+                    if let Some(mut node) = self.document.write().get_node_mut(id) {
+                        if node.handle_ime(event) {
+                            node.fit_content(&self.fonts_manager);
+                            self.interaction_manager
+                                .update_interactions(node, &self.screen_descriptor);
+                        }
+                    }
+                }
+            }
+            RadiantSceneMessage::DoubleClickNode { id, position } => {
+                let editing = self.interaction_manager.path_edit_node_id();
+                match id {
+                    Some(id) if self.interaction_manager.is_interaction(id) => {
+                        let index = self
+                            .interaction_manager
+                            .path_edit_interaction
+                            .anchor_index(id);
+                        if let (Some(node_id), Some(index)) = (editing, index) {
+                            return self.handle_message(RadiantSceneMessage::EditPath {
+                                id: node_id,
+                                edit: RadiantPathEdit::ToggleSmooth { index },
+                            });
+                        }
+                    }
+                    Some(id) if editing == Some(id) => {
+                        let edit = self
+                            .document()
+                            .get_node(id)
+                            .and_then(|node| node.path_commands())
+                            .and_then(|commands| nearest_path_segment(&commands, position))
+                            .filter(|(_, _, distance)| *distance <= self.pick_radius)
+                            .map(|(index, t, _)| RadiantPathEdit::InsertPoint { index, t });
+                        if let Some(edit) = edit {
+                            return self.handle_message(RadiantSceneMessage::EditPath { id, edit });
+                        }
+                    }
+                    Some(id) => {
+                        // Nodes with content to edit, like text, take the double click first.
+                        if let Some(mut node) = self.document.write().get_node_mut(id) {
+                            if node.begin_editing(position) {
+                                return None;
+                            }
+                        }
+                        if let Some(node) = self.document.read().get_node(id) {
+                            self.interaction_manager.enable_path_edit(&*node);
+                        }
+                    }
+                    None => {}
+                }
+            }
+            RadiantSceneMessage::LoadFont { family, name, data } => {
+                return Some(match self.load_font(&family, &name, data) {
+                    Ok(()) => RadiantSceneResponse::FontLoaded { family, name },
+                    Err(error) => RadiantSceneResponse::FontFailed {
+                        reason: error.to_string(),
+                    },
+                });
+            }
+            RadiantSceneMessage::SetFontFallbacks { family, fallbacks } => {
+                if let Err(error) = self.set_font_fallbacks(&family, fallbacks) {
+                    return Some(RadiantSceneResponse::FontFailed {
+                        reason: error.to_string(),
+                    });
+                }
+            }
+            RadiantSceneMessage::EditPath { id, edit } => {
+                if let Some(mut node) = self.document.write().get_node_mut(id) {
+                    let commands = node
+                        .path_commands()
+                        .and_then(|commands| apply_path_edit(&commands, edit));
+                    if let Some(commands) = commands {
+                        if node.set_path_commands(commands) {
+                            node.set_needs_tessellation(true);
                             self.interaction_manager
                                 .update_interactions(node, &self.screen_descriptor);
                         }
@@ -0,0 +1,109 @@
+use rstar::{primitives::GeomWithData, primitives::Rectangle, RTree, AABB};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+type SpatialEntry = GeomWithData<Rectangle<[f32; 2]>, Uuid>;
+
+/// How far entries extend past their bounds, so that zero-size nodes still have an envelope.
+const BOUNDS_EPSILON: f32 = 1e-3;
+
+/// R-tree over node bounding rects (`[min_x, min_y, max_x, max_y]`).
+#[derive(Default)]
+pub struct RadiantSpatialIndex {
+    tree: RTree<SpatialEntry>,
+    bounds: HashMap<Uuid, [f32; 4]>,
+    /// Nodes whose bounds are all zeros, which usually means they were not computed yet.
+    unplaced: HashSet<Uuid>,
+}
+
+impl RadiantSpatialIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bounds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bounds.is_empty()
+    }
+
+    pub fn bounds(&self, id: Uuid) -> Option<[f32; 4]> {
+        self.bounds.get(&id).copied()
+    }
+
+    /// Inserts or moves `id`.
+    pub fn update(&mut self, id: Uuid, rect: [f32; 4]) {
+        if self.bounds.get(&id) == Some(&rect) {
+            return;
+        }
+        self.remove(id);
+        self.tree.insert(Self::entry(id, rect));
+        self.bounds.insert(id, rect);
+        if rect == [0.0, 0.0, 0.0, 0.0] {
+            self.unplaced.insert(id);
+        }
+    }
+
+    pub fn remove(&mut self, id: Uuid) {
+        if let Some(rect) = self.bounds.remove(&id) {
+            self.tree.remove(&Self::entry(id, rect));
+        }
+        self.unplaced.remove(&id);
+    }
+
+    pub fn clear(&mut self) {
+        self.tree = RTree::new();
+        self.bounds.clear();
+        self.unplaced.clear();
+    }
+
+    /// Ids of the nodes whose bounds are all zeros.
+    pub fn unplaced(&self) -> impl Iterator<Item = Uuid> + '_ {
+        self.unplaced.iter().copied()
+    }
+
+    /// Ids of all nodes whose bounds intersect `rect`.
+    pub fn query_rect(&self, rect: [f32; 4]) -> Vec<Uuid> {
+        let envelope = AABB::from_corners([rect[0], rect[1]], [rect[2], rect[3]]);
+        self.tree
+            .locate_in_envelope_intersecting(&envelope)
+            .map(|entry| entry.data)
+            .collect()
+    }
+
+    /// Ids of all nodes whose bounds, grown by `tolerance`, contain `point`.
+    pub fn query_point(&self, point: [f32; 2], tolerance: f32) -> Vec<Uuid> {
+        self.query_rect([
+            point[0] - tolerance,
+            point[1] - tolerance,
+            point[0] + tolerance,
+            point[1] + tolerance,
+        ])
+    }
+
+    pub fn nearest(&self, point: [f32; 2]) -> Option<Uuid> {
+        self.tree.nearest_neighbor(&point).map(|entry| entry.data)
+    }
+
+    /// Ids of the nodes whose bounds are within `max_distance` of `point`, closest first.
+    pub fn nearest_within(&self, point: [f32; 2], max_distance: f32) -> Vec<Uuid> {
+        let max_distance_2 = max_distance * max_distance;
+        self.tree
+            .nearest_neighbor_iter_with_distance_2(&point)
+            .take_while(|(_, distance_2)| *distance_2 <= max_distance_2)
+            .map(|(entry, _)| entry.data)
+            .collect()
+    }
+
+    fn entry(id: Uuid, rect: [f32; 4]) -> SpatialEntry {
+        GeomWithData::new(
+            Rectangle::from_corners(
+                [rect[0] - BOUNDS_EPSILON, rect[1] - BOUNDS_EPSILON],
+                [rect[2] + BOUNDS_EPSILON, rect[3] + BOUNDS_EPSILON],
+            ),
+            id,
+        )
+    }
+}
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// Mouse cursor requested by the active tool, independent of the windowing backend.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RadiantCursor {
+    #[default]
+    Default,
+    Move,
+    Crosshair,
+    Text,
+    ResizeNorth,
+    ResizeEast,
+    ResizeSouth,
+    ResizeWest,
+    ResizeNorthEast,
+    ResizeNorthWest,
+    ResizeSouthEast,
+    ResizeSouthWest,
+}
@@ -1,11 +1,14 @@
+pub mod cursor;
 pub mod rectangle_tool;
 pub mod selection_tool;
 pub mod tool_manager;
 
+pub use cursor::*;
 pub use rectangle_tool::*;
 pub use selection_tool::*;
 pub use tool_manager::*;
 
+use epaint::ClippedShape;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -21,9 +24,31 @@ pub enum KeyCode {
     ArrowLeft,
     ArrowRight,
     ArrowUp,
+    Home,
+    End,
     Char(String),
 }
 
+/// Modifier keys held while a key is pressed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct RadiantKeyModifiers {
+    /// Extends the selection with navigation keys.
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+/// Input from an input method, e.g. for typing CJK text.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RadiantImeEvent {
+    /// Text being composed, shown at the caret but not typed yet. Empty ends the composition.
+    Preedit(String),
+    /// Composed text to type, which ends the composition.
+    Commit(String),
+}
+
 pub trait RadiantTool<M>: Send + Sync {
     fn on_mouse_down(&mut self, _node_id: Option<Uuid>, _position: [f32; 2]) -> Option<M> {
         None
@@ -34,7 +59,20 @@ pub trait RadiantTool<M>: Send + Sync {
     fn on_mouse_up(&mut self, _position: [f32; 2]) -> Option<M> {
         None
     }
-    fn on_key_down(&mut self, _key: KeyCode) -> Option<M> {
+    fn on_key_down(&mut self, _key: KeyCode, _modifiers: RadiantKeyModifiers) -> Option<M> {
         None
     }
+    /// Called after `on_mouse_down` when the press completes a double click. A message
+    /// returned here is sent instead of the mouse down one.
+    fn on_double_click(&mut self, _node_id: Option<Uuid>, _position: [f32; 2]) -> Option<M> {
+        None
+    }
+    /// Cursor to show, given the cursor requested by whatever is under the mouse.
+    fn cursor(&self, _hovered: Option<RadiantCursor>) -> RadiantCursor {
+        RadiantCursor::Default
+    }
+    /// Shapes drawn over the document while the tool is in use. They are never picked.
+    fn overlay(&self) -> Vec<ClippedShape> {
+        Vec::new()
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{RadiantSceneMessage, RadiantTool};
+use crate::{RadiantCursor, RadiantSceneMessage, RadiantTool};
 use macro_magic::export_tokens;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -69,4 +69,8 @@ impl<M: From<RadiantRectangleMessage> + From<RadiantSceneMessage>> RadiantTool<M
         self.prev_position = [0.0, 0.0];
         None
     }
+
+    fn cursor(&self, _hovered: Option<RadiantCursor>) -> RadiantCursor {
+        RadiantCursor::Crosshair
+    }
 }
@@ -1,6 +1,6 @@
 use uuid::Uuid;
 
-use crate::{RadiantSceneMessage, RadiantTool};
+use crate::{RadiantCursor, RadiantSceneMessage, RadiantTool};
 
 pub struct SelectionTool {
     active_node_id: Option<Uuid>,
@@ -23,12 +23,18 @@ impl<M: From<RadiantSceneMessage>> RadiantTool<M> for SelectionTool {
         self.prev_position = position;
         self.is_mouse_down = true;
         self.active_node_id = node_id;
-        Some(RadiantSceneMessage::SelectNode { id: node_id }.into())
+        Some(
+            RadiantSceneMessage::PressNode {
+                id: node_id,
+                position,
+            }
+            .into(),
+        )
     }
 
     fn on_mouse_move(&mut self, position: [f32; 2]) -> Option<M> {
         if !self.is_mouse_down {
-            return None;
+            return Some(RadiantSceneMessage::HoverNode { position }.into());
         }
         let result = if let Some(id) = self.active_node_id {
             let message = RadiantSceneMessage::TransformNode {
@@ -54,7 +60,32 @@ impl<M: From<RadiantSceneMessage>> RadiantTool<M> for SelectionTool {
         None
     }
 
-    fn on_key_down(&mut self, key: crate::KeyCode) -> Option<M> {
-        return Some(RadiantSceneMessage::HandleKey { id: None, key }.into());
+    fn on_double_click(&mut self, node_id: Option<Uuid>, position: [f32; 2]) -> Option<M> {
+        Some(
+            RadiantSceneMessage::DoubleClickNode {
+                id: node_id,
+                position,
+            }
+            .into(),
+        )
+    }
+
+    fn on_key_down(
+        &mut self,
+        key: crate::KeyCode,
+        modifiers: crate::RadiantKeyModifiers,
+    ) -> Option<M> {
+        return Some(
+            RadiantSceneMessage::HandleKey {
+                id: None,
+                key,
+                modifiers,
+            }
+            .into(),
+        );
+    }
+
+    fn cursor(&self, hovered: Option<RadiantCursor>) -> RadiantCursor {
+        hovered.unwrap_or_default()
     }
 }
@@ -0,0 +1,61 @@
+/// Returns true if `point` lies inside `rect` (`[min_x, min_y, max_x, max_y]`), grown by
+/// `tolerance` on every side.
+pub fn rect_contains(rect: [f32; 4], point: [f32; 2], tolerance: f32) -> bool {
+    let min_x = rect[0].min(rect[2]) - tolerance;
+    let min_y = rect[1].min(rect[3]) - tolerance;
+    let max_x = rect[0].max(rect[2]) + tolerance;
+    let max_y = rect[1].max(rect[3]) + tolerance;
+    point[0] >= min_x && point[0] <= max_x && point[1] >= min_y && point[1] <= max_y
+}
+
+pub fn distance_to_segment(point: [f32; 2], start: [f32; 2], end: [f32; 2]) -> f32 {
+    let dx = end[0] - start[0];
+    let dy = end[1] - start[1];
+    let length_sq = dx * dx + dy * dy;
+    let t = if length_sq > 0.0 {
+        (((point[0] - start[0]) * dx + (point[1] - start[1]) * dy) / length_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let x = start[0] + t * dx - point[0];
+    let y = start[1] + t * dy - point[1];
+    (x * x + y * y).sqrt()
+}
+
+/// Even-odd point-in-polygon test. The polygon is implicitly closed.
+pub fn polygon_contains(points: &[[f32; 2]], point: [f32; 2]) -> bool {
+    if points.len() < 3 {
+        return false;
+    }
+    let mut inside = false;
+    let mut j = points.len() - 1;
+    for i in 0..points.len() {
+        let (a, b) = (points[i], points[j]);
+        if (a[1] > point[1]) != (b[1] > point[1])
+            && point[0] < (b[0] - a[0]) * (point[1] - a[1]) / (b[1] - a[1]) + a[0]
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Returns true if `point` is within `tolerance` of any edge of the (closed) polygon.
+pub fn polygon_outline_contains(points: &[[f32; 2]], point: [f32; 2], tolerance: f32) -> bool {
+    if points.is_empty() {
+        return false;
+    }
+    let mut j = points.len() - 1;
+    for i in 0..points.len() {
+        if distance_to_segment(point, points[j], points[i]) <= tolerance {
+            return true;
+        }
+        j = i;
+    }
+    false
+}
+
+pub fn rects_intersect(a: [f32; 4], b: [f32; 4]) -> bool {
+    a[0] <= b[2] && b[0] <= a[2] && a[1] <= b[3] && b[1] <= a[3]
+}
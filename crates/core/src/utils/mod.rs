@@ -1,7 +1,13 @@
 pub mod vec3;
 pub mod atomic;
+pub mod geometry;
 pub mod observer;
+pub mod path_command;
+pub mod path_geometry;
 
 pub use vec3::*;
 pub use atomic::*;
+pub use geometry::*;
 pub use observer::*;
+pub use path_command::*;
+pub use path_geometry::*;
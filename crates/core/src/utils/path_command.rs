@@ -0,0 +1,210 @@
+use serde::{Deserialize, Serialize};
+
+/// How overlapping subpaths decide what is inside a filled path.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RadiantFillRule {
+    #[default]
+    NonZero,
+    EvenOdd,
+}
+
+/// One segment of a path outline. Points are in the path's own coordinates.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum RadiantPathCommand {
+    MoveTo {
+        to: [f32; 2],
+    },
+    LineTo {
+        to: [f32; 2],
+    },
+    QuadTo {
+        control: [f32; 2],
+        to: [f32; 2],
+    },
+    CubicTo {
+        control1: [f32; 2],
+        control2: [f32; 2],
+        to: [f32; 2],
+    },
+    Close,
+}
+
+impl RadiantPathCommand {
+    /// The point the command ends at, if it has one.
+    pub fn end_point(&self) -> Option<[f32; 2]> {
+        match self {
+            Self::MoveTo { to }
+            | Self::LineTo { to }
+            | Self::QuadTo { to, .. }
+            | Self::CubicTo { to, .. } => Some(*to),
+            Self::Close => None,
+        }
+    }
+
+    /// The command with `f` applied to each of its points.
+    pub fn map(self, f: impl Fn([f32; 2]) -> [f32; 2]) -> Self {
+        match self {
+            Self::MoveTo { to } => Self::MoveTo { to: f(to) },
+            Self::LineTo { to } => Self::LineTo { to: f(to) },
+            Self::QuadTo { control, to } => Self::QuadTo {
+                control: f(control),
+                to: f(to),
+            },
+            Self::CubicTo {
+                control1,
+                control2,
+                to,
+            } => Self::CubicTo {
+                control1: f(control1),
+                control2: f(control2),
+                to: f(to),
+            },
+            Self::Close => Self::Close,
+        }
+    }
+}
+
+/// Default distance (in points) flattened curves may stray from the true curve.
+pub const DEFAULT_FLATTENING_TOLERANCE: f32 = 0.1;
+
+/// A subpath flattened to straight segments.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RadiantFlatSubpath {
+    pub points: Vec<[f32; 2]>,
+    pub closed: bool,
+}
+
+/// Flattens `commands` into one polyline per subpath, splitting each curve into as few
+/// segments as keep it within `tolerance`. Subpaths with fewer than two points are dropped.
+pub fn flatten_commands(
+    commands: &[RadiantPathCommand],
+    tolerance: f32,
+) -> Vec<RadiantFlatSubpath> {
+    let mut subpaths = Vec::new();
+    let mut current = RadiantFlatSubpath::default();
+    let mut last = [0.0, 0.0];
+    for command in commands {
+        match *command {
+            RadiantPathCommand::MoveTo { to } => {
+                subpaths.push(std::mem::take(&mut current));
+                current.points.push(to);
+            }
+            RadiantPathCommand::LineTo { to } => current.points.push(to),
+            RadiantPathCommand::QuadTo { control, to } => {
+                // The chords stray from the curve by at most |p0 - 2 p1 + p2| / (4 n^2).
+                let deviation = second_difference(last, control, to);
+                let segments = segment_count(deviation / (4.0 * tolerance));
+                current.points.extend((1..=segments).map(|step| {
+                    let t = step as f32 / segments as f32;
+                    let mt = 1.0 - t;
+                    let [a, b, c] = [mt * mt, 2.0 * mt * t, t * t];
+                    [
+                        a * last[0] + b * control[0] + c * to[0],
+                        a * last[1] + b * control[1] + c * to[1],
+                    ]
+                }));
+            }
+            RadiantPathCommand::CubicTo {
+                control1,
+                control2,
+                to,
+            } => {
+                // Here the bound is 3 max|p(i) - 2 p(i+1) + p(i+2)| / (4 n^2).
+                let deviation = second_difference(last, control1, control2)
+                    .max(second_difference(control1, control2, to));
+                let segments = segment_count(3.0 * deviation / (4.0 * tolerance));
+                current.points.extend((1..=segments).map(|step| {
+                    let t = step as f32 / segments as f32;
+                    let mt = 1.0 - t;
+                    let [a, b, c, d] =
+                        [mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t];
+                    [
+                        a * last[0] + b * control1[0] + c * control2[0] + d * to[0],
+                        a * last[1] + b * control1[1] + c * control2[1] + d * to[1],
+                    ]
+                }));
+            }
+            RadiantPathCommand::Close => {
+                current.closed = true;
+                let start = current.points.first().copied();
+                subpaths.push(std::mem::take(&mut current));
+                // Drawing after a close continues from the start of the closed subpath.
+                if let Some(start) = start {
+                    current.points.push(start);
+                }
+            }
+        }
+        if let Some(point) = current.points.last() {
+            last = *point;
+        }
+    }
+    subpaths.push(current);
+    subpaths.retain(|subpath| subpath.points.len() >= 2);
+    subpaths
+}
+
+fn second_difference(a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> f32 {
+    let x = a[0] - 2.0 * b[0] + c[0];
+    let y = a[1] - 2.0 * b[1] + c[1];
+    (x * x + y * y).sqrt()
+}
+
+fn segment_count(segments_sq: f32) -> usize {
+    (segments_sq.sqrt().ceil() as usize).clamp(1, 1024)
+}
+
+/// Whether `point` is inside the area `subpaths` fill under `fill_rule`. Open subpaths are
+/// filled as if they were closed.
+pub fn subpaths_contain(
+    subpaths: &[RadiantFlatSubpath],
+    point: [f32; 2],
+    fill_rule: RadiantFillRule,
+) -> bool {
+    let mut winding = 0;
+    for subpath in subpaths {
+        let points = &subpath.points;
+        if points.is_empty() {
+            continue;
+        }
+        let mut j = points.len() - 1;
+        for i in 0..points.len() {
+            let (a, b) = (points[j], points[i]);
+            let cross = (b[0] - a[0]) * (point[1] - a[1]) - (point[0] - a[0]) * (b[1] - a[1]);
+            if a[1] <= point[1] && b[1] > point[1] && cross > 0.0 {
+                winding += 1;
+            } else if a[1] > point[1] && b[1] <= point[1] && cross < 0.0 {
+                winding -= 1;
+            }
+            j = i;
+        }
+    }
+    match fill_rule {
+        RadiantFillRule::NonZero => winding != 0,
+        RadiantFillRule::EvenOdd => winding % 2 != 0,
+    }
+}
+
+/// Bounds of every point of `commands`, control points included.
+pub fn commands_bounds(commands: &[RadiantPathCommand]) -> Option<[f32; 4]> {
+    commands
+        .iter()
+        .flat_map(|command| match *command {
+            RadiantPathCommand::MoveTo { to } | RadiantPathCommand::LineTo { to } => vec![to],
+            RadiantPathCommand::QuadTo { control, to } => vec![control, to],
+            RadiantPathCommand::CubicTo {
+                control1,
+                control2,
+                to,
+            } => vec![control1, control2, to],
+            RadiantPathCommand::Close => Vec::new(),
+        })
+        .map(|[x, y]| [x, y, x, y])
+        .reduce(|a, b| {
+            [
+                a[0].min(b[0]),
+                a[1].min(b[1]),
+                a[2].max(b[2]),
+                a[3].max(b[3]),
+            ]
+        })
+}
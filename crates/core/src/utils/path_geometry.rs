@@ -0,0 +1,522 @@
+use crate::{distance_to_segment, RadiantPathCommand};
+use serde::{Deserialize, Serialize};
+
+/// One of the two Bezier handles around an anchor.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RadiantPathHandle {
+    /// Shapes the segment arriving at the anchor.
+    In,
+    /// Shapes the segment leaving the anchor.
+    Out,
+}
+
+/// An edit to a path outline. Anchors are named by the index of the command ending on them,
+/// and segments by the index of the command drawing them.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum RadiantPathEdit {
+    /// Moves an anchor, and its handles with it, by `offset`.
+    MovePoint {
+        index: usize,
+        offset: [f32; 2],
+    },
+    /// Moves a handle by `offset`. The opposite handle of a smooth anchor turns with it.
+    MoveHandle {
+        index: usize,
+        handle: RadiantPathHandle,
+        offset: [f32; 2],
+    },
+    /// Splits a segment in two at `t`, keeping its shape.
+    InsertPoint {
+        index: usize,
+        t: f32,
+    },
+    DeletePoint {
+        index: usize,
+    },
+    /// Makes a smooth anchor a corner by retracting its handles, or a corner anchor smooth by
+    /// pulling out handles in line with its neighbours.
+    ToggleSmooth {
+        index: usize,
+    },
+}
+
+fn is_segment(command: &RadiantPathCommand) -> bool {
+    matches!(
+        command,
+        RadiantPathCommand::LineTo { .. }
+            | RadiantPathCommand::QuadTo { .. }
+            | RadiantPathCommand::CubicTo { .. }
+    )
+}
+
+fn add(a: [f32; 2], b: [f32; 2]) -> [f32; 2] {
+    [a[0] + b[0], a[1] + b[1]]
+}
+
+fn sub(a: [f32; 2], b: [f32; 2]) -> [f32; 2] {
+    [a[0] - b[0], a[1] - b[1]]
+}
+
+fn scale(a: [f32; 2], factor: f32) -> [f32; 2] {
+    [a[0] * factor, a[1] * factor]
+}
+
+fn lerp(a: [f32; 2], b: [f32; 2], t: f32) -> [f32; 2] {
+    add(a, scale(sub(b, a), t))
+}
+
+fn length(a: [f32; 2]) -> f32 {
+    (a[0] * a[0] + a[1] * a[1]).sqrt()
+}
+
+/// The point each command starts drawing from.
+fn segment_starts(commands: &[RadiantPathCommand]) -> Vec<[f32; 2]> {
+    let mut starts = Vec::with_capacity(commands.len());
+    let (mut current, mut subpath_start) = ([0.0, 0.0], [0.0, 0.0]);
+    for command in commands {
+        starts.push(current);
+        match command {
+            RadiantPathCommand::MoveTo { to } => {
+                current = *to;
+                subpath_start = *to;
+            }
+            RadiantPathCommand::Close => current = subpath_start,
+            _ => current = command.end_point().unwrap_or(current),
+        }
+    }
+    starts
+}
+
+/// The first point of the subpath `commands[index]` belongs to.
+fn subpath_start(commands: &[RadiantPathCommand], index: usize) -> [f32; 2] {
+    commands[..=index.min(commands.len().saturating_sub(1))]
+        .iter()
+        .rev()
+        .find_map(|command| match command {
+            RadiantPathCommand::MoveTo { to } => Some(*to),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// For the `MoveTo` at `index`, the segment closing its subpath back onto the same point.
+/// That segment's end is the same anchor as the `MoveTo`.
+fn closing_segment(commands: &[RadiantPathCommand], index: usize) -> Option<usize> {
+    let RadiantPathCommand::MoveTo { to } = commands.get(index)? else {
+        return None;
+    };
+    let end = index + 1 + commands[index + 1..].iter().position(|c| !is_segment(c))?;
+    (end > index + 1
+        && commands[end] == RadiantPathCommand::Close
+        && commands[end - 1].end_point() == Some(*to))
+    .then_some(end - 1)
+}
+
+/// The segment arriving at the anchor at `index`.
+fn incoming(commands: &[RadiantPathCommand], index: usize) -> Option<usize> {
+    match commands.get(index)? {
+        RadiantPathCommand::MoveTo { .. } => closing_segment(commands, index),
+        command => is_segment(command).then_some(index),
+    }
+}
+
+/// The segment leaving the anchor at `index`.
+fn outgoing(commands: &[RadiantPathCommand], index: usize) -> Option<usize> {
+    commands.get(index)?.end_point()?;
+    commands
+        .get(index + 1)
+        .filter(|command| is_segment(command))
+        .map(|_| index + 1)
+}
+
+/// Anchors of the outline, as command indices. Segments closing a subpath onto its first
+/// point are left out, since their end is the first anchor.
+pub fn path_anchors(commands: &[RadiantPathCommand]) -> Vec<usize> {
+    let closing: Vec<usize> = (0..commands.len())
+        .filter_map(|index| closing_segment(commands, index))
+        .collect();
+    (0..commands.len())
+        .filter(|index| commands[*index].end_point().is_some() && !closing.contains(index))
+        .collect()
+}
+
+/// Where `handle` of the anchor at `index` is, if the segment it shapes is a curve.
+pub fn path_handle(
+    commands: &[RadiantPathCommand],
+    index: usize,
+    handle: RadiantPathHandle,
+) -> Option<[f32; 2]> {
+    let segment = match handle {
+        RadiantPathHandle::In => incoming(commands, index)?,
+        RadiantPathHandle::Out => outgoing(commands, index)?,
+    };
+    match (commands[segment], handle) {
+        (RadiantPathCommand::QuadTo { control, .. }, _) => Some(control),
+        (RadiantPathCommand::CubicTo { control2, .. }, RadiantPathHandle::In) => Some(control2),
+        (RadiantPathCommand::CubicTo { control1, .. }, RadiantPathHandle::Out) => Some(control1),
+        _ => None,
+    }
+}
+
+/// Whether the anchor at `index` has both handles, pointing in opposite directions.
+pub fn is_smooth_point(commands: &[RadiantPathCommand], index: usize) -> bool {
+    let Some(point) = commands.get(index).and_then(|command| command.end_point()) else {
+        return false;
+    };
+    let handles = (
+        path_handle(commands, index, RadiantPathHandle::In),
+        path_handle(commands, index, RadiantPathHandle::Out),
+    );
+    let (Some(handle_in), Some(handle_out)) = handles else {
+        return false;
+    };
+    let (a, b) = (sub(handle_in, point), sub(point, handle_out));
+    let (length_a, length_b) = (length(a), length(b));
+    if length_a < f32::EPSILON || length_b < f32::EPSILON {
+        return false;
+    }
+    let cross = (a[0] * b[1] - a[1] * b[0]) / (length_a * length_b);
+    let dot = a[0] * b[0] + a[1] * b[1];
+    cross.abs() < 1e-3 && dot > 0.0
+}
+
+/// The point `t` along the segment drawn by `commands[index]`.
+fn segment_point(
+    commands: &[RadiantPathCommand],
+    starts: &[[f32; 2]],
+    index: usize,
+    t: f32,
+) -> [f32; 2] {
+    let from = starts[index];
+    match commands[index] {
+        RadiantPathCommand::MoveTo { to } => to,
+        RadiantPathCommand::LineTo { to } => lerp(from, to, t),
+        RadiantPathCommand::QuadTo { control, to } => {
+            lerp(lerp(from, control, t), lerp(control, to, t), t)
+        }
+        RadiantPathCommand::CubicTo {
+            control1,
+            control2,
+            to,
+        } => {
+            let (a, b, c) = (
+                lerp(from, control1, t),
+                lerp(control1, control2, t),
+                lerp(control2, to, t),
+            );
+            lerp(lerp(a, b, t), lerp(b, c, t), t)
+        }
+        RadiantPathCommand::Close => lerp(from, subpath_start(commands, index), t),
+    }
+}
+
+/// The segment nearest to `point`, with the `t` of the nearest point on it and its distance.
+/// Closing segments that draw a line back to the first point count too.
+pub fn nearest_path_segment(
+    commands: &[RadiantPathCommand],
+    point: [f32; 2],
+) -> Option<(usize, f32, f32)> {
+    const STEPS: usize = 64;
+    let starts = segment_starts(commands);
+    let mut nearest: Option<(usize, f32, f32)> = None;
+    for index in 0..commands.len() {
+        if !is_segment(&commands[index]) && commands[index] != RadiantPathCommand::Close {
+            continue;
+        }
+        let mut previous = segment_point(commands, &starts, index, 0.0);
+        for step in 1..=STEPS {
+            let t = step as f32 / STEPS as f32;
+            let current = segment_point(commands, &starts, index, t);
+            let distance = distance_to_segment(point, previous, current);
+            let closer = match nearest {
+                Some((_, _, nearest)) => distance < nearest,
+                None => true,
+            };
+            if closer {
+                // Place the point along the step it is closest to.
+                let step_length = length(sub(current, previous)).max(f32::EPSILON);
+                let along = ((point[0] - previous[0]) * (current[0] - previous[0])
+                    + (point[1] - previous[1]) * (current[1] - previous[1]))
+                    / (step_length * step_length);
+                let t = t - (1.0 - along.clamp(0.0, 1.0)) / STEPS as f32;
+                nearest = Some((index, t, distance));
+            }
+            previous = current;
+        }
+    }
+    nearest
+}
+
+/// `commands[index]` as a cubic, so each end of it has its own handle.
+fn make_cubic(commands: &mut [RadiantPathCommand], starts: &[[f32; 2]], index: usize) {
+    let from = starts[index];
+    commands[index] = match commands[index] {
+        RadiantPathCommand::LineTo { to } => RadiantPathCommand::CubicTo {
+            control1: from,
+            control2: to,
+            to,
+        },
+        RadiantPathCommand::QuadTo { control, to } => RadiantPathCommand::CubicTo {
+            control1: lerp(from, control, 2.0 / 3.0),
+            control2: lerp(to, control, 2.0 / 3.0),
+            to,
+        },
+        command => command,
+    };
+}
+
+fn handle_mut(
+    command: &mut RadiantPathCommand,
+    handle: RadiantPathHandle,
+) -> Option<&mut [f32; 2]> {
+    match (command, handle) {
+        (RadiantPathCommand::CubicTo { control2, .. }, RadiantPathHandle::In) => Some(control2),
+        (RadiantPathCommand::CubicTo { control1, .. }, RadiantPathHandle::Out) => Some(control1),
+        _ => None,
+    }
+}
+
+fn end_mut(command: &mut RadiantPathCommand) -> Option<&mut [f32; 2]> {
+    match command {
+        RadiantPathCommand::MoveTo { to }
+        | RadiantPathCommand::LineTo { to }
+        | RadiantPathCommand::QuadTo { to, .. }
+        | RadiantPathCommand::CubicTo { to, .. } => Some(to),
+        RadiantPathCommand::Close => None,
+    }
+}
+
+/// Drops subpaths left without any segment.
+fn remove_empty_subpaths(commands: Vec<RadiantPathCommand>) -> Vec<RadiantPathCommand> {
+    let mut result: Vec<RadiantPathCommand> = Vec::with_capacity(commands.len());
+    let mut subpath = Vec::new();
+    let mut flush = |subpath: &mut Vec<RadiantPathCommand>| {
+        if subpath.iter().any(is_segment) {
+            result.append(subpath);
+        }
+        subpath.clear();
+    };
+    for command in commands {
+        if matches!(command, RadiantPathCommand::MoveTo { .. }) {
+            flush(&mut subpath);
+        }
+        subpath.push(command);
+    }
+    flush(&mut subpath);
+    result
+}
+
+/// `commands` with `edit` applied, or `None` if the edit does not apply to them.
+pub fn apply_path_edit(
+    commands: &[RadiantPathCommand],
+    edit: RadiantPathEdit,
+) -> Option<Vec<RadiantPathCommand>> {
+    let starts = segment_starts(commands);
+    let mut commands = commands.to_vec();
+    match edit {
+        RadiantPathEdit::MovePoint { index, offset } => {
+            commands.get(index)?.end_point()?;
+            let segments = [
+                (incoming(&commands, index), RadiantPathHandle::In),
+                (outgoing(&commands, index), RadiantPathHandle::Out),
+            ];
+            for (segment, handle) in segments {
+                let Some(segment) = segment else {
+                    continue;
+                };
+                if matches!(commands[segment], RadiantPathCommand::QuadTo { .. }) {
+                    make_cubic(&mut commands, &starts, segment);
+                }
+                if let Some(control) = handle_mut(&mut commands[segment], handle) {
+                    *control = add(*control, offset);
+                }
+                if handle == RadiantPathHandle::In {
+                    let to = end_mut(&mut commands[segment])?;
+                    *to = add(*to, offset);
+                }
+            }
+            if let RadiantPathCommand::MoveTo { to } = &mut commands[index] {
+                *to = add(*to, offset);
+            }
+        }
+        RadiantPathEdit::MoveHandle {
+            index,
+            handle,
+            offset,
+        } => {
+            let point = commands.get(index)?.end_point()?;
+            let smooth = is_smooth_point(&commands, index);
+            let (segment, opposite) = match handle {
+                RadiantPathHandle::In => (incoming(&commands, index)?, outgoing(&commands, index)),
+                RadiantPathHandle::Out => (outgoing(&commands, index)?, incoming(&commands, index)),
+            };
+            if matches!(commands[segment], RadiantPathCommand::LineTo { .. }) {
+                return None;
+            }
+            make_cubic(&mut commands, &starts, segment);
+            let control = handle_mut(&mut commands[segment], handle)?;
+            *control = add(*control, offset);
+            let moved = *control;
+
+            if let (true, Some(opposite)) = (smooth, opposite) {
+                make_cubic(&mut commands, &starts, opposite);
+                let opposite_handle = match handle {
+                    RadiantPathHandle::In => RadiantPathHandle::Out,
+                    RadiantPathHandle::Out => RadiantPathHandle::In,
+                };
+                let control = handle_mut(&mut commands[opposite], opposite_handle)?;
+                let direction = sub(point, moved);
+                let direction_length = length(direction);
+                if direction_length > f32::EPSILON {
+                    let reach = length(sub(*control, point));
+                    *control = add(point, scale(direction, reach / direction_length));
+                }
+            }
+        }
+        RadiantPathEdit::InsertPoint { index, t } => {
+            let t = t.clamp(0.0, 1.0);
+            let from = *starts.get(index)?;
+            let split = match *commands.get(index)? {
+                RadiantPathCommand::LineTo { to } => vec![
+                    RadiantPathCommand::LineTo {
+                        to: lerp(from, to, t),
+                    },
+                    RadiantPathCommand::LineTo { to },
+                ],
+                RadiantPathCommand::QuadTo { control, to } => {
+                    let (a, b) = (lerp(from, control, t), lerp(control, to, t));
+                    vec![
+                        RadiantPathCommand::QuadTo {
+                            control: a,
+                            to: lerp(a, b, t),
+                        },
+                        RadiantPathCommand::QuadTo { control: b, to },
+                    ]
+                }
+                RadiantPathCommand::CubicTo {
+                    control1,
+                    control2,
+                    to,
+                } => {
+                    let (a, b, c) = (
+                        lerp(from, control1, t),
+                        lerp(control1, control2, t),
+                        lerp(control2, to, t),
+                    );
+                    let (d, e) = (lerp(a, b, t), lerp(b, c, t));
+                    vec![
+                        RadiantPathCommand::CubicTo {
+                            control1: a,
+                            control2: d,
+                            to: lerp(d, e, t),
+                        },
+                        RadiantPathCommand::CubicTo {
+                            control1: e,
+                            control2: c,
+                            to,
+                        },
+                    ]
+                }
+                // The straight line a close draws back to the first point.
+                RadiantPathCommand::Close => {
+                    let to = subpath_start(&commands, index);
+                    if from == to {
+                        return None;
+                    }
+                    vec![
+                        RadiantPathCommand::LineTo {
+                            to: lerp(from, to, t),
+                        },
+                        RadiantPathCommand::Close,
+                    ]
+                }
+                RadiantPathCommand::MoveTo { .. } => return None,
+            };
+            commands.splice(index..=index, split);
+        }
+        RadiantPathEdit::DeletePoint { index } => {
+            commands.get(index)?.end_point()?;
+            if path_anchors(&commands).len() <= 2 {
+                return None;
+            }
+            match commands[index] {
+                RadiantPathCommand::MoveTo { .. } => {
+                    // The next anchor starts the subpath instead.
+                    let closing = closing_segment(&commands, index);
+                    let first = outgoing(&commands, index);
+                    if let Some(first) = first {
+                        let removed = commands[first];
+                        let to = removed.end_point()?;
+                        commands[first] = RadiantPathCommand::MoveTo { to };
+                        if let Some(closing) = closing {
+                            *end_mut(&mut commands[closing])? = to;
+                            if let RadiantPathCommand::CubicTo { control2, .. } = removed {
+                                if let Some(control) =
+                                    handle_mut(&mut commands[closing], RadiantPathHandle::In)
+                                {
+                                    *control = control2;
+                                }
+                            }
+                        }
+                    }
+                    commands.remove(index);
+                }
+                removed => {
+                    // The segments on either side of the point merge into one.
+                    if let Some(next) = outgoing(&commands, index) {
+                        if let RadiantPathCommand::CubicTo { control1, .. } = removed {
+                            if let Some(control) =
+                                handle_mut(&mut commands[next], RadiantPathHandle::Out)
+                            {
+                                *control = control1;
+                            }
+                        }
+                    }
+                    commands.remove(index);
+                }
+            }
+            commands = remove_empty_subpaths(commands);
+        }
+        RadiantPathEdit::ToggleSmooth { index } => {
+            let point = commands.get(index)?.end_point()?;
+            let segments = (incoming(&commands, index), outgoing(&commands, index));
+            if segments == (None, None) {
+                return None;
+            }
+            if is_smooth_point(&commands, index) {
+                for (segment, handle) in [
+                    (segments.0, RadiantPathHandle::In),
+                    (segments.1, RadiantPathHandle::Out),
+                ] {
+                    if let Some(control) =
+                        segment.and_then(|segment| handle_mut(&mut commands[segment], handle))
+                    {
+                        *control = point;
+                    }
+                }
+            } else {
+                let previous = segments.0.map(|segment| starts[segment]);
+                let next = segments.1.and_then(|segment| commands[segment].end_point());
+                let direction = sub(next.unwrap_or(point), previous.unwrap_or(point));
+                let direction_length = length(direction);
+                if direction_length < f32::EPSILON {
+                    return None;
+                }
+                let direction = scale(direction, 1.0 / direction_length);
+                if let (Some(segment), Some(previous)) = (segments.0, previous) {
+                    make_cubic(&mut commands, &starts, segment);
+                    let reach = length(sub(point, previous)) / 3.0;
+                    *handle_mut(&mut commands[segment], RadiantPathHandle::In)? =
+                        sub(point, scale(direction, reach));
+                }
+                if let (Some(segment), Some(next)) = (segments.1, next) {
+                    make_cubic(&mut commands, &starts, segment);
+                    let reach = length(sub(next, point)) / 3.0;
+                    *handle_mut(&mut commands[segment], RadiantPathHandle::Out)? =
+                        add(point, scale(direction, reach));
+                }
+            }
+        }
+    }
+    Some(commands)
+}
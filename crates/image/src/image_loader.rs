@@ -1,34 +1,168 @@
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// Where an image is loaded from. A bare string deserializes as a URL.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(from = "ImageSourceRepr")]
+pub enum RadiantImageSource {
+    /// Fetched over HTTP, or decoded in place for a `data:` URI.
+    Url(String),
+    /// Read from disk. Not available on the web.
+    Path(String),
+    /// An encoded PNG or JPEG.
+    Bytes(Vec<u8>),
+}
+
+/// Accepts the old `AddImage { path }` form, where `path` was a URL.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ImageSourceRepr {
+    Bare(String),
+    Tagged(TaggedImageSource),
+}
+
+#[derive(Deserialize)]
+enum TaggedImageSource {
+    Url(String),
+    Path(String),
+    Bytes(Vec<u8>),
+}
+
+impl From<ImageSourceRepr> for RadiantImageSource {
+    fn from(repr: ImageSourceRepr) -> Self {
+        match repr {
+            ImageSourceRepr::Bare(url) | ImageSourceRepr::Tagged(TaggedImageSource::Url(url)) => {
+                Self::Url(url)
+            }
+            ImageSourceRepr::Tagged(TaggedImageSource::Path(path)) => Self::Path(path),
+            ImageSourceRepr::Tagged(TaggedImageSource::Bytes(bytes)) => Self::Bytes(bytes),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RadiantImageError {
+    Fetch {
+        url: String,
+        reason: String,
+    },
+    Read {
+        path: String,
+        reason: String,
+    },
+    InvalidDataUri {
+        reason: String,
+    },
+    /// The bytes are not an image in a supported format.
+    Decode {
+        reason: String,
+    },
+}
+
+impl std::fmt::Display for RadiantImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fetch { url, reason } => write!(f, "failed to fetch {url}: {reason}"),
+            Self::Read { path, reason } => write!(f, "failed to read {path}: {reason}"),
+            Self::InvalidDataUri { reason } => write!(f, "invalid data URI: {reason}"),
+            Self::Decode { reason } => write!(f, "failed to decode image: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for RadiantImageError {}
+
+/// Loads and decodes an image. Sources other than HTTP URLs call back before returning.
 pub fn load_image(
-    path: String,
-    callback: impl FnOnce(Result<epaint::ColorImage, String>) + 'static + Send,
+    source: RadiantImageSource,
+    callback: impl FnOnce(Result<epaint::ColorImage, RadiantImageError>) + 'static + Send,
 ) {
-    ehttp::fetch(ehttp::Request::get(path.clone()), move |response| {
-        match response {
-            Ok(response) => {
-                // println!("Response: {:?}", response);
-                // let mime_type = response.content_type().map(|v| v.to_owned());
-
-                let result =
-                    match image::load_from_memory(&response.bytes).map_err(|err| err.to_string()) {
-                        Ok(image) => {
-                            let size = [image.width() as _, image.height() as _];
-                            let image_buffer = image.to_rgba8();
-                            let pixels = image_buffer.as_flat_samples();
-                            Ok(epaint::ColorImage::from_rgba_unmultiplied(
-                                size,
-                                pixels.as_slice(),
-                            ))
-                        }
-                        Err(err) => {
-                            log::error!("Failed to load {path:?}: {err}");
-                            Err(err)
-                        }
-                    };
+    match source {
+        RadiantImageSource::Url(url) if url.starts_with("data:") => {
+            callback(decode_data_uri(&url).and_then(|bytes| decode_image(&bytes)))
+        }
+        RadiantImageSource::Url(url) => {
+            ehttp::fetch(ehttp::Request::get(url.clone()), move |response| {
+                let result = match response {
+                    Ok(response) if response.ok => decode_image(&response.bytes),
+                    Ok(response) => Err(RadiantImageError::Fetch {
+                        url,
+                        reason: format!("{} {}", response.status, response.status_text),
+                    }),
+                    Err(reason) => Err(RadiantImageError::Fetch { url, reason }),
+                };
                 callback(result);
-            }
-            Err(err) => {
-                log::error!("Failed to load {path:?}: {err}");
-            }
-        };
-    });
+            });
+        }
+        RadiantImageSource::Path(path) => callback(read_image(path)),
+        RadiantImageSource::Bytes(bytes) => callback(decode_image(&bytes)),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_image(path: String) -> Result<epaint::ColorImage, RadiantImageError> {
+    match std::fs::read(&path) {
+        Ok(bytes) => decode_image(&bytes),
+        Err(err) => Err(RadiantImageError::Read {
+            path,
+            reason: err.to_string(),
+        }),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_image(path: String) -> Result<epaint::ColorImage, RadiantImageError> {
+    Err(RadiantImageError::Read {
+        path,
+        reason: "files can't be read on the web".to_string(),
+    })
+}
+
+pub fn decode_image(bytes: &[u8]) -> Result<epaint::ColorImage, RadiantImageError> {
+    let image = image::load_from_memory(bytes).map_err(|err| RadiantImageError::Decode {
+        reason: err.to_string(),
+    })?;
+    let size = [image.width() as _, image.height() as _];
+    let image_buffer = image.to_rgba8();
+    let pixels = image_buffer.as_flat_samples();
+    Ok(epaint::ColorImage::from_rgba_unmultiplied(
+        size,
+        pixels.as_slice(),
+    ))
+}
+
+/// Bytes of a `data:[<media type>][;base64],<data>` URI.
+pub fn decode_data_uri(uri: &str) -> Result<Vec<u8>, RadiantImageError> {
+    let invalid = |reason: &str| RadiantImageError::InvalidDataUri {
+        reason: reason.to_string(),
+    };
+    let uri = uri
+        .strip_prefix("data:")
+        .ok_or_else(|| invalid("missing data: scheme"))?;
+    let (header, data) = uri
+        .split_once(',')
+        .ok_or_else(|| invalid("missing comma"))?;
+    if header.ends_with(";base64") {
+        base64::engine::general_purpose::STANDARD
+            .decode(data.trim())
+            .map_err(|err| invalid(&err.to_string()))
+    } else {
+        percent_decode(data).ok_or_else(|| invalid("bad percent escape"))
+    }
+}
+
+fn percent_decode(data: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(data.len());
+    let mut rest = data.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    Some(bytes)
 }
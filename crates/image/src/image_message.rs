@@ -1,8 +1,24 @@
+use crate::RadiantImageSource;
 use macro_magic::export_tokens;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 #[export_tokens]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RadiantImageMessage {
-    AddImage { name: String, path: String },
+    /// Adds the image once it loads. Without a `size`, it is drawn at its own size.
+    AddImage {
+        id: Option<Uuid>,
+        name: String,
+        #[serde(alias = "path")]
+        source: RadiantImageSource,
+        #[serde(default = "default_image_position")]
+        position: [f32; 2],
+        size: Option<[f32; 2]>,
+    },
+}
+
+/// Where images land when a message leaves out the position.
+pub fn default_image_position() -> [f32; 2] {
+    [100.0, 200.0]
 }
@@ -2,7 +2,8 @@ use epaint::{
     pos2, ClippedPrimitive, ClippedShape, Color32, Rect, TessellationOptions, TextureHandle,
 };
 use radiantkit_core::{
-    get_color_for_node, BaseNode, RadiantNode, RadiantTessellatable, ScreenDescriptor, Vec3,
+    pick_color, picking_tessellation_options, BaseNode, RadiantNode, RadiantTessellatable,
+    RadiantVectorGeometry, RadiantVectorNode, ScreenDescriptor, Vec3,
 };
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
@@ -33,10 +34,28 @@ impl RadiantImageNode {
         let mut base = BaseNode::new(id, position.into(), scale.into());
         base.color.set_fill_color(Color32::WHITE);
 
-        Self {
+        let mut node = Self {
             base,
             texture_handle: Some(texture_handle),
-        }
+        };
+        node.update_bounding_rect();
+        node
+    }
+
+    fn update_bounding_rect(&mut self) {
+        let position = self.base.transform.position();
+        let scale = self.base.transform.scale();
+
+        let rect = epaint::Rect::from_min_max(
+            position.into(),
+            Vec3::new_with_added(&position, &scale).into(),
+        );
+        self.base.bounding_rect = [
+            rect.left_top().x,
+            rect.left_top().y,
+            rect.right_bottom().x,
+            rect.right_bottom().y,
+        ];
     }
 
     fn tessellate(&mut self, screen_descriptor: &ScreenDescriptor) {
@@ -67,7 +86,7 @@ impl RadiantImageNode {
             shapes,
         );
 
-        let color = get_color_for_node(self.base.id);
+        let color = pick_color(self.base.pick_id);
         let rect_shape = epaint::RectShape::filled(rect, rounding, color);
         let shapes = vec![ClippedShape(
             Rect::EVERYTHING,
@@ -75,7 +94,7 @@ impl RadiantImageNode {
         )];
         self.base.selection_primitives = epaint::tessellator::tessellate_shapes(
             pixels_per_point,
-            TessellationOptions::default(),
+            picking_tessellation_options(),
             [1, 1],
             vec![],
             shapes,
@@ -94,20 +113,7 @@ impl RadiantTessellatable for RadiantImageNode {
     }
 
     fn set_needs_tessellation(&mut self, notify: bool) {
-        let position = self.base.transform.position();
-        let scale = self.base.transform.scale();
-
-        let rect = epaint::Rect::from_min_max(
-            position.into(),
-            Vec3::new_with_added(&position, &scale).into(),
-        );
-        self.base.bounding_rect = [
-            rect.left_top().x,
-            rect.left_top().y,
-            rect.right_bottom().x,
-            rect.right_bottom().y,
-        ];
-
+        self.update_bounding_rect();
         self.base.set_needs_tessellation();
         if notify {
             self.base.notify(serde_json::to_string(self).unwrap());
@@ -137,4 +143,17 @@ impl RadiantNode for RadiantImageNode {
     fn base_mut(&mut self) -> &mut BaseNode {
         &mut self.base
     }
+
+    fn to_vector(&self) -> Option<RadiantVectorNode> {
+        let texture_id = self.texture_handle.as_ref()?.id();
+        let rect = self.base.bounding_rect;
+        let size = [rect[2] - rect[0], rect[3] - rect[1]];
+        let mut node = RadiantVectorNode::new(
+            &self.base,
+            RadiantVectorGeometry::Image { size, texture_id },
+            0.0,
+        );
+        node.fill = None;
+        Some(node)
+    }
 }
@@ -114,10 +114,129 @@ fn derive_node_internal(item: TokenStream2) -> syn::Result<TokenStream2> {
                 }
             }
 
-            fn handle_key_down(&mut self, key: crate::KeyCode) -> bool {
+            fn hit_test(&self, position: [f32; 2], tolerance: f32) -> bool {
                 match self {
                     #(
-                        #name::#node_names(node) => node.handle_key_down(key),
+                        #name::#node_names(node) => node.hit_test(position, tolerance),
+                    )*
+                }
+            }
+
+            fn handle_key_down(
+                &mut self,
+                key: crate::KeyCode,
+                modifiers: crate::RadiantKeyModifiers,
+            ) -> bool {
+                match self {
+                    #(
+                        #name::#node_names(node) => node.handle_key_down(key, modifiers),
+                    )*
+                }
+            }
+
+            fn handle_ime(&mut self, event: radiantkit_core::RadiantImeEvent) -> bool {
+                match self {
+                    #(
+                        #name::#node_names(node) => node.handle_ime(event),
+                    )*
+                }
+            }
+
+            fn cursor(&self) -> radiantkit_core::RadiantCursor {
+                match self {
+                    #(
+                        #name::#node_names(node) => node.cursor(),
+                    )*
+                }
+            }
+
+            fn handle_press(&mut self, position: [f32; 2], extend: bool) -> bool {
+                match self {
+                    #(
+                        #name::#node_names(node) => node.handle_press(position, extend),
+                    )*
+                }
+            }
+
+            fn begin_editing(&mut self, position: [f32; 2]) -> bool {
+                match self {
+                    #(
+                        #name::#node_names(node) => node.begin_editing(position),
+                    )*
+                }
+            }
+
+            fn end_editing(&mut self) {
+                match self {
+                    #(
+                        #name::#node_names(node) => node.end_editing(),
+                    )*
+                }
+            }
+
+            fn ime_cursor_area(&self) -> Option<[f32; 4]> {
+                match self {
+                    #(
+                        #name::#node_names(node) => node.ime_cursor_area(),
+                    )*
+                }
+            }
+
+            fn to_vector(&self) -> Option<radiantkit_core::RadiantVectorNode> {
+                match self {
+                    #(
+                        #name::#node_names(node) => node.to_vector(),
+                    )*
+                }
+            }
+
+            fn fit_content(&mut self, fonts: &epaint::text::Fonts) -> bool {
+                match self {
+                    #(
+                        #name::#node_names(node) => node.fit_content(fonts),
+                    )*
+                }
+            }
+
+            fn font_families(&self) -> Vec<String> {
+                match self {
+                    #(
+                        #name::#node_names(node) => node.font_families(),
+                    )*
+                }
+            }
+
+            fn text(&self) -> Option<&str> {
+                match self {
+                    #(
+                        #name::#node_names(node) => node.text(),
+                    )*
+                }
+            }
+
+            fn apply_text_delta(&mut self, delta: &[radiantkit_core::RadiantTextDelta]) -> bool {
+                match self {
+                    #(
+                        #name::#node_names(node) => node.apply_text_delta(delta),
+                    )*
+                }
+            }
+
+            fn path_commands(&self) -> Option<Vec<radiantkit_core::RadiantPathCommand>> {
+                match self {
+                    #(
+                        #name::#node_names(node) => node.path_commands(),
+                    )*
+                }
+            }
+
+            fn set_path_commands(
+                &mut self,
+                commands: Vec<radiantkit_core::RadiantPathCommand>,
+            ) -> bool {
+                match self {
+                    #(
+                        #name::#node_names(node) => node.set_path_commands(commands),
                     )*
                 }
             }
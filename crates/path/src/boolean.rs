@@ -0,0 +1,92 @@
+use geo::{Area, BooleanOps, Coord, LineString, MultiPolygon, Polygon};
+use radiantkit_core::{
+    flatten_commands, RadiantFillRule, RadiantPathCommand, DEFAULT_FLATTENING_TOLERANCE,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RadiantBooleanOp {
+    Union,
+    /// Removes every other outline from the first one.
+    Subtract,
+    Intersect,
+    Exclude,
+}
+
+/// Combines `outlines`, given as commands in document coordinates with their fill rules.
+/// Curves are flattened, so the result is polygonal, and it is meant to be filled with the
+/// even-odd rule. Returns `None` when nothing is left.
+pub fn boolean_op(
+    op: RadiantBooleanOp,
+    outlines: &[(Vec<RadiantPathCommand>, RadiantFillRule)],
+) -> Option<Vec<RadiantPathCommand>> {
+    let mut areas = outlines
+        .iter()
+        .map(|(commands, fill_rule)| outline_area(commands, *fill_rule));
+    let first = areas.next()?;
+    let result = areas.fold(first, |result, area| match op {
+        RadiantBooleanOp::Union => result.union(&area),
+        RadiantBooleanOp::Subtract => result.difference(&area),
+        RadiantBooleanOp::Intersect => result.intersection(&area),
+        RadiantBooleanOp::Exclude => result.xor(&area),
+    });
+
+    let mut commands = Vec::new();
+    for polygon in &result {
+        for ring in std::iter::once(polygon.exterior()).chain(polygon.interiors()) {
+            // Rings repeat their first point at the end, which the close already draws to.
+            let coords = &ring.0[..ring.0.len().saturating_sub(1)];
+            for (index, coord) in coords.iter().enumerate() {
+                let to = [coord.x as f32, coord.y as f32];
+                commands.push(if index == 0 {
+                    RadiantPathCommand::MoveTo { to }
+                } else {
+                    RadiantPathCommand::LineTo { to }
+                });
+            }
+            if !coords.is_empty() {
+                commands.push(RadiantPathCommand::Close);
+            }
+        }
+    }
+    (!commands.is_empty()).then_some(commands)
+}
+
+/// The area `commands` fill. Even-odd paths toggle between inside and outside at every
+/// subpath. For non-zero paths, subpaths wound against the largest one are taken as holes,
+/// which covers outlines whose subpaths do not cross each other.
+fn outline_area(commands: &[RadiantPathCommand], fill_rule: RadiantFillRule) -> MultiPolygon<f64> {
+    let polygons: Vec<Polygon<f64>> = flatten_commands(commands, DEFAULT_FLATTENING_TOLERANCE)
+        .into_iter()
+        .filter(|subpath| subpath.points.len() >= 3)
+        .map(|subpath| {
+            let coords: Vec<Coord<f64>> = subpath
+                .points
+                .iter()
+                .map(|[x, y]| Coord {
+                    x: *x as f64,
+                    y: *y as f64,
+                })
+                .collect();
+            Polygon::new(LineString::new(coords), Vec::new())
+        })
+        .collect();
+    let orientation = polygons
+        .iter()
+        .map(|polygon| polygon.signed_area())
+        .max_by(|a, b| a.abs().total_cmp(&b.abs()))
+        .unwrap_or_default()
+        .signum();
+
+    polygons
+        .into_iter()
+        .fold(MultiPolygon::new(Vec::new()), |area, polygon| {
+            let winding = polygon.signed_area().signum();
+            let polygon = MultiPolygon::new(vec![polygon]);
+            match fill_rule {
+                RadiantFillRule::EvenOdd => area.xor(&polygon),
+                RadiantFillRule::NonZero if winding == orientation => area.union(&polygon),
+                RadiantFillRule::NonZero => area.difference(&polygon),
+            }
+        })
+}
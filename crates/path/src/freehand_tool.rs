@@ -0,0 +1,217 @@
+use crate::{RadiantPathMessage, RadiantStrokePoint};
+use radiantkit_core::{distance_to_segment, RadiantCursor, RadiantTool};
+use uuid::Uuid;
+
+/// Width (in points) of a slow stroke. Faster strokes get thinner, down to `MIN_WIDTH_RATIO`
+/// of it.
+const BRUSH_WIDTH: f32 = 4.0;
+const MIN_WIDTH_RATIO: f32 = 0.4;
+/// Distance between samples (in points) at which the stroke is half its full width.
+const HALF_WIDTH_SPEED: f32 = 30.0;
+/// Share of the difference to the new width applied per sample.
+const WIDTH_SMOOTHING: f32 = 0.3;
+/// Samples closer than this (in points) to the previous one are dropped.
+const MIN_SAMPLE_DISTANCE: f32 = 0.5;
+/// Distance (in points) a finished stroke may stray from its samples when simplified.
+const SIMPLIFY_TOLERANCE: f32 = 0.75;
+
+/// One euro filter over positions, taking samples as evenly spaced in time. Slow movements
+/// are smoothed heavily to remove jitter, fast ones lightly to limit lag.
+#[derive(Debug, Clone)]
+struct OneEuroFilter {
+    min_cutoff: f32,
+    beta: f32,
+    derivative_cutoff: f32,
+    previous: Option<([f32; 2], [f32; 2])>,
+}
+
+impl OneEuroFilter {
+    fn new() -> Self {
+        Self {
+            min_cutoff: 0.1,
+            beta: 0.05,
+            derivative_cutoff: 1.0,
+            previous: None,
+        }
+    }
+
+    fn alpha(cutoff: f32) -> f32 {
+        let tau = 1.0 / (2.0 * std::f32::consts::PI * cutoff);
+        1.0 / (1.0 + tau)
+    }
+
+    fn filter(&mut self, value: [f32; 2]) -> [f32; 2] {
+        let Some((previous, derivative)) = self.previous else {
+            self.previous = Some((value, [0.0, 0.0]));
+            return value;
+        };
+        let alpha = Self::alpha(self.derivative_cutoff);
+        let derivative = [
+            derivative[0] + alpha * (value[0] - previous[0] - derivative[0]),
+            derivative[1] + alpha * (value[1] - previous[1] - derivative[1]),
+        ];
+        let speed = (derivative[0] * derivative[0] + derivative[1] * derivative[1]).sqrt();
+        let alpha = Self::alpha(self.min_cutoff + self.beta * speed);
+        let filtered = [
+            previous[0] + alpha * (value[0] - previous[0]),
+            previous[1] + alpha * (value[1] - previous[1]),
+        ];
+        self.previous = Some((filtered, derivative));
+        filtered
+    }
+
+    fn reset(&mut self) {
+        self.previous = None;
+    }
+}
+
+/// Draws freehand strokes. Samples are smoothed as they come in and streamed into the
+/// document, and the finished stroke is simplified to as few points as keep its shape.
+pub struct FreehandTool {
+    active_node_id: Option<Uuid>,
+    filter: OneEuroFilter,
+    points: Vec<RadiantStrokePoint>,
+    last_sample: [f32; 2],
+}
+
+impl FreehandTool {
+    pub fn new() -> Self {
+        Self {
+            active_node_id: None,
+            filter: OneEuroFilter::new(),
+            points: Vec::new(),
+            last_sample: [0.0, 0.0],
+        }
+    }
+}
+
+impl Default for FreehandTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn width_for_distance(distance: f32) -> f32 {
+    let ratio = HALF_WIDTH_SPEED / (HALF_WIDTH_SPEED + distance);
+    BRUSH_WIDTH * ratio.max(MIN_WIDTH_RATIO)
+}
+
+/// How far the edge of the stroke at `point` is from where it would be if `point` were left
+/// out between `a` and `b`: the distance to the segment, or half the change in width.
+fn stroke_deviation(
+    point: &RadiantStrokePoint,
+    a: &RadiantStrokePoint,
+    b: &RadiantStrokePoint,
+) -> f32 {
+    let segment = [b.position[0] - a.position[0], b.position[1] - a.position[1]];
+    let length_squared = segment[0] * segment[0] + segment[1] * segment[1];
+    let t = if length_squared > 0.0 {
+        let offset = [
+            point.position[0] - a.position[0],
+            point.position[1] - a.position[1],
+        ];
+        ((offset[0] * segment[0] + offset[1] * segment[1]) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let width = a.width + t * (b.width - a.width);
+    distance_to_segment(point.position, a.position, b.position)
+        .max((point.width - width).abs() / 2.0)
+}
+
+/// Ramer-Douglas-Peucker simplification over positions and widths. The first and last points
+/// are always kept.
+pub fn simplify_stroke(points: &[RadiantStrokePoint], tolerance: f32) -> Vec<RadiantStrokePoint> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    let mut ranges = vec![(0, points.len() - 1)];
+    while let Some((start, end)) = ranges.pop() {
+        let (a, b) = (&points[start], &points[end]);
+        let farthest = (start + 1..end)
+            .map(|index| (index, stroke_deviation(&points[index], a, b)))
+            .max_by(|x, y| x.1.total_cmp(&y.1));
+        if let Some((index, distance)) = farthest {
+            if distance > tolerance {
+                keep[index] = true;
+                ranges.push((start, index));
+                ranges.push((index, end));
+            }
+        }
+    }
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(point, keep)| keep.then_some(*point))
+        .collect()
+}
+
+impl<M: From<RadiantPathMessage>> RadiantTool<M> for FreehandTool {
+    fn on_mouse_down(&mut self, _node_id: Option<Uuid>, position: [f32; 2]) -> Option<M> {
+        let id = Uuid::new_v4();
+        self.active_node_id = Some(id);
+        self.filter.reset();
+        self.last_sample = position;
+        let point = RadiantStrokePoint {
+            position: self.filter.filter(position),
+            width: BRUSH_WIDTH,
+        };
+        self.points = vec![point];
+        Some(
+            RadiantPathMessage::AddStroke {
+                id: Some(id),
+                points: self.points.clone(),
+            }
+            .into(),
+        )
+    }
+
+    fn on_mouse_move(&mut self, position: [f32; 2]) -> Option<M> {
+        let id = self.active_node_id?;
+        let distance = ((position[0] - self.last_sample[0]).powi(2)
+            + (position[1] - self.last_sample[1]).powi(2))
+        .sqrt();
+        if distance < MIN_SAMPLE_DISTANCE {
+            return None;
+        }
+        self.last_sample = position;
+
+        let previous_width = self.points.last().map_or(BRUSH_WIDTH, |point| point.width);
+        let point = RadiantStrokePoint {
+            position: self.filter.filter(position),
+            width: previous_width
+                + WIDTH_SMOOTHING * (width_for_distance(distance) - previous_width),
+        };
+        self.points.push(point);
+        Some(
+            RadiantPathMessage::ExtendStroke {
+                id,
+                points: vec![point],
+            }
+            .into(),
+        )
+    }
+
+    fn on_mouse_up(&mut self, position: [f32; 2]) -> Option<M> {
+        let id = self.active_node_id.take()?;
+        let mut points = std::mem::take(&mut self.points);
+        // The filter lags behind the pointer, so the stroke is ended where it was released.
+        if let Some(last) = points.last().copied() {
+            if last.position != position {
+                points.push(RadiantStrokePoint {
+                    position,
+                    width: last.width,
+                });
+            }
+        }
+        let points = simplify_stroke(&points, SIMPLIFY_TOLERANCE);
+        Some(RadiantPathMessage::SetStrokePoints { id, points }.into())
+    }
+
+    fn cursor(&self, _hovered: Option<RadiantCursor>) -> RadiantCursor {
+        RadiantCursor::Crosshair
+    }
+}
@@ -1,3 +1,13 @@
+pub mod boolean;
+pub mod freehand_tool;
+pub mod path_message;
 pub mod path_node;
+pub mod pen_tool;
+pub mod stroke_node;
 
+pub use boolean::*;
+pub use freehand_tool::*;
+pub use path_message::*;
 pub use path_node::*;
+pub use pen_tool::*;
+pub use stroke_node::*;
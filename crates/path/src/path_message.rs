@@ -0,0 +1,38 @@
+use crate::{RadiantBooleanOp, RadiantStrokePoint};
+use macro_magic::export_tokens;
+use radiantkit_core::{RadiantFillRule, RadiantPathCommand};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[export_tokens]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RadiantPathMessage {
+    AddPath {
+        id: Option<Uuid>,
+        commands: Vec<RadiantPathCommand>,
+        #[serde(default)]
+        fill_rule: RadiantFillRule,
+    },
+    SetPathCommands {
+        id: Uuid,
+        commands: Vec<RadiantPathCommand>,
+    },
+    AddStroke {
+        id: Option<Uuid>,
+        points: Vec<RadiantStrokePoint>,
+    },
+    ExtendStroke {
+        id: Uuid,
+        points: Vec<RadiantStrokePoint>,
+    },
+    SetStrokePoints {
+        id: Uuid,
+        points: Vec<RadiantStrokePoint>,
+    },
+    /// Replaces the nodes with their combined outline, which takes the place of the first of
+    /// them. Nodes without an outline are left as they are.
+    BooleanOp {
+        op: RadiantBooleanOp,
+        ids: Vec<Uuid>,
+    },
+}
@@ -1,20 +1,98 @@
 use epaint::{ClippedPrimitive, ClippedShape, Rect, TessellationOptions};
+use lyon_tessellation::{
+    math::point, path::Path, BuffersBuilder, FillOptions, FillTessellator, FillVertex,
+    StrokeOptions, StrokeTessellator, StrokeVertex, VertexBuffers,
+};
 use radiantkit_core::{
-    get_color_for_node, BaseNode, RadiantNode, RadiantTessellatable, ScreenDescriptor, Vec3,
+    commands_bounds, flatten_commands, pick_color, picking_tessellation_options,
+    polygon_outline_contains, subpaths_contain, BaseNode, RadiantFillRule, RadiantFlatSubpath,
+    RadiantNode, RadiantPathCommand, RadiantTessellatable, RadiantVectorGeometry,
+    RadiantVectorNode, ScreenDescriptor, Vec3, DEFAULT_FLATTENING_TOLERANCE,
 };
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use uuid::Uuid;
 
+const STROKE_WIDTH: f32 = 1.0;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RadiantPathNode {
     pub base: BaseNode,
+    /// Outline relative to the node's position, scaled to the node's size.
+    #[serde(default)]
+    pub commands: Vec<RadiantPathCommand>,
+    #[serde(default)]
+    pub fill_rule: RadiantFillRule,
 }
 
 impl RadiantPathNode {
-    pub fn new(id: Uuid, position: [f32; 2]) -> Self {
-        let base = BaseNode::new(id, position.into(), Vec3::default());
-        Self { base }
+    /// A path drawing `commands`, which are in document coordinates. The node is positioned and
+    /// sized to their bounds.
+    pub fn new(id: Uuid, commands: Vec<RadiantPathCommand>) -> Self {
+        let base = BaseNode::new(id, Vec3::default(), Vec3::default());
+        let mut node = Self {
+            base,
+            commands: Vec::new(),
+            fill_rule: RadiantFillRule::default(),
+        };
+        node.set_commands(commands);
+        node
+    }
+
+    /// Replaces the outline with `commands`, which are in document coordinates, and moves and
+    /// resizes the node to their bounds.
+    pub fn set_commands(&mut self, commands: Vec<RadiantPathCommand>) {
+        let bounds = commands_bounds(&commands).unwrap_or_default();
+        let position = [bounds[0], bounds[1]];
+        let size = [bounds[2] - bounds[0], bounds[3] - bounds[1]];
+        self.commands = commands
+            .into_iter()
+            .map(|command| command.map(|[x, y]| [x - position[0], y - position[1]]))
+            .collect();
+        self.base.transform.set_position(&position.into());
+        self.base.transform.set_scale(&size.into());
+        self.update_bounding_rect();
+        self.base.set_needs_tessellation();
+    }
+
+    pub fn with_fill_rule(mut self, fill_rule: RadiantFillRule) -> Self {
+        self.fill_rule = fill_rule;
+        self.base.set_needs_tessellation();
+        self
+    }
+
+    fn update_bounding_rect(&mut self) {
+        let points: Vec<epaint::Pos2> = self
+            .subpaths()
+            .iter()
+            .flat_map(|subpath| subpath.points.iter().map(|[x, y]| epaint::pos2(*x, *y)))
+            .collect();
+        let rect = epaint::Rect::from_points(&points);
+        self.base
+            .set_bounding_rect([rect.min.x, rect.min.y, rect.max.x, rect.max.y]);
+    }
+
+    /// `commands` in document coordinates.
+    fn transformed_commands(&self) -> Vec<RadiantPathCommand> {
+        let position = self.base.transform.position();
+        let scale = self.base.transform.scale();
+        let bounds = commands_bounds(&self.commands).unwrap_or_default();
+        let factor = |size: f32, scale: f32| if size > 0.0 { scale / size } else { 1.0 };
+        let factor = [
+            factor(bounds[2] - bounds[0], scale.x),
+            factor(bounds[3] - bounds[1], scale.y),
+        ];
+        self.commands
+            .iter()
+            .map(|command| {
+                command.map(|[x, y]| [position.x + x * factor[0], position.y + y * factor[1]])
+            })
+            .collect()
+    }
+
+    /// The outline flattened to polylines, in document coordinates.
+    fn subpaths(&self) -> Vec<RadiantFlatSubpath> {
+        flatten_commands(&self.transformed_commands(), DEFAULT_FLATTENING_TOLERANCE)
     }
 
     fn tessellate(&mut self, pixels_per_point: f32) {
@@ -23,23 +101,16 @@ impl RadiantPathNode {
         }
         self.base.needs_tessellation = false;
 
-        let position = self.base.transform.position();
-        let scale = self.base.transform.scale();
+        let path = lyon_path(&self.transformed_commands());
+        let tolerance = DEFAULT_FLATTENING_TOLERANCE / pixels_per_point;
+        let fill = fill_vertices(&path, self.fill_rule, tolerance);
+        let stroke = stroke_vertices(&path, tolerance);
 
-        let points = vec![
-            position.into(),
-            epaint::Pos2::new(position.x + scale.x + 200.0, position.y + scale.y + 200.0),
-            epaint::Pos2::new(position.x + scale.x, position.y + scale.y + 400.0),
-            epaint::Pos2::new(position.x - 200.0, position.y + 200.0),
+        let color = self.base.color;
+        let shapes = vec![
+            mesh_shape(&fill, color.fill_color()),
+            mesh_shape(&stroke, color.stroke_color()),
         ];
-
-        let color = epaint::Color32::LIGHT_RED;
-        let stroke = epaint::Stroke::new(1.0, color);
-        let path_shape = epaint::PathShape::convex_polygon(points.clone(), color, stroke);
-        let shapes = vec![ClippedShape(
-            Rect::EVERYTHING,
-            epaint::Shape::Path(path_shape),
-        )];
         self.base.primitives = epaint::tessellator::tessellate_shapes(
             pixels_per_point,
             TessellationOptions::default(),
@@ -48,16 +119,11 @@ impl RadiantPathNode {
             shapes,
         );
 
-        let color = get_color_for_node(self.base.id);
-        let stroke = epaint::Stroke::new(1.0, color);
-        let path_shape = epaint::PathShape::convex_polygon(points, color, stroke);
-        let shapes = vec![ClippedShape(
-            Rect::EVERYTHING,
-            epaint::Shape::Path(path_shape),
-        )];
+        let color = pick_color(self.base.pick_id);
+        let shapes = vec![mesh_shape(&fill, color), mesh_shape(&stroke, color)];
         self.base.selection_primitives = epaint::tessellator::tessellate_shapes(
             pixels_per_point,
-            TessellationOptions::default(),
+            picking_tessellation_options(),
             [1, 1],
             vec![],
             shapes,
@@ -65,6 +131,131 @@ impl RadiantPathNode {
     }
 }
 
+fn lyon_path(commands: &[RadiantPathCommand]) -> Path {
+    let mut builder = Path::builder();
+    let mut open = false;
+    let mut start = [0.0, 0.0];
+    let mut last = start;
+    for command in commands {
+        match *command {
+            RadiantPathCommand::MoveTo { to } => {
+                if open {
+                    builder.end(false);
+                }
+                builder.begin(point(to[0], to[1]));
+                open = true;
+                start = to;
+            }
+            RadiantPathCommand::Close => {
+                if open {
+                    builder.end(true);
+                    open = false;
+                }
+                last = start;
+                continue;
+            }
+            _ => {
+                // Drawing without a move continues from the last point.
+                if !open {
+                    builder.begin(point(last[0], last[1]));
+                    open = true;
+                    start = last;
+                }
+                match *command {
+                    RadiantPathCommand::LineTo { to } => {
+                        builder.line_to(point(to[0], to[1]));
+                    }
+                    RadiantPathCommand::QuadTo { control, to } => {
+                        builder.quadratic_bezier_to(
+                            point(control[0], control[1]),
+                            point(to[0], to[1]),
+                        );
+                    }
+                    RadiantPathCommand::CubicTo {
+                        control1,
+                        control2,
+                        to,
+                    } => {
+                        builder.cubic_bezier_to(
+                            point(control1[0], control1[1]),
+                            point(control2[0], control2[1]),
+                            point(to[0], to[1]),
+                        );
+                    }
+                    RadiantPathCommand::MoveTo { .. } | RadiantPathCommand::Close => {}
+                }
+            }
+        }
+        if let Some(to) = command.end_point() {
+            last = to;
+        }
+    }
+    if open {
+        builder.end(false);
+    }
+    builder.build()
+}
+
+fn fill_vertices(
+    path: &Path,
+    fill_rule: RadiantFillRule,
+    tolerance: f32,
+) -> VertexBuffers<epaint::Pos2, u32> {
+    let fill_rule = match fill_rule {
+        RadiantFillRule::NonZero => lyon_tessellation::FillRule::NonZero,
+        RadiantFillRule::EvenOdd => lyon_tessellation::FillRule::EvenOdd,
+    };
+    let options = FillOptions::tolerance(tolerance).with_fill_rule(fill_rule);
+    let mut buffers = VertexBuffers::new();
+    let result = FillTessellator::new().tessellate_path(
+        path,
+        &options,
+        &mut BuffersBuilder::new(&mut buffers, |vertex: FillVertex| {
+            epaint::pos2(vertex.position().x, vertex.position().y)
+        }),
+    );
+    if let Err(error) = result {
+        log::warn!("Failed to fill path: {:?}", error);
+    }
+    buffers
+}
+
+fn stroke_vertices(path: &Path, tolerance: f32) -> VertexBuffers<epaint::Pos2, u32> {
+    let options = StrokeOptions::tolerance(tolerance).with_line_width(STROKE_WIDTH);
+    let mut buffers = VertexBuffers::new();
+    let result = StrokeTessellator::new().tessellate_path(
+        path,
+        &options,
+        &mut BuffersBuilder::new(&mut buffers, |vertex: StrokeVertex| {
+            epaint::pos2(vertex.position().x, vertex.position().y)
+        }),
+    );
+    if let Err(error) = result {
+        log::warn!("Failed to stroke path: {:?}", error);
+    }
+    buffers
+}
+
+pub(crate) fn mesh_shape(
+    buffers: &VertexBuffers<epaint::Pos2, u32>,
+    color: epaint::Color32,
+) -> ClippedShape {
+    let mesh = epaint::Mesh {
+        indices: buffers.indices.clone(),
+        vertices: buffers
+            .vertices
+            .iter()
+            .map(|pos| epaint::Vertex {
+                pos: *pos,
+                uv: epaint::WHITE_UV,
+                color,
+            })
+            .collect(),
+        texture_id: epaint::TextureId::default(),
+    };
+    ClippedShape(Rect::EVERYTHING, epaint::Shape::Mesh(mesh))
+}
+
 impl RadiantTessellatable for RadiantPathNode {
     fn attach(&mut self, screen_descriptor: &ScreenDescriptor) {
         self.tessellate(screen_descriptor.pixels_per_point);
@@ -76,27 +267,7 @@ impl RadiantTessellatable for RadiantPathNode {
     }
 
     fn set_needs_tessellation(&mut self, notify: bool) {
-        let position = self.base.transform.position();
-        let scale = self.base.transform.scale();
-
-        let points = vec![
-            position.into(),
-            epaint::Pos2::new(position.x + scale.x + 200.0, position.y + scale.y + 200.0),
-            epaint::Pos2::new(position.x + scale.x, position.y + scale.y + 400.0),
-            epaint::Pos2::new(position.x - 200.0, position.y + 200.0),
-        ];
-
-        let color = epaint::Color32::LIGHT_RED;
-        let stroke = epaint::Stroke::new(1.0, color);
-        let path_shape = epaint::PathShape::convex_polygon(points.clone(), color, stroke);
-        let bounding_rect = path_shape.visual_bounding_rect();
-        self.base.bounding_rect = [
-            bounding_rect.min.x,
-            bounding_rect.min.y,
-            bounding_rect.max.x,
-            bounding_rect.max.y,
-        ];
-
+        self.update_bounding_rect();
         self.base.set_needs_tessellation();
         if notify {
             self.base.notify(serde_json::to_string(self).unwrap());
@@ -126,4 +297,41 @@ impl RadiantNode for RadiantPathNode {
     fn base_mut(&mut self) -> &mut BaseNode {
         &mut self.base
     }
+
+    fn hit_test(&self, position: [f32; 2], tolerance: f32) -> bool {
+        let subpaths = self.subpaths();
+        subpaths_contain(&subpaths, position, self.fill_rule)
+            || subpaths
+                .iter()
+                .any(|subpath| polygon_outline_contains(&subpath.points, position, tolerance))
+    }
+
+    fn path_commands(&self) -> Option<Vec<RadiantPathCommand>> {
+        Some(self.transformed_commands())
+    }
+
+    fn set_path_commands(&mut self, commands: Vec<RadiantPathCommand>) -> bool {
+        self.set_commands(commands);
+        true
+    }
+
+    fn to_vector(&self) -> Option<RadiantVectorNode> {
+        if self.commands.is_empty() {
+            return None;
+        }
+        let origin = self.base.bounding_rect;
+        let commands = self
+            .transformed_commands()
+            .into_iter()
+            .map(|command| command.map(|[x, y]| [x - origin[0], y - origin[1]]))
+            .collect();
+        Some(RadiantVectorNode::new(
+            &self.base,
+            RadiantVectorGeometry::Path {
+                commands,
+                fill_rule: self.fill_rule,
+            },
+            STROKE_WIDTH,
+        ))
+    }
 }
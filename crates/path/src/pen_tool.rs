@@ -0,0 +1,215 @@
+use crate::RadiantPathMessage;
+use epaint::{ClippedShape, Color32, Rect, Shape, Stroke};
+use radiantkit_core::{
+    flatten_commands, KeyCode, RadiantCursor, RadiantKeyModifiers, RadiantPathCommand, RadiantTool,
+    DEFAULT_FLATTENING_TOLERANCE,
+};
+use uuid::Uuid;
+
+/// Distance (in points) within which a click on the first point closes the path.
+const CLOSE_DISTANCE: f32 = 6.0;
+/// Distance (in points) the mouse must be dragged before it pulls out handles.
+const DRAG_DISTANCE: f32 = 2.0;
+
+const PREVIEW_COLOR: Color32 = Color32::LIGHT_BLUE;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PenAnchor {
+    point: [f32; 2],
+    /// Outgoing handle. The incoming handle mirrors it around the point.
+    handle: Option<[f32; 2]>,
+}
+
+impl PenAnchor {
+    fn handle_in(&self) -> Option<[f32; 2]> {
+        self.handle
+            .map(|[x, y]| [2.0 * self.point[0] - x, 2.0 * self.point[1] - y])
+    }
+}
+
+/// Draws Bezier paths. Clicks add corner points, dragging pulls out handles, clicking the
+/// first point closes the path and Enter or Escape finishes it.
+pub struct PenTool {
+    active_node_id: Option<Uuid>,
+    anchors: Vec<PenAnchor>,
+    dragging: bool,
+    cursor_position: [f32; 2],
+}
+
+impl PenTool {
+    pub fn new() -> Self {
+        Self {
+            active_node_id: None,
+            anchors: Vec::new(),
+            dragging: false,
+            cursor_position: [0.0, 0.0],
+        }
+    }
+
+    fn commands(&self, closed: bool) -> Vec<RadiantPathCommand> {
+        let mut commands = Vec::new();
+        let Some(first) = self.anchors.first() else {
+            return commands;
+        };
+        commands.push(RadiantPathCommand::MoveTo { to: first.point });
+        for pair in self.anchors.windows(2) {
+            commands.push(segment(&pair[0], &pair[1]));
+        }
+        if closed {
+            if let Some(last) = self.anchors.last() {
+                if last.handle.is_some() || first.handle.is_some() {
+                    commands.push(segment(last, first));
+                }
+            }
+            commands.push(RadiantPathCommand::Close);
+        }
+        commands
+    }
+
+    /// Sends the current outline, adding the path node on its first segment.
+    fn update_path<M: From<RadiantPathMessage>>(&mut self, closed: bool) -> Option<M> {
+        if self.anchors.len() < 2 {
+            return None;
+        }
+        let commands = self.commands(closed);
+        let message = match self.active_node_id {
+            Some(id) => RadiantPathMessage::SetPathCommands { id, commands },
+            None => {
+                let id = Uuid::new_v4();
+                self.active_node_id = Some(id);
+                RadiantPathMessage::AddPath {
+                    id: Some(id),
+                    commands,
+                    fill_rule: Default::default(),
+                }
+            }
+        };
+        Some(message.into())
+    }
+
+    fn finish(&mut self) {
+        self.active_node_id = None;
+        self.anchors.clear();
+        self.dragging = false;
+    }
+}
+
+impl Default for PenTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn segment(from: &PenAnchor, to: &PenAnchor) -> RadiantPathCommand {
+    match (from.handle, to.handle_in()) {
+        (None, None) => RadiantPathCommand::LineTo { to: to.point },
+        (control1, control2) => RadiantPathCommand::CubicTo {
+            control1: control1.unwrap_or(from.point),
+            control2: control2.unwrap_or(to.point),
+            to: to.point,
+        },
+    }
+}
+
+fn distance(a: [f32; 2], b: [f32; 2]) -> f32 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt()
+}
+
+impl<M: From<RadiantPathMessage>> RadiantTool<M> for PenTool {
+    fn on_mouse_down(&mut self, _node_id: Option<Uuid>, position: [f32; 2]) -> Option<M> {
+        self.cursor_position = position;
+        if let Some(first) = self.anchors.first() {
+            if self.anchors.len() > 1 && distance(first.point, position) <= CLOSE_DISTANCE {
+                let message = self.update_path(true);
+                self.finish();
+                return message;
+            }
+        }
+        self.anchors.push(PenAnchor {
+            point: position,
+            handle: None,
+        });
+        self.dragging = true;
+        self.update_path(false)
+    }
+
+    fn on_mouse_move(&mut self, position: [f32; 2]) -> Option<M> {
+        self.cursor_position = position;
+        if !self.dragging {
+            return None;
+        }
+        let anchor = self.anchors.last_mut()?;
+        let handle = (distance(anchor.point, position) > DRAG_DISTANCE).then_some(position);
+        if anchor.handle == handle {
+            return None;
+        }
+        anchor.handle = handle;
+        self.update_path(false)
+    }
+
+    fn on_mouse_up(&mut self, position: [f32; 2]) -> Option<M> {
+        self.cursor_position = position;
+        self.dragging = false;
+        None
+    }
+
+    fn on_key_down(&mut self, key: KeyCode, _modifiers: RadiantKeyModifiers) -> Option<M> {
+        if matches!(key, KeyCode::Enter | KeyCode::Escape) {
+            self.finish();
+        }
+        None
+    }
+
+    fn cursor(&self, _hovered: Option<RadiantCursor>) -> RadiantCursor {
+        RadiantCursor::Crosshair
+    }
+
+    fn overlay(&self) -> Vec<ClippedShape> {
+        let Some(last) = self.anchors.last() else {
+            return Vec::new();
+        };
+        let stroke = Stroke::new(1.0, PREVIEW_COLOR);
+        let mut shapes = Vec::new();
+
+        // The segment the next click would add.
+        if !self.dragging {
+            let next = PenAnchor {
+                point: self.cursor_position,
+                handle: None,
+            };
+            let mut commands = vec![RadiantPathCommand::MoveTo { to: last.point }];
+            commands.push(segment(last, &next));
+            shapes.push(commands_shape(&commands, stroke));
+        }
+        if let Some(handle) = last.handle {
+            for point in [Some(handle), last.handle_in()].into_iter().flatten() {
+                shapes.push(Shape::line_segment(
+                    [last.point.into(), point.into()],
+                    stroke,
+                ));
+                shapes.push(Shape::circle_filled(point.into(), 3.0, PREVIEW_COLOR));
+            }
+        }
+        for anchor in &self.anchors {
+            let rect = Rect::from_center_size(anchor.point.into(), epaint::vec2(6.0, 6.0));
+            shapes.push(Shape::rect_filled(rect, 0.0, Color32::WHITE));
+            shapes.push(Shape::rect_stroke(rect, 0.0, stroke));
+        }
+
+        shapes
+            .into_iter()
+            .map(|shape| ClippedShape(Rect::EVERYTHING, shape))
+            .collect()
+    }
+}
+
+fn commands_shape(commands: &[RadiantPathCommand], stroke: Stroke) -> Shape {
+    let shapes = flatten_commands(commands, DEFAULT_FLATTENING_TOLERANCE)
+        .into_iter()
+        .map(|subpath| {
+            let points = subpath.points.into_iter().map(Into::into).collect();
+            Shape::line(points, stroke)
+        })
+        .collect();
+    Shape::Vec(shapes)
+}
@@ -0,0 +1,501 @@
+use crate::path_node::mesh_shape;
+use epaint::{ClippedPrimitive, Primitive, TessellationOptions};
+use lyon_tessellation::{
+    math::point, path::Path, BuffersBuilder, LineCap, LineJoin, StrokeOptions, StrokeTessellator,
+    StrokeVertex, VertexBuffers,
+};
+use radiantkit_core::{
+    distance_to_segment, flatten_commands, pick_color, picking_tessellation_options, BaseNode,
+    RadiantNode, RadiantPathCommand, RadiantTessellatable, RadiantVectorGeometry,
+    RadiantVectorNode, ScreenDescriptor, Vec3, DEFAULT_FLATTENING_TOLERANCE, MIN_SIZE,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use uuid::Uuid;
+
+/// Points added by [`RadiantStrokeNode::extend_points`] before the stroke is sent to observers
+/// again, so that collaborators see it while it is drawn.
+const STREAMED_POINTS: usize = 8;
+
+/// A sample of a freehand stroke.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RadiantStrokePoint {
+    pub position: [f32; 2],
+    pub width: f32,
+}
+
+/// A freehand stroke. The curve passes through every point, and its width varies from point
+/// to point.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RadiantStrokeNode {
+    pub base: BaseNode,
+    /// Points relative to the node's position, scaled to the node's size.
+    pub points: Vec<RadiantStrokePoint>,
+    /// First curve segment changed by [`Self::extend_points`] since the last tessellation.
+    #[serde(skip)]
+    pending_segment: Option<usize>,
+    /// Number of points when the stroke was last sent to observers.
+    #[serde(skip)]
+    sent_points: usize,
+}
+
+impl RadiantStrokeNode {
+    /// A stroke through `points`, which are in document coordinates.
+    pub fn new(id: Uuid, points: Vec<RadiantStrokePoint>) -> Self {
+        let mut base = BaseNode::new(id, Vec3::default(), Vec3::default());
+        base.color.set_stroke_color(epaint::Color32::WHITE);
+        let mut node = Self {
+            base,
+            points: Vec::new(),
+            pending_segment: None,
+            sent_points: 0,
+        };
+        node.set_points(points);
+        node.sent_points = node.points.len();
+        node
+    }
+
+    /// Replaces the points with `points`, which are in document coordinates, and moves and
+    /// resizes the node to their bounds.
+    pub fn set_points(&mut self, points: Vec<RadiantStrokePoint>) {
+        let [min_x, min_y, max_x, max_y] = points_bounds(&points);
+        self.points = points
+            .into_iter()
+            .map(|point| RadiantStrokePoint {
+                position: [point.position[0] - min_x, point.position[1] - min_y],
+                width: point.width,
+            })
+            .collect();
+        self.base.transform.set_position(&[min_x, min_y].into());
+        self.base
+            .transform
+            .set_scale(&[max_x - min_x, max_y - min_y].into());
+        self.update_bounding_rect();
+        self.base.set_needs_tessellation();
+    }
+
+    /// Adds `points`, which are in document coordinates, to the end of the stroke. Only the
+    /// curve segments they change are measured and tessellated again. Every few points, the
+    /// stroke is sent to observers.
+    pub fn extend_points(&mut self, points: impl IntoIterator<Item = RadiantStrokePoint>) {
+        self.append_points(points);
+        if self.points.len() >= self.sent_points + STREAMED_POINTS {
+            self.sent_points = self.points.len();
+            self.base.notify(serde_json::to_string(self).unwrap());
+        }
+    }
+
+    fn append_points(&mut self, points: impl IntoIterator<Item = RadiantStrokePoint>) {
+        let position = self.base.transform.position();
+        let scale = self.base.transform.scale();
+        let [min_x, min_y, max_x, max_y] = points_bounds(&self.points);
+        if scale_factor(max_x - min_x, scale.x) != 1.0
+            || scale_factor(max_y - min_y, scale.y) != 1.0
+        {
+            // Resized strokes are rebuilt from their document coordinates.
+            let mut all = self.transformed_points();
+            all.extend(points);
+            self.set_points(all);
+            return;
+        }
+
+        let start = self.points.len();
+        self.points
+            .extend(points.into_iter().map(|point| RadiantStrokePoint {
+                position: [
+                    point.position[0] - position.x,
+                    point.position[1] - position.y,
+                ],
+                width: point.width,
+            }));
+        if self.points.len() == start {
+            return;
+        }
+
+        // Points left of or above the stroke move its origin.
+        let [new_min_x, new_min_y, new_max_x, new_max_y] = points_bounds(&self.points[start..]);
+        let offset = [new_min_x.min(0.0), new_min_y.min(0.0)];
+        if offset != [0.0, 0.0] {
+            for point in &mut self.points {
+                point.position[0] -= offset[0];
+                point.position[1] -= offset[1];
+            }
+            self.base
+                .transform
+                .set_position(&[position.x + offset[0], position.y + offset[1]].into());
+        }
+        self.base.transform.set_scale(
+            &[
+                new_max_x.max(max_x) - offset[0],
+                new_max_y.max(max_y) - offset[1],
+            ]
+            .into(),
+        );
+
+        // The segment ending at the previous last point bends towards the new points.
+        let first_segment = start.saturating_sub(2);
+        self.extend_bounding_rect(first_segment);
+        if !self.base.needs_tessellation {
+            self.pending_segment = Some(
+                self.pending_segment
+                    .map_or(first_segment, |pending| pending.min(first_segment)),
+            );
+            self.base.revision += 1;
+        }
+    }
+
+    /// The points in document coordinates.
+    pub fn transformed_points(&self) -> Vec<RadiantStrokePoint> {
+        let position = self.base.transform.position();
+        let scale = self.base.transform.scale();
+        let bounds = points_bounds(&self.points);
+        let factor = [
+            scale_factor(bounds[2] - bounds[0], scale.x),
+            scale_factor(bounds[3] - bounds[1], scale.y),
+        ];
+        self.points
+            .iter()
+            .map(|point| RadiantStrokePoint {
+                position: [
+                    position.x + point.position[0] * factor[0],
+                    position.y + point.position[1] * factor[1],
+                ],
+                width: point.width,
+            })
+            .collect()
+    }
+
+    fn max_width(&self) -> f32 {
+        self.points
+            .iter()
+            .map(|point| point.width)
+            .fold(0.0, f32::max)
+    }
+
+    fn update_bounding_rect(&mut self) {
+        let rect = segments_rect(&self.transformed_points(), 0);
+        self.base
+            .set_bounding_rect([rect.min.x, rect.min.y, rect.max.x, rect.max.y]);
+    }
+
+    /// Grows the bounds to the segments from `first_segment` on, for unscaled strokes.
+    fn extend_bounding_rect(&mut self, first_segment: usize) {
+        let position = self.base.transform.position();
+        let tail = self.points[first_segment.saturating_sub(1)..]
+            .iter()
+            .map(|point| RadiantStrokePoint {
+                position: [
+                    position.x + point.position[0],
+                    position.y + point.position[1],
+                ],
+                width: point.width,
+            })
+            .collect::<Vec<_>>();
+        let rect = segments_rect(&tail, first_segment.min(1));
+        let [min_x, min_y, max_x, max_y] = self.base.bounding_rect;
+        let rect = if max_x > min_x || max_y > min_y {
+            rect.union(epaint::Rect::from_min_max(
+                epaint::pos2(min_x, min_y),
+                epaint::pos2(max_x, max_y),
+            ))
+        } else {
+            rect
+        };
+        self.base
+            .set_bounding_rect([rect.min.x, rect.min.y, rect.max.x, rect.max.y]);
+    }
+
+    fn tessellate(&mut self, pixels_per_point: f32) {
+        let first_segment = if self.base.needs_tessellation {
+            0
+        } else if let Some(pending) = self.pending_segment {
+            pending
+        } else {
+            return;
+        };
+        self.pending_segment = None;
+
+        // Extended strokes only tessellate their new segments, drawn over the old ones.
+        let tolerance = DEFAULT_FLATTENING_TOLERANCE / pixels_per_point;
+        let stroke = stroke_vertices(&self.transformed_points(), first_segment, tolerance);
+
+        let shapes = vec![mesh_shape(&stroke, self.base.color.stroke_color())];
+        let primitives = epaint::tessellator::tessellate_shapes(
+            pixels_per_point,
+            TessellationOptions::default(),
+            [1, 1],
+            vec![],
+            shapes,
+        );
+
+        let shapes = vec![mesh_shape(&stroke, pick_color(self.base.pick_id))];
+        let selection_primitives = epaint::tessellator::tessellate_shapes(
+            pixels_per_point,
+            picking_tessellation_options(),
+            [1, 1],
+            vec![],
+            shapes,
+        );
+
+        if self.base.needs_tessellation {
+            self.base.needs_tessellation = false;
+            self.base.primitives = primitives;
+            self.base.selection_primitives = selection_primitives;
+        } else {
+            append_primitives(&mut self.base.primitives, primitives);
+            append_primitives(&mut self.base.selection_primitives, selection_primitives);
+        }
+    }
+}
+
+fn points_bounds(points: &[RadiantStrokePoint]) -> [f32; 4] {
+    points
+        .iter()
+        .map(|point| {
+            let [x, y] = point.position;
+            [x, y, x, y]
+        })
+        .reduce(|a, b| {
+            [
+                a[0].min(b[0]),
+                a[1].min(b[1]),
+                a[2].max(b[2]),
+                a[3].max(b[3]),
+            ]
+        })
+        .unwrap_or_default()
+}
+
+/// How far points spanning `size` are stretched to fill `scale`. Strokes thinner than
+/// [`MIN_SIZE`] keep their shape, as the transform can't be scaled below it.
+fn scale_factor(size: f32, scale: f32) -> f32 {
+    if size > 0.0 && scale != size.max(MIN_SIZE) {
+        scale / size
+    } else {
+        1.0
+    }
+}
+
+/// Merges the meshes of `primitives` into the last mesh of `target`.
+fn append_primitives(target: &mut Vec<ClippedPrimitive>, primitives: Vec<ClippedPrimitive>) {
+    for primitive in primitives {
+        match (target.last_mut(), primitive.primitive) {
+            (
+                Some(ClippedPrimitive {
+                    primitive: Primitive::Mesh(last),
+                    ..
+                }),
+                Primitive::Mesh(mesh),
+            ) if last.texture_id == mesh.texture_id => last.append(mesh),
+            (_, other) => target.push(ClippedPrimitive {
+                clip_rect: primitive.clip_rect,
+                primitive: other,
+            }),
+        }
+    }
+}
+
+/// The Catmull-Rom spline through `points`, as one cubic per pair of points.
+fn catmull_rom_controls(points: &[RadiantStrokePoint]) -> Vec<([f32; 2], [f32; 2])> {
+    let position = |index: usize| points[index.min(points.len() - 1)].position;
+    (0..points.len().saturating_sub(1))
+        .map(|i| {
+            let [p0, p1, p2, p3] = [
+                position(i.saturating_sub(1)),
+                position(i),
+                position(i + 1),
+                position(i + 2),
+            ];
+            (
+                [p1[0] + (p2[0] - p0[0]) / 6.0, p1[1] + (p2[1] - p0[1]) / 6.0],
+                [p2[0] - (p3[0] - p1[0]) / 6.0, p2[1] - (p3[1] - p1[1]) / 6.0],
+            )
+        })
+        .collect()
+}
+
+/// The spline segments starting at the point `first` on. Each segment only depends on the
+/// points around it, so the ones before are left out without changing the rest.
+fn spline_segments(
+    points: &[RadiantStrokePoint],
+    first: usize,
+) -> Vec<([f32; 2], [f32; 2], RadiantStrokePoint)> {
+    let start = first.saturating_sub(1);
+    let window = &points[start.min(points.len())..];
+    catmull_rom_controls(window)
+        .into_iter()
+        .zip(window.iter().skip(1))
+        .skip(first - start)
+        .map(|((control1, control2), to)| (control1, control2, *to))
+        .collect()
+}
+
+fn stroke_commands(points: &[RadiantStrokePoint]) -> Vec<RadiantPathCommand> {
+    let Some(first) = points.first() else {
+        return Vec::new();
+    };
+    std::iter::once(RadiantPathCommand::MoveTo { to: first.position })
+        .chain(
+            spline_segments(points, 0)
+                .into_iter()
+                .map(|(control1, control2, to)| RadiantPathCommand::CubicTo {
+                    control1,
+                    control2,
+                    to: to.position,
+                }),
+        )
+        .collect()
+}
+
+/// Bounds of the stroke from the point `first` on, widths included.
+fn segments_rect(points: &[RadiantStrokePoint], first: usize) -> epaint::Rect {
+    let Some(start) = points.get(first) else {
+        return epaint::Rect::NOTHING;
+    };
+    let commands = std::iter::once(RadiantPathCommand::MoveTo { to: start.position })
+        .chain(
+            spline_segments(points, first)
+                .into_iter()
+                .map(|(control1, control2, to)| RadiantPathCommand::CubicTo {
+                    control1,
+                    control2,
+                    to: to.position,
+                }),
+        )
+        .collect::<Vec<_>>();
+    let positions: Vec<epaint::Pos2> = flatten_commands(&commands, DEFAULT_FLATTENING_TOLERANCE)
+        .iter()
+        .flat_map(|subpath| subpath.points.iter().map(|[x, y]| epaint::pos2(*x, *y)))
+        .chain(std::iter::once(start.position.into()))
+        .collect();
+    let width = points[first..]
+        .iter()
+        .map(|point| point.width)
+        .fold(0.0, f32::max);
+    epaint::Rect::from_points(&positions).expand(width / 2.0)
+}
+
+/// Outline of the stroke from the point `first` on.
+fn stroke_vertices(
+    points: &[RadiantStrokePoint],
+    first: usize,
+    tolerance: f32,
+) -> VertexBuffers<epaint::Pos2, u32> {
+    let mut buffers = VertexBuffers::new();
+    let Some(start) = points.get(first) else {
+        return buffers;
+    };
+
+    // Widths are carried as a custom attribute and interpolated along each curve.
+    let mut builder = Path::builder_with_attributes(1);
+    builder.begin(point(start.position[0], start.position[1]), &[start.width]);
+    for (control1, control2, to) in spline_segments(points, first) {
+        builder.cubic_bezier_to(
+            point(control1[0], control1[1]),
+            point(control2[0], control2[1]),
+            point(to.position[0], to.position[1]),
+            &[to.width],
+        );
+    }
+    builder.end(false);
+    let path = builder.build();
+
+    let options = StrokeOptions::tolerance(tolerance)
+        .with_line_width(1.0)
+        .with_variable_line_width(0)
+        .with_line_cap(LineCap::Round)
+        .with_line_join(LineJoin::Round);
+    let result = StrokeTessellator::new().tessellate_path(
+        &path,
+        &options,
+        &mut BuffersBuilder::new(&mut buffers, |vertex: StrokeVertex| {
+            epaint::pos2(vertex.position().x, vertex.position().y)
+        }),
+    );
+    if let Err(error) = result {
+        log::warn!("Failed to stroke freehand path: {:?}", error);
+    }
+    buffers
+}
+
+impl RadiantTessellatable for RadiantStrokeNode {
+    fn attach(&mut self, screen_descriptor: &ScreenDescriptor) {
+        self.tessellate(screen_descriptor.pixels_per_point);
+    }
+
+    fn detach(&mut self) {
+        self.base.primitives.clear();
+        self.base.selection_primitives.clear();
+    }
+
+    fn set_needs_tessellation(&mut self, notify: bool) {
+        self.update_bounding_rect();
+        self.base.set_needs_tessellation();
+        if notify {
+            self.base.notify(serde_json::to_string(self).unwrap());
+        }
+    }
+
+    fn tessellate(
+        &mut self,
+        selection: bool,
+        screen_descriptor: &ScreenDescriptor,
+        _fonts_manager: &epaint::text::Fonts,
+    ) -> Vec<ClippedPrimitive> {
+        self.tessellate(screen_descriptor.pixels_per_point);
+        if selection {
+            self.base.selection_primitives.clone()
+        } else {
+            self.base.primitives.clone()
+        }
+    }
+}
+
+impl RadiantNode for RadiantStrokeNode {
+    fn base(&self) -> &BaseNode {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut BaseNode {
+        &mut self.base
+    }
+
+    fn hit_test(&self, position: [f32; 2], tolerance: f32) -> bool {
+        let points = self.transformed_points();
+        let reach = self.max_width() / 2.0 + tolerance;
+        if let [point] = points.as_slice() {
+            return distance_to_segment(position, point.position, point.position) <= reach;
+        }
+        flatten_commands(&stroke_commands(&points), DEFAULT_FLATTENING_TOLERANCE)
+            .iter()
+            .any(|subpath| {
+                subpath
+                    .points
+                    .windows(2)
+                    .any(|segment| distance_to_segment(position, segment[0], segment[1]) <= reach)
+            })
+    }
+
+    /// Vector formats have no variable width strokes, so the stroke is exported at its widest.
+    fn to_vector(&self) -> Option<RadiantVectorNode> {
+        let points = self.transformed_points();
+        if points.is_empty() {
+            return None;
+        }
+        let [x, y] = [self.base.bounding_rect[0], self.base.bounding_rect[1]];
+        let commands = stroke_commands(&points)
+            .into_iter()
+            .map(|command| command.map(|[px, py]| [px - x, py - y]))
+            .collect();
+        let mut node = RadiantVectorNode::new(
+            &self.base,
+            RadiantVectorGeometry::Path {
+                commands,
+                fill_rule: Default::default(),
+            },
+            self.max_width(),
+        );
+        node.fill = None;
+        Some(node)
+    }
+}
@@ -1,5 +1,11 @@
+pub mod text_layout;
 pub mod text_message;
 pub mod text_node;
+pub mod text_selection;
+pub mod text_style;
 
+pub use text_layout::*;
 pub use text_message::*;
 pub use text_node::*;
+pub use text_selection::*;
+pub use text_style::*;
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+/// How a text box gets its size.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum RadiantTextSizing {
+    /// Lines never wrap, and the box fits them.
+    #[default]
+    AutoWidth,
+    /// Lines wrap at the box's width, and its height fits them.
+    AutoHeight,
+    /// Lines wrap at the box's width, and its height is kept.
+    Fixed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum RadiantTextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+    /// Wrapped lines are stretched to the box's width. The last line of a paragraph is not.
+    Justify,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum RadiantTextVerticalAlign {
+    #[default]
+    Top,
+    Middle,
+    Bottom,
+}
+
+/// How a text is laid out in its box.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct RadiantTextLayout {
+    pub sizing: RadiantTextSizing,
+    pub align: RadiantTextAlign,
+    /// Only matters for [`RadiantTextSizing::Fixed`] boxes, the others fit their lines.
+    pub vertical_align: RadiantTextVerticalAlign,
+    /// Distance between lines, as a multiple of the height the font gives them.
+    pub line_height: f32,
+    /// Extra space (in points) between characters.
+    pub letter_spacing: f32,
+}
+
+impl Default for RadiantTextLayout {
+    fn default() -> Self {
+        Self {
+            sizing: RadiantTextSizing::default(),
+            align: RadiantTextAlign::default(),
+            vertical_align: RadiantTextVerticalAlign::default(),
+            line_height: 1.0,
+            letter_spacing: 0.0,
+        }
+    }
+}
@@ -1,7 +1,10 @@
 use macro_magic::export_tokens;
 use serde::{Deserialize, Serialize};
+use std::ops::Range;
 use uuid::Uuid;
 
+use crate::{RadiantTextLayout, RadiantTextStyle};
+
 #[export_tokens]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RadiantAddTextMessage {
@@ -10,13 +13,29 @@ pub enum RadiantAddTextMessage {
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RadiantTextMessage {
-    SetText { id: Uuid, text: String },
+    SetText {
+        id: Uuid,
+        text: String,
+    },
+    /// Styles the characters in `range`.
+    SetTextStyle {
+        id: Uuid,
+        range: Range<usize>,
+        style: RadiantTextStyle,
+    },
+    /// Sizing, alignment and spacing of the text in its box.
+    SetTextLayout {
+        id: Uuid,
+        layout: RadiantTextLayout,
+    },
 }
 
 impl RadiantTextMessage {
     pub fn id(&self) -> Uuid {
         match self {
-            RadiantTextMessage::SetText { id, .. } => *id,
+            RadiantTextMessage::SetText { id, .. }
+            | RadiantTextMessage::SetTextStyle { id, .. }
+            | RadiantTextMessage::SetTextLayout { id, .. } => *id,
         }
     }
 }
@@ -1,33 +1,75 @@
-use epaint::emath::NumExt;
+use epaint::emath::{Align, NumExt};
 use epaint::{
-    text::{LayoutJob, TextFormat},
-    ClippedPrimitive, ClippedShape, Color32, FontFamily, FontId, Fonts, Rect, TessellationOptions,
+    text::{cursor::CCursor, Galley, Glyph, LayoutJob, TextFormat},
+    ClippedPrimitive, ClippedShape, Color32, FontFamily, FontId, Fonts, Mesh, Pos2, Rect, Stroke,
+    TessellationOptions,
 };
 use once_cell::sync::Lazy;
 use radiantkit_core::{
-    get_color_for_node, BaseNode, RadiantLineNode, RadiantNode, RadiantTessellatable,
-    ScreenDescriptor,
+    bold_family, pick_color, picking_tessellation_options, BaseNode, KeyCode, RadiantImeEvent,
+    RadiantKeyModifiers, RadiantLineNode, RadiantNode, RadiantTessellatable, RadiantTextDelta,
+    RadiantVectorGeometry, RadiantVectorNode, RadiantVectorTextRun, ScreenDescriptor,
 };
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::fmt::Debug;
+use std::ops::Range;
+use std::sync::Arc;
+use unicode_segmentation::UnicodeSegmentation;
 use uuid::Uuid;
 
-use crate::RadiantTextMessage;
+use crate::{
+    RadiantTextAlign, RadiantTextLayout, RadiantTextMessage, RadiantTextSelection,
+    RadiantTextSizing, RadiantTextSpans, RadiantTextStyle, RadiantTextVerticalAlign,
+};
 
 const CURSOR_NODE_ID: Lazy<Uuid> = Lazy::new(|| Uuid::new_v4());
+/// Size text is laid out at unless styled otherwise.
+pub const FONT_SIZE: f32 = 24.0;
+/// Drawn behind selected characters.
+const SELECTION_COLOR: Color32 = Color32::from_rgba_premultiplied(0, 64, 128, 128);
+/// Smallest change (in points) to a box's size that counts as a resize.
+const RESIZE_EPSILON: f32 = 0.01;
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct RadiantTextNode {
     pub base: BaseNode,
     pub text: String,
+    #[serde(default)]
+    pub spans: RadiantTextSpans,
+    #[serde(default)]
+    pub layout: RadiantTextLayout,
+    #[serde(skip)]
+    pub cursor_node: Box<RadiantLineNode>,
+    /// Caret and selected characters while the text is edited.
+    #[serde(skip)]
+    pub selection: RadiantTextSelection,
+    /// Whether presses place the caret, rather than move the node. Set by a double click.
+    #[serde(skip)]
+    pub editing: bool,
+    /// Text an input method is composing at the caret. It is shown but not part of the text.
+    #[serde(skip)]
+    pub preedit: String,
+    /// Layout from the last tessellation, for placing the caret.
+    #[serde(skip)]
+    galley: Option<Arc<Galley>>,
+    /// Where the galley was last drawn.
     #[serde(skip)]
-    pub cursor_node: RadiantLineNode,
+    galley_origin: epaint::Pos2,
+    /// Box size of the last layout, to tell a resize from the box fitting its text.
+    #[serde(skip)]
+    measured: Option<[f32; 2]>,
 }
 
 impl Debug for RadiantTextNode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RadiantTextNode")
             .field("base", &self.base)
+            .field("spans", &self.spans)
+            .field("layout", &self.layout)
+            .field("selection", &self.selection)
+            .field("editing", &self.editing)
+            .field("preedit", &self.preedit)
             .finish()
     }
 }
@@ -35,13 +77,165 @@ impl Debug for RadiantTextNode {
 impl RadiantTextNode {
     pub fn new(id: Uuid, text: String, position: [f32; 2], scale: [f32; 2]) -> Self {
         let base = BaseNode::new(id, position.into(), scale.into());
-        let cursor_node = RadiantLineNode::new(*CURSOR_NODE_ID, [0.0, 0.0], [0.0, 0.0]);
+        let cursor_node = Box::new(RadiantLineNode::new(
+            *CURSOR_NODE_ID,
+            [0.0, 0.0],
+            [0.0, 0.0],
+        ));
+        let spans = RadiantTextSpans::new(text.chars().count(), Default::default());
+        let selection = RadiantTextSelection::caret(text.chars().count());
 
         Self {
             base,
             text,
+            spans,
             cursor_node,
+            layout: RadiantTextLayout::default(),
+            selection,
+            editing: false,
+            preedit: String::new(),
+            galley: None,
+            galley_origin: epaint::Pos2::ZERO,
+            measured: None,
+        }
+    }
+
+    /// The text with every character in `style`.
+    pub fn with_style(mut self, style: RadiantTextStyle) -> Self {
+        self.spans = RadiantTextSpans::new(self.text.chars().count(), style);
+        self
+    }
+
+    /// The text laid out in `layout`.
+    pub fn with_layout(mut self, layout: RadiantTextLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Width lines wrap at, if they wrap.
+    fn wrap_width(&self) -> f32 {
+        match self.layout.sizing {
+            RadiantTextSizing::AutoWidth => f32::INFINITY,
+            _ => self.base.transform.scale().x.max(1.0),
+        }
+    }
+
+    /// One section per run, with a line break before each character in `breaks`. Letter
+    /// spaced text is wrapped and aligned by [`space_letters`] instead, as epaint measures rows
+    /// without the spacing.
+    fn layout_job(&self, fonts: &Fonts, breaks: &[usize]) -> LayoutJob {
+        let families = fonts.families();
+        let layout = self.layout;
+        let mut job = LayoutJob::default();
+        if layout.letter_spacing == 0.0 {
+            job.wrap.max_width = self.wrap_width();
+            job.halign = match layout.align {
+                RadiantTextAlign::Center => Align::Center,
+                RadiantTextAlign::Right => Align::Max,
+                RadiantTextAlign::Left | RadiantTextAlign::Justify => Align::Min,
+            };
+            job.justify =
+                layout.align == RadiantTextAlign::Justify && job.wrap.max_width.is_finite();
+        }
+
+        let (text, spans) = self.composed();
+        let (mut start, mut end_char) = (0, 0);
+        let mut breaks = breaks.iter().peekable();
+        for span in spans.iter() {
+            end_char += span.length;
+            let end = byte_index(&text, end_char);
+            let style = &span.style;
+            let line = |enabled: bool| {
+                if enabled {
+                    Stroke::new((style.size / 16.0).max(1.0), style.color)
+                } else {
+                    Stroke::NONE
+                }
+            };
+            let format = TextFormat {
+                font_id: FontId::new(style.size, run_family(&families, style)),
+                color: style.color,
+                italics: style.italic,
+                underline: line(style.underline),
+                strikethrough: line(style.strikethrough),
+                ..Default::default()
+            };
+            while let Some(index) = breaks.next_if(|index| **index < end_char) {
+                let split = byte_index(&text, *index);
+                job.append(&text[start..split], 0.0, format.clone());
+                job.append("\n", 0.0, format.clone());
+                start = split;
+            }
+            job.append(&text[start..end], 0.0, format);
+            start = end;
+        }
+        job
+    }
+
+    /// The text laid out in the box, rows spaced apart by the line height.
+    fn layout(&self, fonts: &Fonts) -> Arc<Galley> {
+        let layout = self.layout;
+        let mut galley = fonts.layout_job(self.layout_job(fonts, &[]));
+        if layout.letter_spacing != 0.0 {
+            let width = self.wrap_width();
+            let breaks = row_breaks(&galley, layout.letter_spacing, width);
+            if !breaks.is_empty() {
+                galley = fonts.layout_job(self.layout_job(fonts, &breaks));
+            }
+            galley = space_letters(&galley, &breaks, layout.letter_spacing, layout.align, width);
         }
+        space_rows(galley, layout.line_height)
+    }
+
+    /// The text and its runs with the preedit, underlined, at the caret.
+    fn composed(&self) -> (Cow<'_, str>, Cow<'_, RadiantTextSpans>) {
+        if self.preedit.is_empty() {
+            return (Cow::Borrowed(&self.text), Cow::Borrowed(&self.spans));
+        }
+        let head = self.selection.head;
+        let length = self.preedit.chars().count();
+        let mut text = self.text.clone();
+        text.insert_str(byte_index(&text, head), &self.preedit);
+        let mut spans = self.spans.clone();
+        spans.insert(head, length);
+        let style = RadiantTextStyle {
+            underline: true,
+            ..spans.style_at(head)
+        };
+        spans.set_style(head..head + length, style);
+        (Cow::Owned(text), Cow::Owned(spans))
+    }
+
+    /// Index in the laid out text, preedit included, of the character at `index`.
+    fn galley_index(&self, index: usize) -> usize {
+        if index >= self.selection.head {
+            index + self.preedit.chars().count()
+        } else {
+            index
+        }
+    }
+
+    /// Index in the text of the character at `index` in the layout. The preedit maps to the
+    /// caret.
+    fn text_index(&self, index: usize) -> usize {
+        let head = self.selection.head;
+        index - index.saturating_sub(head).min(self.preedit.chars().count())
+    }
+
+    /// A resize through the bounding box fixes the dimensions it changed, so that the text
+    /// reflows in them.
+    fn follow_resize(&mut self) {
+        let Some(measured) = self.measured else {
+            return;
+        };
+        let scale = self.base.transform.scale();
+        let width = (scale.x - measured[0]).abs() > RESIZE_EPSILON;
+        let height = (scale.y - measured[1]).abs() > RESIZE_EPSILON;
+        self.layout.sizing = match self.layout.sizing {
+            _ if height => RadiantTextSizing::Fixed,
+            RadiantTextSizing::AutoWidth if width => RadiantTextSizing::AutoHeight,
+            sizing => sizing,
+        };
     }
 
     fn tessellate(&mut self, screen_descriptor: &ScreenDescriptor, fonts: &Fonts) {
@@ -52,54 +246,55 @@ impl RadiantTextNode {
 
         let pixels_per_point = screen_descriptor.pixels_per_point;
         let position = self.base.transform.position();
-        // let scale = self.transform.scale();
 
-        let font_id = FontId::new(24.0, FontFamily::Proportional);
-        let row_height = fonts.row_height(&font_id);
+        // Documents saved before text had runs have none.
+        self.spans.fit(self.text.chars().count());
+        self.selection.clamp(self.spans.len());
+        let caret_style = self.spans.style_at(self.selection.head.max(1) - 1);
+        let family = available_family(&fonts.families(), &caret_style.family);
+        let row_height = fonts.row_height(&FontId::new(caret_style.size, family));
 
-        let mut job = LayoutJob::default();
-        job.append(
-            &self.text,
-            0.0,
-            TextFormat {
-                font_id,
-                color: Color32::WHITE,
-                ..Default::default()
-            },
-        );
-        // job.append(
-        //     "Hello ",
-        //     0.0,
-        //     TextFormat {
-        //         font_id: FontId::new(14.0, FontFamily::Proportional),
-        //         color: Color32::WHITE,
-        //         ..Default::default()
-        //     },
-        // );
-        // job.append(
-        //     "World!",
-        //     0.0,
-        //     TextFormat {
-        //         font_id: FontId::new(14.0, FontFamily::Monospace),
-        //         color: Color32::BLACK,
-        //         ..Default::default()
-        //     },
-        // );
-
-        let galley = fonts.layout_job(job);
-
-        let cursor = galley.end();
-        let mut cursor_pos = galley.pos_from_cursor(&cursor); //.translate(pos.to_vec2());
+        let galley = self.layout(fonts);
+        self.galley = Some(galley.clone());
+
+        // Auto sized boxes were fitted to their text by `fit_content`.
+        let text_size = galley.rect.size();
+        let scale = self.base.transform.scale();
+        let size = epaint::vec2(scale.x.max(0.0), scale.y.max(0.0));
+
+        let dx = match self.layout.align {
+            RadiantTextAlign::Center => size.x / 2.0,
+            RadiantTextAlign::Right => size.x,
+            RadiantTextAlign::Left | RadiantTextAlign::Justify => 0.0,
+        };
+        let dy = match self.layout.vertical_align {
+            RadiantTextVerticalAlign::Top => 0.0,
+            RadiantTextVerticalAlign::Middle => (size.y - text_size.y) / 2.0,
+            RadiantTextVerticalAlign::Bottom => size.y - text_size.y,
+        };
+        let origin = epaint::pos2(position.x + dx, position.y + dy);
+        self.galley_origin = origin;
+
+        let cursor = galley.from_ccursor(CCursor::new(self.galley_index(self.selection.head)));
+        let mut cursor_pos = galley.pos_from_cursor(&cursor).translate(origin.to_vec2());
         cursor_pos.max.y = cursor_pos.max.y.at_least(cursor_pos.min.y + row_height); // Handle completely empty galleys
         cursor_pos = cursor_pos.expand(1.5); // slightly above/below row
 
         let top = cursor_pos.center_top();
         let bottom = cursor_pos.center_bottom();
-        self.cursor_node.start = [top.x + position.x, top.y + position.y].into();
-        self.cursor_node.end = [bottom.x + position.x, bottom.y + position.y].into();
-        // self.cursor_node.transform = self.transform.clone();
+        self.cursor_node.start = [top.x, top.y].into();
+        self.cursor_node.end = [bottom.x, bottom.y].into();
 
-        let shape = epaint::TextShape::new(position.into(), galley);
+        let highlight = if self.base.selection.is_selected() {
+            let range = self.selection.range();
+            selection_rects(
+                &galley,
+                self.galley_index(range.start)..self.galley_index(range.end),
+            )
+        } else {
+            Vec::new()
+        };
+        let shape = epaint::TextShape::new(origin, galley);
 
         let texture_atlas = fonts.texture_atlas();
         let (font_tex_size, prepared_discs) = {
@@ -107,17 +302,23 @@ impl RadiantTextNode {
             (atlas.size(), atlas.prepared_discs())
         };
 
-        let rect: Rect = shape.visual_bounding_rect();
-        self.base.bounding_rect = [
-            rect.left_top().x,
-            rect.left_top().y,
-            rect.right_bottom().x + 10.0,
-            rect.right_bottom().y,
-        ];
+        let rect = Rect::from_min_size(epaint::pos2(position.x, position.y), size);
+        self.base
+            .set_bounding_rect([rect.min.x, rect.min.y, rect.max.x, rect.max.y]);
 
         let rounding = epaint::Rounding::default();
 
-        let shapes = vec![ClippedShape(Rect::EVERYTHING, epaint::Shape::Text(shape))];
+        let highlight = highlight.into_iter().map(|rect| {
+            let rect = rect.translate(origin.to_vec2());
+            let shape = epaint::RectShape::filled(rect, rounding, SELECTION_COLOR);
+            ClippedShape(Rect::EVERYTHING, epaint::Shape::Rect(shape))
+        });
+        let shapes = highlight
+            .chain(std::iter::once(ClippedShape(
+                Rect::EVERYTHING,
+                epaint::Shape::Text(shape),
+            )))
+            .collect();
         self.base.primitives = epaint::tessellator::tessellate_shapes(
             pixels_per_point,
             TessellationOptions::default(),
@@ -132,7 +333,7 @@ impl RadiantTextNode {
                 .append(&mut self.cursor_node.tessellate(false, screen_descriptor, fonts));
         }
 
-        let fill_color = get_color_for_node(self.base.id);
+        let fill_color = pick_color(self.base.pick_id);
         let rect_shape = epaint::RectShape::filled(rect, rounding, fill_color);
         let shapes = vec![ClippedShape(
             Rect::EVERYTHING,
@@ -140,7 +341,7 @@ impl RadiantTextNode {
         )];
         self.base.selection_primitives = epaint::tessellator::tessellate_shapes(
             pixels_per_point,
-            TessellationOptions::default(),
+            picking_tessellation_options(),
             [1, 1],
             vec![],
             shapes,
@@ -148,6 +349,310 @@ impl RadiantTextNode {
     }
 }
 
+/// Spreads the rows of `galley` to `line_height` times their height, half of the difference
+/// going above each row and half below.
+fn space_rows(galley: Arc<Galley>, line_height: f32) -> Arc<Galley> {
+    if line_height == 1.0 {
+        return galley;
+    }
+    let mut galley = (*galley).clone();
+    let mut offset = 0.0;
+    for row in &mut galley.rows {
+        let extra = row.rect.height() * (line_height - 1.0);
+        let delta = epaint::vec2(0.0, offset + extra / 2.0);
+        row.rect = row.rect.translate(delta);
+        row.visuals.mesh.translate(delta);
+        row.visuals.mesh_bounds = row.visuals.mesh_bounds.translate(delta);
+        for glyph in &mut row.glyphs {
+            glyph.pos += delta;
+        }
+        offset += extra;
+    }
+    galley.rect.max.y += offset;
+    galley.mesh_bounds.max.y += offset;
+    Arc::new(galley)
+}
+
+/// Characters that start a row when `galley`, laid out without wrapping, is wrapped at `width`
+/// with `spacing` between its characters. Like epaint, rows break after their last space, or
+/// before the character that overflows them if they have none.
+fn row_breaks(galley: &Galley, spacing: f32, width: f32) -> Vec<usize> {
+    let mut breaks = Vec::new();
+    if !width.is_finite() {
+        return breaks;
+    }
+    let mut offset = 0;
+    for row in &galley.rows {
+        let right = |index: usize| row.glyphs[index].max_x() + spacing * index as f32;
+        let (mut start, mut space) = (0, None);
+        for (index, glyph) in row.glyphs.iter().enumerate() {
+            let left = row.glyphs[start].pos.x + spacing * start as f32;
+            if index > start && right(index) - left > width {
+                start = match space {
+                    Some(space) if space > start => space,
+                    _ => index,
+                };
+                breaks.push(offset + start);
+                space = None;
+            }
+            if glyph.chr == ' ' {
+                space = Some(index + 1);
+            }
+        }
+        offset += row.char_count_including_newline();
+    }
+    breaks
+}
+
+/// `galley`, laid out left aligned without wrapping, with `spacing` between the characters of
+/// each row, and the rows aligned in `width`. The line breaks before `breaks` wrap their rows
+/// rather than end paragraphs.
+fn space_letters(
+    galley: &Galley,
+    breaks: &[usize],
+    spacing: f32,
+    align: RadiantTextAlign,
+    width: f32,
+) -> Arc<Galley> {
+    let mut galley = galley.clone();
+    let job = galley.job.clone();
+    let last_row = galley.rows.len().saturating_sub(1);
+    let mut offset = 0;
+    for (index, row) in galley.rows.iter_mut().enumerate() {
+        offset += row.glyphs.len();
+        if row.ends_with_newline && breaks.contains(&offset) {
+            row.ends_with_newline = false;
+        } else if row.ends_with_newline {
+            offset += 1;
+        }
+        if row.glyphs.is_empty() {
+            continue;
+        }
+
+        // Trailing spaces neither count towards the width of the row nor get justified.
+        let visible = row
+            .glyphs
+            .iter()
+            .rposition(|glyph| !glyph.chr.is_whitespace())
+            .map_or(row.glyphs.len(), |index| index + 1);
+        let left = row.glyphs[0].pos.x;
+        let text_width = row.glyphs[visible - 1].max_x() + spacing * (visible - 1) as f32 - left;
+        let justify = align == RadiantTextAlign::Justify
+            && width.is_finite()
+            && !row.ends_with_newline
+            && index != last_row;
+        let spaces = row.glyphs[..visible]
+            .iter()
+            .filter(|glyph| glyph.chr == ' ')
+            .count();
+        let (row_width, space_width) = if justify && spaces > 0 && text_width < width {
+            (width, (width - text_width) / spaces as f32)
+        } else {
+            (text_width, 0.0)
+        };
+        let row_left = match align {
+            RadiantTextAlign::Center => -row_width / 2.0,
+            RadiantTextAlign::Right => -row_width,
+            RadiantTextAlign::Left | RadiantTextAlign::Justify => 0.0,
+        };
+
+        let mesh = &mut row.visuals.mesh;
+        let glyph_vertices = row.visuals.glyph_vertex_range.clone();
+        // Decorations span runs of glyphs, so they are drawn again once the glyphs moved.
+        mesh.vertices.truncate(glyph_vertices.end);
+        mesh.indices
+            .retain(|vertex| (*vertex as usize) < glyph_vertices.end);
+        let mut vertex = glyph_vertices.start;
+        let mut shift = row_left - left;
+        for glyph in row.glyphs.iter_mut() {
+            glyph.pos.x += shift;
+            if !glyph.uv_rect.is_nothing() {
+                for vertex in &mut mesh.vertices[vertex..vertex + 4] {
+                    vertex.pos.x += shift;
+                }
+                vertex += 4;
+            }
+            shift += spacing;
+            if glyph.chr == ' ' {
+                shift += space_width;
+            }
+        }
+        decorate_row(&job, &row.glyphs, mesh);
+        row.rect.min.x = row_left;
+        row.rect.max.x = row_left + row_width;
+        row.visuals.mesh_bounds = mesh.calc_bounds();
+    }
+
+    let (mut min_x, mut max_x) = (0.0f32, 0.0f32);
+    galley.mesh_bounds = Rect::NOTHING;
+    galley.num_vertices = 0;
+    galley.num_indices = 0;
+    for row in &galley.rows {
+        min_x = min_x.min(row.rect.min.x);
+        max_x = max_x.max(row.rect.max.x);
+        galley.mesh_bounds = galley.mesh_bounds.union(row.visuals.mesh_bounds);
+        galley.num_vertices += row.visuals.mesh.vertices.len();
+        galley.num_indices += row.visuals.mesh.indices.len();
+    }
+    galley.rect.min.x = min_x;
+    galley.rect.max.x = max_x;
+    Arc::new(galley)
+}
+
+/// Underlines and strikes through the runs of `glyphs` whose format has them.
+fn decorate_row(job: &LayoutJob, glyphs: &[Glyph], mesh: &mut Mesh) {
+    for underline in [true, false] {
+        let mut start: Option<(Stroke, Pos2)> = None;
+        let mut right = 0.0;
+        for glyph in glyphs.iter().map(Some).chain([None]) {
+            let next = glyph.map(|glyph| {
+                let format = &job.sections[glyph.section_index as usize].format;
+                let rect = glyph.logical_rect();
+                if underline {
+                    (format.underline, epaint::pos2(glyph.pos.x, rect.bottom()))
+                } else {
+                    (
+                        format.strikethrough,
+                        epaint::pos2(glyph.pos.x, rect.center().y),
+                    )
+                }
+            });
+            let continues = matches!(
+                (start, next),
+                (Some((stroke, from)), Some((next_stroke, to)))
+                    if stroke == next_stroke && from.y == to.y
+            );
+            if !continues {
+                if let Some((stroke, from)) = start.take() {
+                    let half = stroke.width / 2.0;
+                    let rect = Rect::from_min_max(
+                        epaint::pos2(from.x, from.y - half),
+                        epaint::pos2(right, from.y + half),
+                    );
+                    mesh.add_colored_rect(rect, stroke.color);
+                }
+                start = next.filter(|(stroke, _)| *stroke != Stroke::NONE);
+            }
+            if let Some(glyph) = glyph {
+                right = glyph.max_x();
+            }
+        }
+    }
+}
+
+/// `family` if `fonts` has it, or the default one for text in a family not loaded yet.
+fn available_family(families: &[FontFamily], family: &FontFamily) -> FontFamily {
+    if families.contains(family) {
+        family.clone()
+    } else {
+        FontFamily::Proportional
+    }
+}
+
+/// Family `style` is laid out in. Bold runs use the bold faces of their family, and stay at
+/// the regular weight in families that have none loaded.
+fn run_family(families: &[FontFamily], style: &RadiantTextStyle) -> FontFamily {
+    match &style.family {
+        FontFamily::Name(name) if style.bold => {
+            let bold = bold_family(name);
+            if families.contains(&bold) {
+                bold
+            } else {
+                available_family(families, &style.family)
+            }
+        }
+        family => available_family(families, family),
+    }
+}
+
+/// Rectangles covering the characters in `range`, one per row, relative to the galley.
+fn selection_rects(galley: &Galley, range: Range<usize>) -> Vec<Rect> {
+    let mut rects = Vec::new();
+    let mut start = 0;
+    for row in &galley.rows {
+        let end = start + row.char_count_excluding_newline();
+        let from = range.start.max(start);
+        let to = range.end.min(end);
+        // A selected line break shows as a sliver past the end of its row.
+        let newline = row.ends_with_newline && range.contains(&end);
+        if from < to || (newline && from <= end) {
+            let left = row.x_offset(from - start);
+            let mut right = row.x_offset(to.max(from) - start);
+            if newline {
+                right += row.rect.height() / 4.0;
+            }
+            rects.push(Rect::from_min_max(
+                epaint::pos2(left, row.rect.min.y),
+                epaint::pos2(right, row.rect.max.y),
+            ));
+        }
+        start += row.char_count_including_newline();
+    }
+    rects
+}
+
+/// Byte offset of the character at `index`, or the end of `text` past its last character.
+fn byte_index(text: &str, index: usize) -> usize {
+    text.char_indices()
+        .nth(index)
+        .map_or(text.len(), |(offset, _)| offset)
+}
+
+/// Number of UTF-16 code units in the characters of `text` in `range`.
+fn utf16_len(text: &str, range: Range<usize>) -> usize {
+    text.chars()
+        .skip(range.start)
+        .take(range.len())
+        .map(char::len_utf16)
+        .sum()
+}
+
+/// Index of the character `units` UTF-16 code units after the one at `index`.
+fn utf16_advance(text: &str, index: usize, units: usize) -> usize {
+    let mut walked = 0;
+    index
+        + text
+            .chars()
+            .skip(index)
+            .take_while(|c| {
+                walked += c.len_utf16();
+                walked <= units
+            })
+            .count()
+}
+
+/// Character indices between grapheme clusters, the places a caret can be, from 0 to the
+/// length of `text`.
+fn boundaries(text: &str) -> impl Iterator<Item = usize> + '_ {
+    std::iter::once(0).chain(text.graphemes(true).scan(0, |end, grapheme| {
+        *end += grapheme.chars().count();
+        Some(*end)
+    }))
+}
+
+/// The caret position one cluster before `index`.
+fn previous_boundary(text: &str, index: usize) -> usize {
+    boundaries(text)
+        .take_while(|boundary| *boundary < index)
+        .last()
+        .unwrap_or(0)
+}
+
+/// The caret position one cluster after `index`.
+fn next_boundary(text: &str, index: usize) -> usize {
+    boundaries(text)
+        .find(|boundary| *boundary > index)
+        .unwrap_or(index)
+}
+
+/// The caret position at or before `index`, so that no cluster is split.
+fn boundary_at(text: &str, index: usize) -> usize {
+    boundaries(text)
+        .take_while(|boundary| *boundary <= index)
+        .last()
+        .unwrap_or(0)
+}
+
 impl RadiantTessellatable for RadiantTextNode {
     fn attach(&mut self, _screen_descriptor: &ScreenDescriptor) {}
 
@@ -157,6 +662,7 @@ impl RadiantTessellatable for RadiantTextNode {
     }
 
     fn set_needs_tessellation(&mut self, notify: bool) {
+        self.follow_resize();
         self.base.set_needs_tessellation();
         self.cursor_node.set_needs_tessellation(notify);
         if notify {
@@ -188,38 +694,316 @@ impl RadiantNode for RadiantTextNode {
         &mut self.base
     }
 
-    fn handle_key_down(&mut self, key: radiantkit_core::KeyCode) -> bool {
-        let did_update = match key {
-            radiantkit_core::KeyCode::Backspace => {
-                self.text.pop();
-                true
+    fn handle_key_down(&mut self, key: KeyCode, modifiers: RadiantKeyModifiers) -> bool {
+        let length = self.text.chars().count();
+        self.selection.clamp(length);
+        let head = self.selection.head;
+        match key {
+            KeyCode::Backspace => {
+                if self.selection.is_empty() {
+                    self.selection
+                        .move_to(previous_boundary(&self.text, head), true);
+                }
+                self.replace_selection("")
             }
-            radiantkit_core::KeyCode::Enter => {
-                self.text.push('\n');
-                true
+            KeyCode::Delete => {
+                if self.selection.is_empty() {
+                    self.selection
+                        .move_to(next_boundary(&self.text, head), true);
+                }
+                self.replace_selection("")
             }
-            radiantkit_core::KeyCode::Space => {
-                self.text.push(' ');
+            KeyCode::Enter => self.replace_selection("\n"),
+            KeyCode::Space => self.replace_selection(" "),
+            KeyCode::Char(c) => !c.is_empty() && self.replace_selection(&c),
+            key => self.move_caret(key, modifiers.shift),
+        }
+    }
+
+    fn handle_press(&mut self, position: [f32; 2], extend: bool) -> bool {
+        if !self.editing {
+            return false;
+        }
+        let Some(galley) = &self.galley else {
+            return false;
+        };
+        let origin = self.galley_origin;
+        let cursor =
+            galley.cursor_from_pos(epaint::vec2(position[0] - origin.x, position[1] - origin.y));
+        let index = boundary_at(&self.text, self.text_index(cursor.ccursor.index));
+        self.selection.move_to(index, extend);
+        self.set_needs_tessellation(false);
+        true
+    }
+
+    fn handle_ime(&mut self, event: RadiantImeEvent) -> bool {
+        match event {
+            RadiantImeEvent::Preedit(text) => {
+                self.preedit = text;
+                self.set_needs_tessellation(false);
                 true
             }
-            radiantkit_core::KeyCode::Char(c) => {
-                self.text.push_str(&c);
+            RadiantImeEvent::Commit(text) => {
+                self.preedit.clear();
+                if !self.replace_selection(&text) {
+                    self.set_needs_tessellation(false);
+                }
                 true
             }
-            _ => false,
+        }
+    }
+
+    fn begin_editing(&mut self, position: [f32; 2]) -> bool {
+        self.editing = true;
+        self.handle_press(position, false);
+        true
+    }
+
+    fn end_editing(&mut self) {
+        self.editing = false;
+        self.preedit.clear();
+        self.set_needs_tessellation(false);
+    }
+
+    fn ime_cursor_area(&self) -> Option<[f32; 4]> {
+        let (top, bottom) = (self.cursor_node.start, self.cursor_node.end);
+        self.editing
+            .then_some([top.x, top.y, bottom.x - top.x, bottom.y - top.y])
+    }
+
+    fn cursor(&self) -> radiantkit_core::RadiantCursor {
+        if self.editing {
+            radiantkit_core::RadiantCursor::Text
+        } else {
+            radiantkit_core::RadiantCursor::Move
+        }
+    }
+
+    fn replace(&mut self, node: &str) {
+        let node: Self = match serde_json::from_str(node) {
+            Ok(node) => node,
+            Err(error) => {
+                log::warn!("Ignoring invalid text node {}: {}", self.base.id, error);
+                return;
+            }
         };
-        if did_update {
-            self.set_needs_tessellation(true);
+        let observers = std::mem::take(&mut self.base.observers);
+        let bounds_observer = self.base.bounds_observer.take();
+        let text_observer = self.base.text_observer.take();
+        let revision = self.base.revision;
+        let pick_id = self.base.pick_id;
+        self.base = node.base;
+        self.base.observers = observers;
+        self.base.bounds_observer = bounds_observer;
+        self.base.text_observer = text_observer;
+        self.base.revision = revision;
+        self.base.pick_id = pick_id;
+        self.text = node.text;
+        self.spans = node.spans;
+        self.layout = node.layout;
+        // The remote box fitted its text already.
+        self.measured = None;
+        self.set_needs_tessellation(false);
+    }
+
+    fn fit_content(&mut self, fonts: &Fonts) -> bool {
+        let text_size = self.layout(fonts).rect.size();
+        let scale = self.base.transform.scale();
+        let size = match self.layout.sizing {
+            RadiantTextSizing::AutoWidth => [text_size.x, text_size.y],
+            RadiantTextSizing::AutoHeight => [self.wrap_width(), text_size.y],
+            RadiantTextSizing::Fixed => [self.wrap_width(), scale.y.max(0.0)],
+        };
+        self.measured = Some(size);
+        if size == [scale.x, scale.y] {
+            return false;
+        }
+        self.base.transform.set_scale(&size.into());
+        self.base.set_needs_tessellation();
+        self.base.notify(serde_json::to_string(self).unwrap());
+        true
+    }
+
+    fn font_families(&self) -> Vec<String> {
+        let mut families: Vec<String> = self
+            .spans
+            .iter()
+            .filter_map(|span| match &span.style.family {
+                FontFamily::Name(name) => Some(name.to_string()),
+                _ => None,
+            })
+            .collect();
+        families.dedup();
+        families
+    }
+
+    fn text(&self) -> Option<&str> {
+        Some(&self.text)
+    }
+
+    fn apply_text_delta(&mut self, delta: &[RadiantTextDelta]) -> bool {
+        let mut index = 0;
+        for step in delta {
+            match step {
+                RadiantTextDelta::Retain(units) => index = utf16_advance(&self.text, index, *units),
+                RadiantTextDelta::Insert(text) => {
+                    let length = text.chars().count();
+                    self.text.insert_str(byte_index(&self.text, index), text);
+                    self.spans.insert(index, length);
+                    self.selection.insert(index, length);
+                    index += length;
+                }
+                RadiantTextDelta::Delete(units) => {
+                    let end = utf16_advance(&self.text, index, *units);
+                    let bytes = byte_index(&self.text, index)..byte_index(&self.text, end);
+                    self.text.replace_range(bytes, "");
+                    self.spans.remove(index..end);
+                    self.selection.remove(index..end);
+                }
+            }
         }
-        did_update
+        self.set_needs_tessellation(false);
+        true
+    }
+
+    /// The text is exported run by run, each in its own style.
+    fn to_vector(&self) -> Option<RadiantVectorNode> {
+        let position = self.base.transform.position();
+        let mut chars = self.text.chars();
+        let runs = self
+            .spans
+            .iter()
+            .filter(|span| span.length > 0)
+            .map(|span| {
+                let style = &span.style;
+                RadiantVectorTextRun {
+                    text: chars.by_ref().take(span.length).collect(),
+                    family: match &style.family {
+                        FontFamily::Name(name) => Some(name.to_string()),
+                        _ => None,
+                    },
+                    font_size: style.size,
+                    color: style.color,
+                    bold: style.bold,
+                    italic: style.italic,
+                    underline: style.underline,
+                    strikethrough: style.strikethrough,
+                }
+            })
+            .collect();
+        let mut node =
+            RadiantVectorNode::new(&self.base, RadiantVectorGeometry::Text { runs }, 0.0);
+        node.position = [position.x, position.y];
+        node.fill = None;
+        Some(node)
     }
 }
 
 impl RadiantTextNode {
+    /// Types `text` over the selected characters, in the style of the first of them, and
+    /// leaves the caret after it.
+    fn replace_selection(&mut self, text: &str) -> bool {
+        let range = self.selection.range();
+        if !self.splice(range.clone(), text) {
+            return false;
+        }
+        self.selection = RadiantTextSelection::caret(range.start + text.chars().count());
+        self.set_needs_tessellation(true);
+        true
+    }
+
+    /// Replaces the characters in `range` with `text`, in the style of the first of them, and
+    /// sends the change to text observers. Every local edit of the characters goes through
+    /// here, so that collaborators receive it. Returns whether anything changed.
+    fn splice(&mut self, range: Range<usize>, text: &str) -> bool {
+        if range.is_empty() && text.is_empty() {
+            return false;
+        }
+        let bytes = byte_index(&self.text, range.start)..byte_index(&self.text, range.end);
+        let delta: Vec<RadiantTextDelta> = [
+            RadiantTextDelta::Retain(utf16_len(&self.text, 0..range.start)),
+            RadiantTextDelta::Delete(utf16_len(&self.text, range.clone())),
+            RadiantTextDelta::Insert(text.to_string()),
+        ]
+        .into_iter()
+        .filter(|step| match step {
+            RadiantTextDelta::Retain(units) | RadiantTextDelta::Delete(units) => *units > 0,
+            RadiantTextDelta::Insert(text) => !text.is_empty(),
+        })
+        .collect();
+        let length = text.chars().count();
+        let style = self.spans.style_at(range.start);
+        self.spans.remove(range.clone());
+        if length > 0 {
+            self.spans.insert(range.start, length);
+            if !range.is_empty() {
+                self.spans
+                    .set_style(range.start..range.start + length, style);
+            }
+        }
+        self.text.replace_range(bytes, text);
+        self.selection.remove(range.clone());
+        self.selection.insert(range.start, length);
+        self.base.notify_text(&delta);
+        true
+    }
+
+    /// Moves the caret for an arrow, Home or End key, a grapheme cluster at a time. Rows come
+    /// from the last layout.
+    fn move_caret(&mut self, key: KeyCode, extend: bool) -> bool {
+        let selection = self.selection;
+        let collapse = !extend && !selection.is_empty();
+        let head = match key {
+            KeyCode::ArrowLeft if collapse => selection.range().start,
+            KeyCode::ArrowRight if collapse => selection.range().end,
+            KeyCode::ArrowLeft => previous_boundary(&self.text, selection.head),
+            KeyCode::ArrowRight => next_boundary(&self.text, selection.head),
+            KeyCode::ArrowUp | KeyCode::ArrowDown | KeyCode::Home | KeyCode::End => {
+                let Some(galley) = &self.galley else {
+                    return false;
+                };
+                let cursor = galley.from_ccursor(CCursor::new(self.galley_index(selection.head)));
+                let cursor = match key {
+                    KeyCode::ArrowUp => galley.cursor_up_one_row(&cursor),
+                    KeyCode::ArrowDown => galley.cursor_down_one_row(&cursor),
+                    KeyCode::Home => galley.cursor_begin_of_row(&cursor),
+                    _ => galley.cursor_end_of_row(&cursor),
+                };
+                boundary_at(&self.text, self.text_index(cursor.ccursor.index))
+            }
+            _ => return false,
+        };
+        self.selection.move_to(head, extend);
+        self.set_needs_tessellation(false);
+        true
+    }
+
     pub fn handle_message(&mut self, message: RadiantTextMessage) -> bool {
         match message {
             RadiantTextMessage::SetText { text, .. } => {
-                self.text = text;
+                // Only the characters in between the common start and end are replaced, so
+                // that the runs around them keep their styles.
+                let old: Vec<char> = self.text.chars().collect();
+                let new: Vec<char> = text.chars().collect();
+                let start = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+                let end = old[start..]
+                    .iter()
+                    .rev()
+                    .zip(new[start..].iter().rev())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                let inserted: String = new[start..new.len() - end].iter().collect();
+                self.splice(start..old.len() - end, &inserted);
+                self.set_needs_tessellation(true);
+                true
+            }
+            RadiantTextMessage::SetTextStyle { range, style, .. } => {
+                self.spans.set_style(range, style);
+                self.set_needs_tessellation(true);
+                true
+            }
+            RadiantTextMessage::SetTextLayout { layout, .. } => {
+                self.layout = layout;
                 self.set_needs_tessellation(true);
                 true
             }
@@ -0,0 +1,63 @@
+use std::ops::Range;
+
+/// Caret and selection inside a text, as character indices. The caret sits at `head`, and
+/// the selection runs from `anchor` to it. Both are equal when nothing is selected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RadiantTextSelection {
+    pub anchor: usize,
+    pub head: usize,
+}
+
+impl RadiantTextSelection {
+    /// A caret at `index` with nothing selected.
+    pub fn caret(index: usize) -> Self {
+        Self {
+            anchor: index,
+            head: index,
+        }
+    }
+
+    /// The selected characters, start first.
+    pub fn range(&self) -> Range<usize> {
+        self.anchor.min(self.head)..self.anchor.max(self.head)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.anchor == self.head
+    }
+
+    /// Moves the caret to `index`, keeping the anchor where it is when `extend` is set.
+    pub fn move_to(&mut self, index: usize, extend: bool) {
+        self.head = index;
+        if !extend {
+            self.anchor = index;
+        }
+    }
+
+    /// Follows `length` characters inserted at `index` by someone else. An end right at
+    /// `index` stays before them.
+    pub fn insert(&mut self, index: usize, length: usize) {
+        for end in [&mut self.anchor, &mut self.head] {
+            if *end > index {
+                *end += length;
+            }
+        }
+    }
+
+    /// Follows the characters in `range` removed by someone else.
+    pub fn remove(&mut self, range: Range<usize>) {
+        for end in [&mut self.anchor, &mut self.head] {
+            if *end >= range.end {
+                *end -= range.len();
+            } else if *end > range.start {
+                *end = range.start;
+            }
+        }
+    }
+
+    /// Keeps both ends within a text of `length` characters.
+    pub fn clamp(&mut self, length: usize) {
+        self.anchor = self.anchor.min(length);
+        self.head = self.head.min(length);
+    }
+}
@@ -0,0 +1,174 @@
+use crate::FONT_SIZE;
+use epaint::{Color32, FontFamily};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Formatting of a run of characters.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct RadiantTextStyle {
+    pub family: FontFamily,
+    pub size: f32,
+    pub color: Color32,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strikethrough: bool,
+}
+
+impl Default for RadiantTextStyle {
+    fn default() -> Self {
+        Self {
+            family: FontFamily::Proportional,
+            size: FONT_SIZE,
+            color: Color32::WHITE,
+            bold: false,
+            italic: false,
+            underline: false,
+            strikethrough: false,
+        }
+    }
+}
+
+/// `length` characters sharing one style.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RadiantTextSpan {
+    pub length: usize,
+    pub style: RadiantTextStyle,
+}
+
+/// Styled runs covering a text from its first character to its last. Neighbouring runs
+/// always differ in style. An empty text keeps one empty run, so typing into it picks up
+/// its style.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(transparent)]
+pub struct RadiantTextSpans(Vec<RadiantTextSpan>);
+
+impl RadiantTextSpans {
+    pub fn new(length: usize, style: RadiantTextStyle) -> Self {
+        Self(vec![RadiantTextSpan { length, style }])
+    }
+
+    /// Number of characters covered.
+    pub fn len(&self) -> usize {
+        self.0.iter().map(|span| span.length).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &RadiantTextSpan> {
+        self.0.iter()
+    }
+
+    /// Style of the character at `index`, or of the last one past the end.
+    pub fn style_at(&self, index: usize) -> RadiantTextStyle {
+        let mut start = 0;
+        for span in &self.0 {
+            if index < start + span.length {
+                return span.style.clone();
+            }
+            start += span.length;
+        }
+        self.0
+            .last()
+            .map(|span| span.style.clone())
+            .unwrap_or_default()
+    }
+
+    /// Applies `style` to the characters in `range`.
+    pub fn set_style(&mut self, range: Range<usize>, style: RadiantTextStyle) {
+        let range = range.start.min(self.len())..range.end.min(self.len());
+        if range.is_empty() {
+            return;
+        }
+        let start = self.split(range.start);
+        let end = self.split(range.end);
+        self.0.splice(
+            start..end,
+            [RadiantTextSpan {
+                length: range.len(),
+                style,
+            }],
+        );
+        self.normalize();
+    }
+
+    /// Makes room for `length` characters inserted at `index`, styled like the character
+    /// before them.
+    pub fn insert(&mut self, index: usize, length: usize) {
+        let mut start = 0;
+        let position = self.0.iter().position(|span| {
+            start += span.length;
+            index <= start
+        });
+        match position.or(self.0.len().checked_sub(1)) {
+            Some(position) => self.0[position].length += length,
+            None => self.0.push(RadiantTextSpan {
+                length,
+                style: Default::default(),
+            }),
+        }
+    }
+
+    /// Drops the characters in `range`.
+    pub fn remove(&mut self, range: Range<usize>) {
+        let range = range.start.min(self.len())..range.end.min(self.len());
+        if range.is_empty() {
+            return;
+        }
+        let start = self.split(range.start);
+        let end = self.split(range.end);
+        self.0.drain(start..end);
+        self.normalize();
+    }
+
+    /// Grows or shrinks the runs at the end to cover `length` characters, for texts replaced
+    /// wholesale or documents saved without runs.
+    pub fn fit(&mut self, length: usize) {
+        let current = self.len();
+        if current < length {
+            self.insert(current, length - current);
+        } else {
+            self.remove(length..current);
+        }
+    }
+
+    /// Index of the run starting at character `index`, splitting the run around it if needed.
+    fn split(&mut self, index: usize) -> usize {
+        let mut start = 0;
+        for position in 0..self.0.len() {
+            let end = start + self.0[position].length;
+            if index == start {
+                return position;
+            }
+            if index < end {
+                let mut tail = self.0[position].clone();
+                tail.length = end - index;
+                self.0[position].length = index - start;
+                self.0.insert(position + 1, tail);
+                return position + 1;
+            }
+            start = end;
+        }
+        self.0.len()
+    }
+
+    fn normalize(&mut self) {
+        let first = self.0.first().cloned();
+        self.0.retain(|span| span.length > 0);
+        self.0.dedup_by(|next, previous| {
+            if next.style == previous.style {
+                previous.length += next.length;
+                true
+            } else {
+                false
+            }
+        });
+        if let (true, Some(mut first)) = (self.0.is_empty(), first) {
+            first.length = 0;
+            self.0.push(first);
+        }
+    }
+}
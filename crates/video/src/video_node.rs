@@ -2,8 +2,8 @@ use epaint::{
     pos2, ClippedPrimitive, ClippedShape, Color32, Rect, TessellationOptions, TextureHandle,
 };
 use radiantkit_core::{
-    BaseNode, ColorComponent, RadiantNode, RadiantTessellatable, ScreenDescriptor,
-    TransformComponent, Vec3,
+    pick_color, picking_tessellation_options, BaseNode, ColorComponent, RadiantNode,
+    RadiantTessellatable, ScreenDescriptor, TransformComponent, Vec3,
 };
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
@@ -127,11 +127,7 @@ impl RadiantVideoNode {
             shapes,
         );
 
-        let color = epaint::Color32::from_rgb(
-            (self.base.id.as_u128() + 1 >> 0) as u8 & 0xFF,
-            (self.base.id.as_u128() + 1 >> 8) as u8 & 0xFF,
-            (self.base.id.as_u128() + 1 >> 16) as u8 & 0xFF,
-        );
+        let color = pick_color(self.base.pick_id);
         let rect_shape = epaint::RectShape::filled(rect, rounding, color);
         let shapes = vec![ClippedShape(
             Rect::EVERYTHING,
@@ -139,7 +135,7 @@ impl RadiantVideoNode {
         )];
         self.base.selection_primitives = epaint::tessellator::tessellate_shapes(
             pixels_per_point,
-            TessellationOptions::default(),
+            picking_tessellation_options(),
             [1, 1],
             vec![],
             shapes,
@@ -172,7 +168,7 @@ impl RadiantTessellatable for RadiantVideoNode {
             rect.right_bottom().y,
         ];
 
-        self.base.needs_tessellation = true;
+        self.base.invalidate();
     }
 
     fn tessellate(
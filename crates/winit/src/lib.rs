@@ -1,12 +1,14 @@
 use radiantkit_core::{
-    KeyCode, RadiantNode, RadiantScene, RadiantSceneMessage, Runtime, ScreenDescriptor, Vec3, View,
+    KeyCode, RadiantCursor, RadiantImeEvent, RadiantKeyModifiers, RadiantNode, RadiantScene,
+    RadiantSceneMessage, Runtime, ScreenDescriptor, Vec3, View,
 };
 use winit::event_loop::EventLoop;
-use winit::window::{Window, WindowBuilder};
+use winit::window::{CursorIcon, Window, WindowBuilder};
 use winit::{event::*, event_loop::ControlFlow};
 
+use instant::{Duration, Instant};
 use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
-use winit::dpi::PhysicalSize;
+use winit::dpi::{LogicalPosition, PhysicalSize};
 pub use winit::event::Event::RedrawRequested;
 
 #[cfg(target_arch = "wasm32")]
@@ -14,6 +16,11 @@ use wasm_bindgen::prelude::*;
 #[cfg(target_arch = "wasm32")]
 use winit::platform::web::EventLoopExtWebSys;
 
+/// Longest gap between the presses of a double click.
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+/// Farthest (in points) the second press of a double click can land from the first.
+const DOUBLE_CLICK_DISTANCE: f32 = 4.0;
+
 pub struct RadiantView<M, N: RadiantNode> {
     pub window: Arc<Window>,
     pub event_loop: Option<EventLoop<()>>,
@@ -24,6 +31,13 @@ pub struct RadiantView<M, N: RadiantNode> {
 
     mouse_position: [f32; 2],
     mouse_dragging: bool,
+    cursor: RadiantCursor,
+    last_click: Option<(Instant, [f32; 2])>,
+    modifiers: ModifiersState,
+    /// Set while an input method composes text, whose key presses are its own.
+    composing: bool,
+    /// Caret input methods compose at, while a node is edited.
+    ime_cursor_area: Option<[f32; 4]>,
 }
 
 impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode>
@@ -179,6 +193,11 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
             scene: Arc::new(RwLock::new(scene)),
             mouse_position: [0.0, 0.0],
             mouse_dragging: false,
+            cursor: RadiantCursor::Default,
+            last_click: None,
+            modifiers: ModifiersState::default(),
+            composing: false,
+            ime_cursor_area: None,
         }
     }
 
@@ -278,11 +297,17 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
                                 }
                             }
                         }
+                        WindowEvent::ModifiersChanged(modifiers) => {
+                            self.modifiers = *modifiers;
+                        }
                         WindowEvent::KeyboardInput { input, .. } => {
                             if input.state == ElementState::Pressed {
                                 return self.on_key_down(&input.virtual_keycode);
                             }
                         }
+                        WindowEvent::Ime(ime) => {
+                            return self.on_ime(ime);
+                        }
                         _ => {}
                     }
                 }
@@ -304,6 +329,7 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
                 if needs_resize {
                     self.resize(size);
                 }
+                self.update_ime();
             }
             Event::MainEventsCleared => {
                 self.window.request_redraw();
@@ -318,14 +344,38 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
     RadiantView<M, N>
 {
     pub fn on_mouse_down(&mut self, position: [f32; 2]) -> Option<M> {
-        let id = pollster::block_on(self.scene_mut().select(position));
-        self.scene_mut()
-            .tool_manager
-            .active_tool()
-            .on_mouse_down(id, position)
+        let id = self.scene().hit_test(position);
+        self.update_cursor(position);
+
+        let now = Instant::now();
+        let double_click = self.last_click.is_some_and(|(time, last)| {
+            let distance =
+                ((position[0] - last[0]).powi(2) + (position[1] - last[1]).powi(2)).sqrt();
+            now.duration_since(time) <= DOUBLE_CLICK_INTERVAL && distance <= DOUBLE_CLICK_DISTANCE
+        });
+        // A third press starts over rather than making another double click.
+        self.last_click = if double_click {
+            None
+        } else {
+            Some((now, position))
+        };
+
+        let mut scene = self.scene_mut();
+        let tool = scene.tool_manager.active_tool();
+        let message = tool.on_mouse_down(id, position);
+        if double_click {
+            if let Some(message) = tool.on_double_click(id, position) {
+                return Some(message);
+            }
+        }
+        message
     }
 
     pub fn on_mouse_move(&mut self, position: [f32; 2]) -> Option<M> {
+        // Keep the cursor picked at mouse down for the whole drag.
+        if !self.mouse_dragging {
+            self.update_cursor(position);
+        }
         self.scene_mut()
             .tool_manager
             .active_tool()
@@ -333,22 +383,92 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
     }
 
     pub fn on_mouse_up(&mut self, position: [f32; 2]) -> Option<M> {
-        self.scene_mut()
+        let message = self
+            .scene_mut()
             .tool_manager
             .active_tool()
-            .on_mouse_up(position)
+            .on_mouse_up(position);
+        self.update_cursor(position);
+        message
+    }
+
+    fn update_cursor(&mut self, position: [f32; 2]) {
+        let cursor = self.scene_mut().cursor(position);
+        if cursor != self.cursor {
+            self.cursor = cursor;
+            self.window.set_cursor_icon(to_cursor_icon(cursor));
+        }
     }
 
     pub fn on_key_down(&mut self, input: &Option<VirtualKeyCode>) -> Option<M> {
+        if self.composing {
+            return None;
+        }
         if let Some(keycode) = input {
+            let modifiers = RadiantKeyModifiers {
+                shift: self.modifiers.shift(),
+                ctrl: self.modifiers.ctrl(),
+                alt: self.modifiers.alt(),
+                meta: self.modifiers.logo(),
+            };
             self.scene_mut()
                 .tool_manager
                 .active_tool()
-                .on_key_down(to_keycode(keycode))
+                .on_key_down(to_keycode(keycode), modifiers)
         } else {
             None
         }
     }
+
+    /// Allows input methods only while a node is edited, and shows their candidates under its
+    /// caret, which is placed when the node is drawn.
+    fn update_ime(&mut self) {
+        let area = self.scene().ime_cursor_area();
+        if area == self.ime_cursor_area {
+            return;
+        }
+        if area.is_some() != self.ime_cursor_area.is_some() {
+            self.window.set_ime_allowed(area.is_some());
+            self.composing = false;
+        }
+        if let Some([x, y, _, height]) = area {
+            self.window.set_ime_position(LogicalPosition::new(x, y + height));
+        }
+        self.ime_cursor_area = area;
+    }
+
+    /// Sends input method text to the selected node.
+    pub fn on_ime(&mut self, ime: &Ime) -> Option<M> {
+        let event = match ime {
+            Ime::Preedit(text, _) => {
+                self.composing = !text.is_empty();
+                RadiantImeEvent::Preedit(text.clone())
+            }
+            Ime::Commit(text) => {
+                self.composing = false;
+                RadiantImeEvent::Commit(text.clone())
+            }
+            Ime::Enabled | Ime::Disabled => return None,
+        };
+        Some(RadiantSceneMessage::HandleIme { id: None, event }.into())
+    }
+}
+
+fn to_cursor_icon(cursor: RadiantCursor) -> CursorIcon {
+    match cursor {
+        RadiantCursor::Default => CursorIcon::Default,
+        RadiantCursor::Move => CursorIcon::Move,
+        RadiantCursor::Crosshair => CursorIcon::Crosshair,
+        RadiantCursor::Text => CursorIcon::Text,
+        RadiantCursor::ResizeNorth => CursorIcon::NResize,
+        RadiantCursor::ResizeEast => CursorIcon::EResize,
+        RadiantCursor::ResizeSouth => CursorIcon::SResize,
+        RadiantCursor::ResizeWest => CursorIcon::WResize,
+        RadiantCursor::ResizeNorthEast => CursorIcon::NeResize,
+        RadiantCursor::ResizeNorthWest => CursorIcon::NwResize,
+        RadiantCursor::ResizeSouthEast => CursorIcon::SeResize,
+        RadiantCursor::ResizeSouthWest => CursorIcon::SwResize,
+    }
 }
 
 fn to_keycode(keycode: &VirtualKeyCode) -> KeyCode {
@@ -363,6 +483,8 @@ fn to_keycode(keycode: &VirtualKeyCode) -> KeyCode {
         VirtualKeyCode::Left => KeyCode::ArrowLeft,
         VirtualKeyCode::Right => KeyCode::ArrowRight,
         VirtualKeyCode::Up => KeyCode::ArrowUp,
+        VirtualKeyCode::Home => KeyCode::Home,
+        VirtualKeyCode::End => KeyCode::End,
         VirtualKeyCode::Key0 => KeyCode::Char("0".to_string()),
         VirtualKeyCode::Key1 => KeyCode::Char("1".to_string()),
         VirtualKeyCode::Key2 => KeyCode::Char("2".to_string()),
@@ -431,18 +553,19 @@ pub fn run_native<
                     handler(response);
                 }
             }
+            for response in runtime.take_responses() {
+                handler(response);
+            }
 
             match event {
                 RedrawRequested(..) => {
-                    let output_frame = std::mem::replace(
-                        &mut runtime
-                            .view_mut()
-                            .scene_mut()
-                            .render_manager
-                            .current_texture,
-                        None,
-                    );
-                    output_frame.unwrap().present();
+                    if let Some(render_manager) =
+                        runtime.view_mut().scene_mut().gpu_render_manager_mut()
+                    {
+                        let output_frame =
+                            std::mem::replace(&mut render_manager.current_texture, None);
+                        output_frame.unwrap().present();
+                    }
                 }
                 _ => {}
             }
@@ -473,12 +596,14 @@ pub fn run_wasm<
         event_loop.spawn(move |event, _, control_flow| {
             if let Some(runtime) = weak_runtime.upgrade() {
                 if let Ok(mut runtime) = runtime.write() {
+                    let mut responses = Vec::new();
                     if let Some(message) = runtime.view_mut().handle_event(&event, control_flow) {
-                        if let Some(response) = runtime.handle_message(message) {
-                            let this = JsValue::null();
-                            let _ =
-                                f.call1(&this, &serde_wasm_bindgen::to_value(&response).unwrap());
-                        }
+                        responses.extend(runtime.handle_message(message));
+                    }
+                    responses.extend(runtime.take_responses());
+                    for response in responses {
+                        let this = JsValue::null();
+                        let _ = f.call1(&this, &serde_wasm_bindgen::to_value(&response).unwrap());
                     }
                 }
             }
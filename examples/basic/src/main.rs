@@ -1,5 +1,6 @@
 use radiantkit::{
-    run_native, RadiantPathNode, RadiantRectangleNode, RadiantResponse, RadiantRuntime, Runtime, Vec3,
+    run_native, RadiantPathCommand, RadiantPathNode, RadiantRectangleNode, RadiantResponse,
+    RadiantRuntime, Runtime, Vec3,
 };
 use uuid::Uuid;
 
@@ -16,7 +17,23 @@ async fn run() {
 
     let mut runtime = RadiantRuntime::new(2, false, None, Vec3::zero()).await;
     runtime.add(RadiantRectangleNode::new(Uuid::new_v4(), [100.0, 100.0], [200.0, 200.0]).into());
-    runtime.add(RadiantPathNode::new(Uuid::new_v4(), [400.0, 400.0]).into());
+    runtime.add(
+        RadiantPathNode::new(
+            Uuid::new_v4(),
+            vec![
+                RadiantPathCommand::MoveTo { to: [400.0, 400.0] },
+                RadiantPathCommand::LineTo { to: [600.0, 600.0] },
+                RadiantPathCommand::QuadTo {
+                    control: [500.0, 650.0],
+                    to: [400.0, 800.0],
+                },
+                RadiantPathCommand::LineTo { to: [200.0, 600.0] },
+                RadiantPathCommand::LineTo { to: [350.0, 550.0] },
+                RadiantPathCommand::Close,
+            ],
+        )
+        .into(),
+    );
 
     run_native(runtime, handler);
 }
@@ -1,8 +1,9 @@
 use once_cell::sync::Lazy;
 use radiantkit::Vec3;
 use radiantkit::{
-    RadiantMessage, RadiantPathNode, RadiantRectangleNode, RadiantResponse, RadiantRuntime,
-    RadiantSceneMessage, RadiantTextMessage, RadiantTextNode, Runtime, View,
+    RadiantImageSource, RadiantMessage, RadiantPathCommand, RadiantPathNode, RadiantRectangleNode,
+    RadiantResponse, RadiantRuntime, RadiantSceneMessage, RadiantTextMessage, RadiantTextNode,
+    Runtime, View,
 };
 use std::env;
 use std::iter;
@@ -50,8 +51,13 @@ impl RadiantKitAppController {
                 }
                 if ui.button("Load Image").clicked() {
                     self.pending_messages.push(RadiantMessage::AddImage {
+                        id: None,
                         name: "".to_string(),
-                        path: "https://i.imgur.com/XbLP6ux.png".to_string(),
+                        source: RadiantImageSource::Url(
+                            "https://i.imgur.com/XbLP6ux.png".to_string(),
+                        ),
+                        position: [100.0, 200.0],
+                        size: None,
                     });
                 }
                 if ui.button("Add Text").clicked() {
@@ -99,7 +105,23 @@ async fn run(client_id: u64) {
 
     let mut runtime = RadiantRuntime::new(client_id, true, None, Vec3::zero()).await;
     runtime.add(RadiantRectangleNode::new(*NODE_1, [200.0, 200.0], [200.0, 200.0]).into());
-    runtime.add(RadiantPathNode::new(*NODE_2, [400.0, 400.0]).into());
+    runtime.add(
+        RadiantPathNode::new(
+            *NODE_2,
+            vec![
+                RadiantPathCommand::MoveTo { to: [400.0, 400.0] },
+                RadiantPathCommand::LineTo { to: [600.0, 600.0] },
+                RadiantPathCommand::QuadTo {
+                    control: [500.0, 650.0],
+                    to: [400.0, 800.0],
+                },
+                RadiantPathCommand::LineTo { to: [200.0, 600.0] },
+                RadiantPathCommand::LineTo { to: [350.0, 550.0] },
+                RadiantPathCommand::Close,
+            ],
+        )
+        .into(),
+    );
     runtime.add(
         RadiantTextNode::new(
             *NODE_3,
@@ -124,11 +146,11 @@ async fn run(client_id: u64) {
     let mut egui_rpass;
     {
         let scene = runtime.view.scene_mut();
-        egui_rpass = RenderPass::new(
-            &scene.render_manager.device,
-            scene.render_manager.config.format,
-            1,
-        );
+        let Some(render_manager) = scene.gpu_render_manager() else {
+            log::error!("egui needs a GPU render manager");
+            return;
+        };
+        egui_rpass = RenderPass::new(&render_manager.device, render_manager.config.format, 1);
     }
     let mut demo_app = RadiantKitAppController::new();
 
@@ -141,6 +163,9 @@ async fn run(client_id: u64) {
                     }
                 }
             }
+            for response in runtime.take_responses() {
+                handler(response);
+            }
 
             platform.handle_event(&event);
 
@@ -162,35 +187,38 @@ async fn run(client_id: u64) {
                     let paint_jobs = platform.context().tessellate(full_output.shapes);
 
                     let scene = &mut runtime.scene_mut();
+                    let Some(render_manager) = scene.gpu_render_manager_mut() else {
+                        return;
+                    };
 
                     let output_frame =
-                        std::mem::replace(&mut scene.render_manager.current_texture, None);
+                        std::mem::replace(&mut render_manager.current_texture, None);
                     let output_frame = output_frame.unwrap();
 
-                    let output_view = scene.render_manager.current_view.as_ref().unwrap();
+                    let output_view = render_manager.current_view.as_ref().unwrap();
 
                     // Upload all resources for the GPU.
                     let screen_descriptor = ScreenDescriptor {
-                        physical_width: scene.render_manager.config.width,
-                        physical_height: scene.render_manager.config.height,
+                        physical_width: render_manager.config.width,
+                        physical_height: render_manager.config.height,
                         scale_factor: scale_factor as f32,
                     };
                     let tdelta: egui::TexturesDelta = full_output.textures_delta;
                     egui_rpass
                         .add_textures(
-                            &scene.render_manager.device,
-                            &scene.render_manager.queue,
+                            &render_manager.device,
+                            &render_manager.queue,
                             &tdelta,
                         )
                         .expect("add texture ok");
                     egui_rpass.update_buffers(
-                        &scene.render_manager.device,
-                        &scene.render_manager.queue,
+                        &render_manager.device,
+                        &render_manager.queue,
                         &paint_jobs,
                         &screen_descriptor,
                     );
 
-                    let mut encoder = scene.render_manager.device.create_command_encoder(
+                    let mut encoder = render_manager.device.create_command_encoder(
                         &wgpu::CommandEncoderDescriptor {
                             label: Some("encoder"),
                         },
@@ -207,10 +235,7 @@ async fn run(client_id: u64) {
                         )
                         .unwrap();
                     // Submit the commands.
-                    scene
-                        .render_manager
-                        .queue
-                        .submit(iter::once(encoder.finish()));
+                    render_manager.queue.submit(iter::once(encoder.finish()));
 
                     // Redraw egui
                     output_frame.present();
@@ -1,16 +1,19 @@
 pub mod message;
 pub mod node;
 pub mod runtime;
+pub mod svg_import;
 pub mod tool;
 
 pub use message::*;
 pub use node::*;
 pub use runtime::*;
+pub use svg_import::*;
 pub use tool::*;
 
 pub use radiantkit_core::*;
-pub use radiantkit_image::RadiantImageNode;
+pub use radiantkit_image::{RadiantImageNode, RadiantImageSource};
 pub use radiantkit_path::RadiantPathNode;
+pub use radiantkit_path::RadiantStrokeNode;
 pub use radiantkit_text::RadiantTextMessage;
 pub use radiantkit_text::RadiantTextNode;
 
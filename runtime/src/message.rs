@@ -1,14 +1,21 @@
-use radiantkit_core::{RadiantSceneMessage, RadiantSceneResponse};
+use radiantkit_core::{
+    RadiantFillRule, RadiantPathCommand, RadiantSceneMessage, RadiantSceneResponse,
+};
+use radiantkit_image::{default_image_position, RadiantImageSource};
 use radiantkit_macros::{combine_enum, combine_response, nested_message};
+use radiantkit_path::{RadiantBooleanOp, RadiantStrokePoint};
 use radiantkit_text::RadiantTextMessage;
 
 use crate::RadiantNodeType;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 #[nested_message]
 #[combine_enum(radiantkit_core::RadiantRectangleMessage)]
 #[combine_enum(radiantkit_image::RadiantImageMessage)]
 #[combine_enum(radiantkit_text::RadiantAddTextMessage)]
+#[combine_enum(radiantkit_path::RadiantPathMessage)]
+#[combine_enum(radiantkit_core::RadiantImportMessage)]
 #[cfg_attr(
     all(not(target_arch = "wasm32"), feature = "video"),
     combine_enum(radiantkit_video::RadiantVideoMessage)
@@ -23,4 +30,21 @@ pub enum RadiantMessage {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RadiantResponse {
     NoOp,
+    SvgImported {
+        ids: Vec<Uuid>,
+        unsupported: Vec<String>,
+    },
+    ImportFailed {
+        reason: String,
+    },
+    ImageLoaded {
+        id: Uuid,
+    },
+    ImageFailed {
+        id: Uuid,
+        error: String,
+    },
+    BooleanOpFailed {
+        reason: String,
+    },
 }
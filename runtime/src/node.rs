@@ -4,7 +4,7 @@ use radiantkit_core::{
 };
 use radiantkit_image::RadiantImageNode;
 use radiantkit_macros::{RadiantNode, RadiantTessellatable};
-use radiantkit_path::RadiantPathNode;
+use radiantkit_path::{RadiantPathNode, RadiantStrokeNode};
 use radiantkit_text::RadiantTextNode;
 use serde::{Deserialize, Serialize};
 
@@ -13,6 +13,7 @@ pub enum RadiantNodeType {
     Artboard(RadiantGroupNode<RadiantNodeType>),
     Rectangle(RadiantRectangleNode),
     Path(RadiantPathNode),
+    Stroke(RadiantStrokeNode),
     Image(RadiantImageNode),
     Text(RadiantTextNode),
     #[cfg(all(not(target_arch = "wasm32"), feature = "video"))]
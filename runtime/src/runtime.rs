@@ -1,19 +1,33 @@
 use std::sync::Arc;
 
-use crate::{RadiantMessage, RadiantNodeType, RadiantResponse, RadiantToolType};
-use parking_lot::RwLockWriteGuard;
+use crate::{import_svg, RadiantMessage, RadiantNodeType, RadiantResponse, RadiantToolType};
+use parking_lot::{Mutex, RwLockWriteGuard};
 use radiantkit_collaboration::Collaborator;
 use radiantkit_core::{
-    RadiantRectangleNode, RadiantSceneMessage, RadiantSceneResponse, RadiantTessellatable,
-    RectangleTool, Runtime, Vec3, View,
+    RadiantFillRule, RadiantNode, RadiantRectangleNode, RadiantSceneMessage, RadiantSceneResponse,
+    RadiantTessellatable, RectangleTool, Runtime, Vec3, View,
 };
-use radiantkit_image::{image_loader, RadiantImageNode};
+use radiantkit_image::{image_loader, RadiantImageError, RadiantImageNode};
+use radiantkit_path::{boolean_op, FreehandTool, PenTool, RadiantPathNode, RadiantStrokeNode};
 use radiantkit_text::RadiantTextNode;
 use radiantkit_winit::RadiantView;
 use uuid::Uuid;
 
+/// An image that finished loading and waits to be added to the document.
+struct RadiantLoadedImage {
+    id: Uuid,
+    name: String,
+    position: [f32; 2],
+    size: Option<[f32; 2]>,
+    result: Result<epaint::ColorImage, RadiantImageError>,
+}
+
 pub struct RadiantRuntime {
     pub view: RadiantView<RadiantMessage, RadiantNodeType>,
+    /// Images loaded by [`image_loader::load_image`], possibly on another thread.
+    loaded_images: Arc<Mutex<Vec<RadiantLoadedImage>>>,
+    /// Responses of images that finished loading, for [`Runtime::take_responses`].
+    responses: Vec<RadiantResponse>,
 }
 
 impl RadiantRuntime {
@@ -23,6 +37,13 @@ impl RadiantRuntime {
             RadiantToolType::Rectangle as u32,
             Box::new(RectangleTool::new()),
         );
+        view.scene_mut()
+            .tool_manager
+            .register_tool(RadiantToolType::Pen as u32, Box::new(PenTool::new()));
+        view.scene_mut().tool_manager.register_tool(
+            RadiantToolType::Freehand as u32,
+            Box::new(FreehandTool::new()),
+        );
         if collaborate {
             let doc = Arc::downgrade(&view.scene_mut().document.clone());
             if let Ok(collaborator) = Collaborator::new(client_id, doc).await {
@@ -31,7 +52,60 @@ impl RadiantRuntime {
                 }
             }
         }
-        Self { view }
+        Self {
+            view,
+            loaded_images: Default::default(),
+            responses: Vec::new(),
+        }
+    }
+
+    /// Runs `f` on the stroke node with `id`. Returns `None` if there is no such node.
+    fn with_stroke_node(
+        &mut self,
+        id: Uuid,
+        f: impl FnOnce(&mut RadiantStrokeNode),
+    ) -> Option<()> {
+        let scene = self.view.scene_mut();
+        let mut document = scene.document.try_write()?;
+        let node = document.get_node_mut(id)?;
+        let mut stroke_node = RwLockWriteGuard::try_map(node, |node| match node {
+            RadiantNodeType::Stroke(stroke_node) => Some(stroke_node),
+            _ => None,
+        })
+        .ok()?;
+        f(&mut stroke_node);
+        Some(())
+    }
+
+    /// Adds the images that finished loading since the last call.
+    fn add_loaded_images(&mut self) {
+        let loaded_images = std::mem::take(&mut *self.loaded_images.lock());
+        for image in loaded_images {
+            let id = image.id;
+            let response = match image.result {
+                Ok(color_image) => {
+                    let size = image
+                        .size
+                        .unwrap_or([color_image.size[0] as f32, color_image.size[1] as f32]);
+                    let texture_handle = self.view.scene_mut().texture_manager.load_texture(
+                        image.name,
+                        color_image,
+                        Default::default(),
+                    );
+                    let node = RadiantImageNode::new(id, image.position, size, texture_handle);
+                    self.view.scene_mut().add(node.into());
+                    RadiantResponse::ImageLoaded { id }
+                }
+                Err(error) => {
+                    log::error!("Failed to load image {id}: {error}");
+                    RadiantResponse::ImageFailed {
+                        id,
+                        error: error.to_string(),
+                    }
+                }
+            };
+            self.responses.push(response);
+        }
     }
 }
 
@@ -46,6 +120,11 @@ impl Runtime<'_, RadiantMessage, RadiantNodeType, RadiantResponse> for RadiantRu
         &mut self.view
     }
 
+    fn take_responses(&mut self) -> Vec<RadiantResponse> {
+        self.add_loaded_images();
+        std::mem::take(&mut self.responses)
+    }
+
     fn handle_message(&mut self, message: RadiantMessage) -> Option<RadiantResponse> {
         match message {
             RadiantMessage::SceneMessage(message) => {
@@ -64,8 +143,8 @@ impl Runtime<'_, RadiantMessage, RadiantNodeType, RadiantResponse> for RadiantRu
                 let update_interactions;
                 {
                     let mut scene = self.view.scene_mut();
-                    let document = &mut scene.document;
-                    let Some(mut document) = document.try_write() else {
+                    let scene = &mut *scene;
+                    let Some(mut document) = scene.document.try_write() else {
                         return None;
                     };
                     let Some(node) = document.get_node_mut(id) else {
@@ -78,7 +157,10 @@ impl Runtime<'_, RadiantMessage, RadiantNodeType, RadiantResponse> for RadiantRu
                         return None;
                     };
                     update_interactions = text_node.handle_message(message);
+                    text_node.fit_content(&scene.fonts_manager);
                 }
+                // A restyled run may use a font family the document did not reference yet.
+                self.view.scene_mut().document_mut().update_font_families(id);
                 if update_interactions {
                     return self
                         .handle_message(RadiantSceneMessage::SelectNode { id: Some(id) }.into());
@@ -95,28 +177,26 @@ impl Runtime<'_, RadiantMessage, RadiantNodeType, RadiantResponse> for RadiantRu
                 return self
                     .handle_message(RadiantSceneMessage::SelectNode { id: Some(id) }.into());
             }
-            RadiantMessage::AddImage { path, name } => {
-                let screen_descriptor = self.view.scene().screen_descriptor;
-                let texture_manager = self.view.scene_mut().texture_manager.clone();
-                let document = self.view.scene_mut().document.clone();
-                image_loader::load_image(path, move |response| {
-                    let image = response
-                        .unwrap_or(epaint::ColorImage::new([400, 100], epaint::Color32::RED));
-                    let size = image.size;
-                    if let Some(mut document) = document.try_write() {
-                        let texture_handle =
-                            texture_manager.load_texture(name, image, Default::default());
-                        let id = Uuid::new_v4();
-                        let mut node = RadiantImageNode::new(
-                            id,
-                            [100.0, 200.0],
-                            [size[0] as f32, size[1] as f32],
-                            texture_handle,
-                        );
-                        node.attach(&screen_descriptor);
-                        document.add(node.into());
-                    }
+            RadiantMessage::AddImage {
+                id,
+                name,
+                source,
+                position,
+                size,
+            } => {
+                let id = id.unwrap_or(Uuid::new_v4());
+                let loaded_images = self.loaded_images.clone();
+                image_loader::load_image(source, move |result| {
+                    loaded_images.lock().push(RadiantLoadedImage {
+                        id,
+                        name,
+                        position,
+                        size,
+                        result,
+                    });
                 });
+                // Local sources are already loaded.
+                self.add_loaded_images();
             }
             RadiantMessage::AddText { text, position } => {
                 let id = Uuid::new_v4();
@@ -125,6 +205,108 @@ impl Runtime<'_, RadiantMessage, RadiantNodeType, RadiantResponse> for RadiantRu
                 return self
                     .handle_message(RadiantSceneMessage::SelectNode { id: Some(id) }.into());
             }
+            RadiantMessage::AddPath {
+                id,
+                commands,
+                fill_rule,
+            } => {
+                let id = id.unwrap_or(Uuid::new_v4());
+                let node = RadiantPathNode::new(id, commands).with_fill_rule(fill_rule);
+                self.view.scene_mut().add(node.into());
+                return self
+                    .handle_message(RadiantSceneMessage::SelectNode { id: Some(id) }.into());
+            }
+            RadiantMessage::SetPathCommands { id, commands } => {
+                {
+                    let scene = self.view.scene_mut();
+                    let mut document = scene.document.try_write()?;
+                    let node = document.get_node_mut(id)?;
+                    let Ok(mut path_node) = RwLockWriteGuard::try_map(node, |node| match node {
+                        RadiantNodeType::Path(path_node) => Some(path_node),
+                        _ => None,
+                    }) else {
+                        return None;
+                    };
+                    path_node.set_commands(commands);
+                    path_node.set_needs_tessellation(true);
+                }
+                return self
+                    .handle_message(RadiantSceneMessage::SelectNode { id: Some(id) }.into());
+            }
+            RadiantMessage::AddStroke { id, points } => {
+                let id = id.unwrap_or(Uuid::new_v4());
+                let node = RadiantStrokeNode::new(id, points);
+                self.view.scene_mut().add(node.into());
+            }
+            RadiantMessage::ExtendStroke { id, points } => {
+                self.with_stroke_node(id, |stroke_node| stroke_node.extend_points(points))?;
+            }
+            RadiantMessage::SetStrokePoints { id, points } => {
+                self.with_stroke_node(id, |stroke_node| {
+                    stroke_node.set_points(points);
+                    stroke_node.set_needs_tessellation(true);
+                })?;
+            }
+            RadiantMessage::BooleanOp { op, ids } => {
+                // Nodes without an outline are left out, and kept in the document.
+                let (ids, outlines, color) = {
+                    let scene = self.view.scene();
+                    let document = scene.document();
+                    let mut contributing = Vec::new();
+                    let mut outlines = Vec::new();
+                    let mut color = None;
+                    for id in ids {
+                        let Some(node) = document.get_node(id) else {
+                            continue;
+                        };
+                        let outline = node.to_vector().and_then(|vector| vector.outline());
+                        let Some(outline) = outline else {
+                            continue;
+                        };
+                        color.get_or_insert_with(|| node.color());
+                        contributing.push(id);
+                        outlines.push(outline);
+                    }
+                    (contributing, outlines, color)
+                };
+                let Some(commands) = boolean_op(op, &outlines) else {
+                    let reason = if outlines.is_empty() {
+                        "none of the nodes has an outline"
+                    } else {
+                        "nothing is left of the nodes"
+                    };
+                    return Some(RadiantResponse::BooleanOpFailed {
+                        reason: reason.to_string(),
+                    });
+                };
+                // Nodes are drawn in id order, so the result takes the first node's place.
+                let id = ids[0];
+                for id in ids {
+                    self.handle_message(RadiantSceneMessage::RemoveNode { id }.into());
+                }
+                let mut node =
+                    RadiantPathNode::new(id, commands).with_fill_rule(RadiantFillRule::EvenOdd);
+                if let Some(color) = color {
+                    *node.color_mut() = color;
+                }
+                self.view.scene_mut().add(node.into());
+                return self
+                    .handle_message(RadiantSceneMessage::SelectNode { id: Some(id) }.into());
+            }
+            RadiantMessage::ImportSvg { svg, position } => {
+                return Some(match import_svg(&svg, position) {
+                    Ok((nodes, unsupported)) => {
+                        let ids = nodes.iter().map(|node| node.get_id()).collect();
+                        for node in nodes {
+                            self.view.scene_mut().add(node);
+                        }
+                        RadiantResponse::SvgImported { ids, unsupported }
+                    }
+                    Err(error) => RadiantResponse::ImportFailed {
+                        reason: error.to_string(),
+                    },
+                });
+            }
             #[cfg(all(not(target_arch = "wasm32"), feature = "video"))]
             RadiantMessage::AddVideo { id, name, path } => {
                 let texture_handle = self.view.scene_mut().texture_manager.load_texture(
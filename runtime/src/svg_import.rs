@@ -0,0 +1,114 @@
+use crate::RadiantNodeType;
+use epaint::FontFamily;
+use radiantkit_core::{
+    read_svg, RadiantGroupNode, RadiantImportError, RadiantNode, RadiantPathCommand,
+    RadiantRectangleNode, RadiantSvgImport, RadiantVectorGeometry, RadiantVectorNode,
+};
+use radiantkit_path::RadiantPathNode;
+use radiantkit_text::{RadiantTextNode, RadiantTextStyle};
+
+/// Reads `svg` into nodes offset by `position`, along with the features that could not be
+/// imported. SVG groups become group nodes holding their children.
+pub fn import_svg(
+    svg: &str,
+    position: [f32; 2],
+) -> Result<(Vec<RadiantNodeType>, Vec<String>), RadiantImportError> {
+    let mut import = read_svg(svg)?;
+    let mut nodes = Vec::new();
+    for node in std::mem::take(&mut import.nodes) {
+        add_vector_node(&mut import, node, position, &mut nodes);
+    }
+    Ok((nodes, import.unsupported))
+}
+
+fn add_vector_node(
+    import: &mut RadiantSvgImport,
+    vector: RadiantVectorNode,
+    offset: [f32; 2],
+    nodes: &mut Vec<RadiantNodeType>,
+) {
+    let position = [
+        vector.position[0] + offset[0],
+        vector.position[1] + offset[1],
+    ];
+    let mut node: RadiantNodeType = match vector.geometry {
+        RadiantVectorGeometry::Group { children } => {
+            let mut children_nodes = Vec::new();
+            for child in children {
+                add_vector_node(import, child, position, &mut children_nodes);
+            }
+            if children_nodes.is_empty() {
+                return;
+            }
+            let mut group = RadiantGroupNode::new(vector.id);
+            for child in children_nodes {
+                group.add(child);
+            }
+            nodes.push(group.into());
+            return;
+        }
+        RadiantVectorGeometry::Rect { size } => {
+            RadiantRectangleNode::new(vector.id, position, size).into()
+        }
+        RadiantVectorGeometry::Line { end } => RadiantPathNode::new(
+            vector.id,
+            vec![
+                RadiantPathCommand::MoveTo { to: position },
+                RadiantPathCommand::LineTo {
+                    to: [position[0] + end[0], position[1] + end[1]],
+                },
+            ],
+        )
+        .into(),
+        RadiantVectorGeometry::Path {
+            commands,
+            fill_rule,
+        } => {
+            let commands = commands
+                .into_iter()
+                .map(|command| command.map(|[x, y]| [position[0] + x, position[1] + y]))
+                .collect();
+            RadiantPathNode::new(vector.id, commands)
+                .with_fill_rule(fill_rule)
+                .into()
+        }
+        RadiantVectorGeometry::Text { runs } => {
+            let text: String = runs.iter().map(|run| run.text.as_str()).collect();
+            let mut node = RadiantTextNode::new(vector.id, text, position, [100.0, 100.0]);
+            let mut start = 0;
+            for run in runs {
+                let end = start + run.text.chars().count();
+                let style = RadiantTextStyle {
+                    family: run.family.map_or(FontFamily::Proportional, |family| {
+                        FontFamily::Name(family.into())
+                    }),
+                    size: run.font_size,
+                    color: run.color,
+                    bold: run.bold,
+                    italic: run.italic,
+                    underline: run.underline,
+                    strikethrough: run.strikethrough,
+                };
+                node.spans.set_style(start..end, style);
+                start = end;
+            }
+            node.into()
+        }
+        RadiantVectorGeometry::Image { .. } => {
+            import.report("image");
+            return;
+        }
+    };
+
+    if !matches!(node, RadiantNodeType::Text(_)) {
+        let color = node.color_mut();
+        color.set_fill_color(vector.fill.unwrap_or(epaint::Color32::TRANSPARENT));
+        if let Some(stroke) = vector.stroke {
+            if stroke.width != 1.0 {
+                import.report("stroke width");
+            }
+            color.set_stroke_color(stroke.color);
+        }
+    }
+    nodes.push(node);
+}
@@ -2,4 +2,6 @@
 pub enum RadiantToolType {
     Select = 0, // Default
     Rectangle = 1,
+    Pen = 2,
+    Freehand = 3,
 }
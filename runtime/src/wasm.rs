@@ -46,7 +46,9 @@ impl RadiantKitAppController {
     pub fn handle_message(&mut self, message: JsValue) {
         if let Ok(message) = serde_wasm_bindgen::from_value(message.clone()) {
             if let Ok(mut runtime) = self.runtime.write() {
-                if let Some(response) = runtime.handle_message(message) {
+                let mut responses: Vec<_> = runtime.handle_message(message).into_iter().collect();
+                responses.extend(runtime.take_responses());
+                for response in responses {
                     let this = JsValue::null();
                     let _ = self
                         .callback
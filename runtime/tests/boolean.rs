@@ -0,0 +1,120 @@
+use radiantkit_core::{
+    flatten_commands, subpaths_contain, RadiantFillRule, RadiantNode, RadiantPathCommand,
+    RadiantRectangleNode, DEFAULT_FLATTENING_TOLERANCE,
+};
+use radiantkit_path::{boolean_op, RadiantBooleanOp, RadiantStrokeNode, RadiantStrokePoint};
+use uuid::Uuid;
+
+fn square(min: [f32; 2], size: f32) -> Vec<RadiantPathCommand> {
+    let [x, y] = min;
+    vec![
+        RadiantPathCommand::MoveTo { to: [x, y] },
+        RadiantPathCommand::LineTo { to: [x + size, y] },
+        RadiantPathCommand::LineTo {
+            to: [x + size, y + size],
+        },
+        RadiantPathCommand::LineTo { to: [x, y + size] },
+        RadiantPathCommand::Close,
+    ]
+}
+
+fn contains(commands: &[RadiantPathCommand], point: [f32; 2]) -> bool {
+    let subpaths = flatten_commands(commands, DEFAULT_FLATTENING_TOLERANCE);
+    subpaths_contain(&subpaths, point, RadiantFillRule::EvenOdd)
+}
+
+#[test]
+fn test_boolean_ops_on_overlapping_squares() {
+    let outlines = [
+        (square([0.0, 0.0], 100.0), RadiantFillRule::NonZero),
+        (square([50.0, 50.0], 100.0), RadiantFillRule::NonZero),
+    ];
+    // Points only in the first square, in both, and only in the second.
+    let points = [[25.0, 25.0], [75.0, 75.0], [125.0, 125.0]];
+    let expected = [
+        (RadiantBooleanOp::Union, [true, true, true]),
+        (RadiantBooleanOp::Subtract, [true, false, false]),
+        (RadiantBooleanOp::Intersect, [false, true, false]),
+        (RadiantBooleanOp::Exclude, [true, false, true]),
+    ];
+    for (op, inside) in expected {
+        let result = boolean_op(op, &outlines).unwrap();
+        for (point, inside) in points.iter().zip(inside) {
+            assert_eq!(contains(&result, *point), inside, "{op:?} at {point:?}");
+        }
+    }
+
+    let disjoint = [
+        (square([0.0, 0.0], 10.0), RadiantFillRule::NonZero),
+        (square([50.0, 50.0], 10.0), RadiantFillRule::NonZero),
+    ];
+    assert!(boolean_op(RadiantBooleanOp::Intersect, &disjoint).is_none());
+}
+
+#[test]
+fn test_boolean_op_keeps_holes() {
+    // The inner square is wound against the outer one, so it is a hole under either rule.
+    let inner = vec![
+        RadiantPathCommand::MoveTo { to: [25.0, 25.0] },
+        RadiantPathCommand::LineTo { to: [25.0, 75.0] },
+        RadiantPathCommand::LineTo { to: [75.0, 75.0] },
+        RadiantPathCommand::LineTo { to: [75.0, 25.0] },
+        RadiantPathCommand::Close,
+    ];
+    let donut = [square([0.0, 0.0], 100.0), inner].concat();
+
+    for fill_rule in [RadiantFillRule::NonZero, RadiantFillRule::EvenOdd] {
+        let result = boolean_op(RadiantBooleanOp::Union, &[(donut.clone(), fill_rule)]).unwrap();
+        assert!(contains(&result, [10.0, 10.0]));
+        assert!(!contains(&result, [50.0, 50.0]));
+    }
+}
+
+#[test]
+fn test_only_filled_nodes_have_outlines() {
+    let rectangle = RadiantRectangleNode::new(Uuid::new_v4(), [10.0, 20.0], [30.0, 40.0]);
+    let (commands, _) = rectangle.to_vector().unwrap().outline().unwrap();
+    assert!(contains(&commands, [25.0, 40.0]));
+
+    let point = |x: f32, y: f32| RadiantStrokePoint {
+        position: [x, y],
+        width: 4.0,
+    };
+    let stroke = RadiantStrokeNode::new(
+        Uuid::new_v4(),
+        vec![point(0.0, 0.0), point(50.0, 40.0), point(100.0, 0.0)],
+    );
+    assert!(stroke.to_vector().unwrap().outline().is_none());
+}
+
+#[test]
+fn test_boolean_ops_on_degenerate_outlines() {
+    let sliver = vec![
+        RadiantPathCommand::MoveTo { to: [0.0, 0.0] },
+        RadiantPathCommand::LineTo { to: [50.0, 50.0] },
+        RadiantPathCommand::LineTo {
+            to: [100.0, 100.000_01],
+        },
+        RadiantPathCommand::LineTo { to: [50.0, 50.0] },
+        RadiantPathCommand::Close,
+    ];
+    let outlines = [
+        (square([0.0, 0.0], 100.0), RadiantFillRule::NonZero),
+        (sliver, RadiantFillRule::NonZero),
+        (square([100.0, 0.0], 100.0), RadiantFillRule::EvenOdd),
+    ];
+    for op in [
+        RadiantBooleanOp::Union,
+        RadiantBooleanOp::Subtract,
+        RadiantBooleanOp::Intersect,
+        RadiantBooleanOp::Exclude,
+    ] {
+        boolean_op(op, &outlines);
+    }
+
+    let union = boolean_op(RadiantBooleanOp::Union, &outlines).unwrap();
+    assert!(contains(&union, [150.0, 50.0]));
+    // Squares that only touch have nothing in common.
+    let touching = [outlines[0].clone(), outlines[2].clone()];
+    assert!(boolean_op(RadiantBooleanOp::Intersect, &touching).is_none());
+}
@@ -0,0 +1,112 @@
+use parking_lot::RwLock;
+use radiantkit::RadiantNodeType;
+use radiantkit_collaboration::RemoteChanges;
+use radiantkit_core::{RadiantDocumentNode, RadiantExportTarget};
+use radiantkit_text::{RadiantTextNode, RadiantTextStyle};
+use std::sync::Arc;
+use uuid::Uuid;
+use yrs::{types::Value, Doc, Map, Text, TextPrelim, Transact};
+
+const ROOT: &str = "radiantkit-root";
+const TEXTS: &str = "radiantkit-text";
+
+fn styled_text(id: Uuid, text: &str, bold: usize) -> RadiantTextNode {
+    let mut node = RadiantTextNode::new(id, text.into(), [0.0; 2], [0.0; 2]);
+    node.spans.set_style(
+        0..bold,
+        RadiantTextStyle {
+            bold: true,
+            ..Default::default()
+        },
+    );
+    node
+}
+
+/// A document with one text node, shared in `doc` and observed for remote changes.
+fn shared_text(
+    doc: &Doc,
+    id: Uuid,
+) -> (
+    Arc<RwLock<RadiantDocumentNode<RadiantNodeType>>>,
+    RemoteChanges,
+) {
+    let node = styled_text(id, "Hello world", 5);
+    let root = doc.get_or_insert_map(ROOT);
+    let texts = doc.get_or_insert_map(TEXTS);
+    {
+        let mut txn = doc.transact_mut();
+        root.insert(
+            &mut txn,
+            id.to_string(),
+            serde_json::to_string(&node).unwrap(),
+        );
+        texts.insert(&mut txn, id.to_string(), TextPrelim::new("Hello world"));
+    }
+    let document = Arc::new(RwLock::new(RadiantDocumentNode::new()));
+    document.write().add(node.into());
+    let remote_changes =
+        RemoteChanges::observe(doc, Arc::downgrade(&document), Uuid::new_v4()).unwrap();
+    (document, remote_changes)
+}
+
+fn text_node(document: &RwLock<RadiantDocumentNode<RadiantNodeType>>, id: Uuid) -> RadiantTextNode {
+    match &*document.read().get_node(id).unwrap() {
+        RadiantNodeType::Text(node) => node.clone(),
+        _ => panic!("expected a text node"),
+    }
+}
+
+#[test]
+fn test_remote_text_edit_keeps_the_styles_of_its_characters() {
+    let id = Uuid::new_v4();
+    let doc = Doc::new();
+    let (document, _remote_changes) = shared_text(&doc, id);
+
+    // A peer types at the start, and sends the characters and its node in one transaction.
+    let root = doc.get_or_insert_map(ROOT);
+    let texts = doc.get_or_insert_map(TEXTS);
+    {
+        let mut txn = doc.transact_mut();
+        let Some(Value::YText(text)) = texts.get(&txn, &id.to_string()) else {
+            panic!("expected shared text");
+        };
+        text.insert(&mut txn, 0, "X");
+        let node = styled_text(id, "XHello world", 6);
+        root.insert(
+            &mut txn,
+            id.to_string(),
+            serde_json::to_string(&node).unwrap(),
+        );
+    }
+
+    let node = text_node(&document, id);
+    assert_eq!(node.text, "XHello world");
+    let lengths: Vec<usize> = node.spans.iter().map(|span| span.length).collect();
+    assert_eq!(lengths, vec![6, 6]);
+    assert!(node.spans.style_at(5).bold);
+    assert!(!node.spans.style_at(6).bold);
+}
+
+#[test]
+fn test_invalid_remote_nodes_are_ignored() {
+    let id = Uuid::new_v4();
+    let doc = Doc::new();
+    let (document, _remote_changes) = shared_text(&doc, id);
+
+    let root = doc.get_or_insert_map(ROOT);
+    {
+        let mut txn = doc.transact_mut();
+        root.insert(&mut txn, Uuid::new_v4().to_string(), "not a node");
+        root.insert(&mut txn, "not an id", "{}");
+        root.insert(&mut txn, id.to_string(), "{\"text\": 1}");
+    }
+
+    assert_eq!(
+        document
+            .read()
+            .export_nodes(RadiantExportTarget::Document)
+            .len(),
+        1
+    );
+    assert_eq!(text_node(&document, id).text, "Hello world");
+}
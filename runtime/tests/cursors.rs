@@ -0,0 +1,56 @@
+use radiantkit::{RadiantMessage, RadiantNodeType};
+use radiantkit_core::{
+    BoundingBoxInteraction, RadiantCursor, RadiantNode, RadiantRectangleNode, RadiantScene,
+    RadiantSceneMessage, RadiantTool, RectangleTool, ScreenDescriptor, SelectionTool,
+};
+use uuid::Uuid;
+
+#[test]
+fn test_bounding_box_handles_request_resize_cursors() {
+    let interaction = BoundingBoxInteraction::new();
+
+    assert_eq!(
+        interaction.cursor(interaction.nodes[0].get_id()),
+        Some(RadiantCursor::ResizeNorth)
+    );
+    assert_eq!(
+        interaction.cursor(interaction.corner_nodes[1].get_id()),
+        Some(RadiantCursor::ResizeSouthEast)
+    );
+    assert_eq!(interaction.cursor(Uuid::new_v4()), None);
+}
+
+#[test]
+fn test_tools_pick_cursor() {
+    let selection_tool = SelectionTool::new();
+    let hovered = Some(RadiantCursor::ResizeWest);
+    assert_eq!(
+        RadiantTool::<RadiantSceneMessage>::cursor(&selection_tool, hovered),
+        RadiantCursor::ResizeWest
+    );
+    assert_eq!(
+        RadiantTool::<RadiantSceneMessage>::cursor(&selection_tool, None),
+        RadiantCursor::Default
+    );
+
+    let rectangle_tool = RectangleTool::new();
+    assert_eq!(
+        RadiantTool::<radiantkit::RadiantMessage>::cursor(&rectangle_tool, hovered),
+        RadiantCursor::Crosshair
+    );
+}
+
+#[test]
+fn test_cursor_is_picked_again_when_the_scene_changes() {
+    let mut scene =
+        RadiantScene::<RadiantMessage, RadiantNodeType>::new_headless(ScreenDescriptor {
+            size_in_pixels: [64, 64],
+            pixels_per_point: 1.0,
+        });
+    let id = Uuid::new_v4();
+    scene.add(RadiantRectangleNode::new(id, [10.0, 10.0], [20.0, 20.0]).into());
+    assert_eq!(scene.cursor([20.0, 20.0]), RadiantCursor::Move);
+
+    scene.handle_message(RadiantSceneMessage::RemoveNode { id });
+    assert_eq!(scene.cursor([20.0, 20.0]), RadiantCursor::Default);
+}
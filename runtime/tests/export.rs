@@ -0,0 +1,259 @@
+use epaint::{text::FontDefinitions, FontFamily};
+use radiantkit::{RadiantMessage, RadiantNodeType, RadiantTextNode};
+use radiantkit_core::{
+    write_svg, RadiantExportBackground, RadiantExportError, RadiantExportTarget,
+    RadiantImageExportOptions, RadiantImageFormat, RadiantNode, RadiantRectangleNode, RadiantScene,
+    RadiantSceneMessage, RadiantVectorGeometry, ScreenDescriptor,
+};
+use radiantkit_path::{RadiantStrokeNode, RadiantStrokePoint};
+use radiantkit_text::RadiantTextStyle;
+use uuid::Uuid;
+
+fn png_size(data: &[u8]) -> [u32; 2] {
+    assert_eq!(&data[..8], b"\x89PNG\r\n\x1a\n");
+    let width = u32::from_be_bytes(data[16..20].try_into().unwrap());
+    let height = u32::from_be_bytes(data[20..24].try_into().unwrap());
+    [width, height]
+}
+
+#[test]
+fn test_export_image_is_independent_of_window_size() {
+    let mut scene =
+        RadiantScene::<RadiantMessage, RadiantNodeType>::new_headless(ScreenDescriptor {
+            size_in_pixels: [16, 16],
+            pixels_per_point: 1.0,
+        });
+    let id = Uuid::new_v4();
+    scene.add(RadiantRectangleNode::new(id, [100.0, 100.0], [40.0, 20.0]).into());
+
+    let options = RadiantImageExportOptions::default();
+    let [width, height] = png_size(&scene.export_image(&options).unwrap());
+    assert!((40..=44).contains(&width), "width {width}");
+    assert!((20..=24).contains(&height), "height {height}");
+
+    let options = RadiantImageExportOptions {
+        scale: 2.0,
+        background: RadiantExportBackground::Filled {
+            color: epaint::Color32::WHITE,
+        },
+        ..options
+    };
+    let [width_2x, height_2x] = png_size(&scene.export_image(&options).unwrap());
+    assert!(width_2x >= 2 * width - 2 && width_2x <= 2 * width);
+    assert!(height_2x >= 2 * height - 2 && height_2x <= 2 * height);
+
+    let jpeg = scene
+        .export_image(&RadiantImageExportOptions {
+            format: RadiantImageFormat::Jpeg { quality: 90 },
+            ..options
+        })
+        .unwrap();
+    assert_eq!(&jpeg[..2], &[0xff, 0xd8]);
+}
+
+#[test]
+fn test_export_image_errors() {
+    let mut scene =
+        RadiantScene::<RadiantMessage, RadiantNodeType>::new_headless(ScreenDescriptor {
+            size_in_pixels: [16, 16],
+            pixels_per_point: 1.0,
+        });
+    let options = RadiantImageExportOptions {
+        target: RadiantExportTarget::Selection,
+        ..Default::default()
+    };
+    assert_eq!(
+        scene.export_image(&options),
+        Err(RadiantExportError::EmptyTarget)
+    );
+    assert_eq!(
+        scene.export_image(&RadiantImageExportOptions {
+            scale: 0.0,
+            ..options
+        }),
+        Err(RadiantExportError::InvalidScale { scale: 0.0 })
+    );
+}
+
+#[test]
+fn test_export_svg() {
+    let mut scene =
+        RadiantScene::<RadiantMessage, RadiantNodeType>::new_headless(ScreenDescriptor {
+            size_in_pixels: [16, 16],
+            pixels_per_point: 1.0,
+        });
+    assert_eq!(
+        scene.export_svg(RadiantExportTarget::Document),
+        Err(RadiantExportError::EmptyTarget)
+    );
+
+    let id = Uuid::new_v4();
+    scene.add(RadiantRectangleNode::new(id, [100.0, 100.0], [40.0, 20.0]).into());
+    let svg = scene.export_svg(RadiantExportTarget::Document).unwrap();
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.contains(r#"viewBox="100 100 40 20""#), "{svg}");
+    assert!(
+        svg.contains(&format!(
+            r#"<rect width="40" height="20" id="node-{id}" transform="translate(100 100)""#
+        )),
+        "{svg}"
+    );
+    assert!(svg.trim_end().ends_with("</g></svg>"));
+}
+
+#[test]
+fn test_export_pdf_writes_a_page_per_artboard() {
+    let mut scene =
+        RadiantScene::<RadiantMessage, RadiantNodeType>::new_headless(ScreenDescriptor {
+            size_in_pixels: [16, 16],
+            pixels_per_point: 1.0,
+        });
+    assert_eq!(scene.export_pdf(), Err(RadiantExportError::EmptyTarget));
+
+    scene.add(RadiantRectangleNode::new(Uuid::new_v4(), [0.0, 0.0], [40.0, 20.0]).into());
+    {
+        let mut document = scene.document_mut();
+        document.add_artboard();
+        let id = *document
+            .artboards
+            .keys()
+            .find(|id| **id != document.active_artboard_id)
+            .unwrap();
+        document.active_artboard_id = id;
+    }
+    scene.add(
+        RadiantTextNode::new(
+            Uuid::new_v4(),
+            "Hello".to_string(),
+            [0.0, 0.0],
+            [100.0, 100.0],
+        )
+        .into(),
+    );
+
+    let pdf = scene.export_pdf().unwrap();
+    assert!(pdf.starts_with(b"%PDF-"));
+    let pages = pdf
+        .windows(9)
+        .filter(|window| *window == b"/MediaBox")
+        .count();
+    assert_eq!(pages, 2);
+    assert!(pdf.windows(10).any(|window| window == b"/ToUnicode"));
+}
+
+fn count(data: &[u8], pattern: &[u8]) -> usize {
+    data.windows(pattern.len())
+        .filter(|window| *window == pattern)
+        .count()
+}
+
+#[test]
+fn test_export_pdf_embeds_the_fonts_of_each_glyph() {
+    let mut scene =
+        RadiantScene::<RadiantMessage, RadiantNodeType>::new_headless(ScreenDescriptor {
+            size_in_pixels: [16, 16],
+            pixels_per_point: 1.0,
+        });
+    scene.handle_message(RadiantSceneMessage::LoadFont {
+        family: "Brand".into(),
+        name: "brand mono".into(),
+        data: FontDefinitions::default().font_data["Hack"].font.to_vec(),
+    });
+    let style = RadiantTextStyle {
+        family: FontFamily::Name("Brand".into()),
+        italic: true,
+        ..Default::default()
+    };
+    // Hack has no emoji, so that falls back to the default fonts.
+    let node = RadiantTextNode::new(
+        Uuid::new_v4(),
+        "Hi \u{1F600}".into(),
+        [0.0, 0.0],
+        [100.0, 100.0],
+    )
+    .with_style(style);
+    scene.add(node.into());
+
+    let pdf = scene.export_pdf().unwrap();
+    assert_eq!(count(&pdf, b"/BaseFont /brand_mono"), 2);
+    assert_eq!(count(&pdf, b"/BaseFont /NotoEmoji-Regular"), 2);
+    assert_eq!(count(&pdf, b"/BaseFont /Ubuntu-Light"), 0);
+    assert_eq!(count(&pdf, b"/ToUnicode"), 2);
+}
+
+#[test]
+fn test_export_image_spans_tiles_up_to_the_size_cap() {
+    let mut scene =
+        RadiantScene::<RadiantMessage, RadiantNodeType>::new_headless(ScreenDescriptor {
+            size_in_pixels: [16, 16],
+            pixels_per_point: 1.0,
+        });
+    scene.add(RadiantRectangleNode::new(Uuid::new_v4(), [0.0, 0.0], [1500.0, 30.0]).into());
+
+    let options = RadiantImageExportOptions::default();
+    let [width, height] = png_size(&scene.export_image(&options).unwrap());
+    assert!((1500..=1502).contains(&width), "width {width}");
+    assert!((30..=32).contains(&height), "height {height}");
+
+    assert!(matches!(
+        scene.export_image(&RadiantImageExportOptions {
+            scale: 8.0,
+            ..options
+        }),
+        Err(RadiantExportError::TooLarge { .. })
+    ));
+}
+
+#[test]
+fn test_vector_nodes_keep_the_drawn_stroke() {
+    let mut rectangle = RadiantRectangleNode::new(Uuid::new_v4(), [0.0, 0.0], [40.0, 20.0]);
+    rectangle
+        .color_mut()
+        .set_stroke_color(epaint::Color32::BLACK);
+    assert_eq!(rectangle.to_vector().unwrap().stroke, None);
+
+    let point = |x: f32, width: f32| RadiantStrokePoint {
+        position: [x, 0.0],
+        width,
+    };
+    let stroke = RadiantStrokeNode::new(
+        Uuid::new_v4(),
+        vec![point(0.0, 4.0), point(50.0, 12.0), point(100.0, 4.0)],
+    );
+    assert_eq!(
+        stroke.to_vector().unwrap().stroke,
+        Some(epaint::Stroke::new(12.0, epaint::Color32::WHITE))
+    );
+}
+
+#[test]
+fn test_export_svg_keeps_text_runs() {
+    let mut node = RadiantTextNode::new(
+        Uuid::new_v4(),
+        "Hi bold\nnext".to_string(),
+        [0.0, 0.0],
+        [100.0, 100.0],
+    );
+    node.spans.set_style(
+        3..7,
+        RadiantTextStyle {
+            bold: true,
+            color: epaint::Color32::RED,
+            ..Default::default()
+        },
+    );
+    let vector = node.to_vector().unwrap();
+    let RadiantVectorGeometry::Text { runs } = &vector.geometry else {
+        panic!("text exported as {:?}", vector.geometry);
+    };
+    assert_eq!(runs.len(), 3);
+    assert_eq!(runs[1].text, "bold");
+    assert!(runs[1].bold && !runs[2].bold);
+
+    let svg = write_svg(&[vector], |_| None).unwrap();
+    assert!(
+        svg.contains(r##"font-size="24" fill="#ff0000" font-weight="bold">bold</tspan>"##),
+        "{svg}"
+    );
+    assert!(svg.contains(r#"bold</tspan><tspan x="0" y="28.8"#), "{svg}");
+}
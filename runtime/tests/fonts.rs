@@ -0,0 +1,143 @@
+use epaint::{text::FontDefinitions, FontFamily};
+use radiantkit::{RadiantMessage, RadiantNodeType, RadiantTextNode};
+use radiantkit_core::{
+    bold_family, RadiantFontError, RadiantFontRegistry, RadiantScene, RadiantSceneMessage,
+    RadiantSceneResponse, ScreenDescriptor,
+};
+use radiantkit_text::RadiantTextStyle;
+use uuid::Uuid;
+
+fn font_bytes(name: &str) -> Vec<u8> {
+    FontDefinitions::default().font_data[name].font.to_vec()
+}
+
+#[test]
+fn test_font_registry_chains_fallbacks() {
+    let mut registry = RadiantFontRegistry::new();
+    assert!(matches!(
+        registry.load("Brand", "brand", vec![0; 16]),
+        Err(RadiantFontError::InvalidFont { .. })
+    ));
+    registry
+        .load("Brand", "brand", font_bytes("Ubuntu-Light"))
+        .unwrap();
+    registry
+        .load("Symbols", "symbols", font_bytes("NotoEmoji-Regular"))
+        .unwrap();
+    assert_eq!(
+        registry.set_fallbacks("Missing", Vec::new()),
+        Err(RadiantFontError::UnknownFamily {
+            family: "Missing".into()
+        })
+    );
+    // A cycle between families ends where it started.
+    registry
+        .set_fallbacks("Brand", vec!["Symbols".into()])
+        .unwrap();
+    registry
+        .set_fallbacks("Symbols", vec!["Brand".into()])
+        .unwrap();
+
+    let definitions = registry.font_definitions();
+    let brand = &definitions.families[&FontFamily::Name("Brand".into())];
+    assert_eq!(brand[..2], ["brand".to_string(), "symbols".to_string()]);
+    let defaults = &definitions.families[&FontFamily::Proportional];
+    assert_eq!(brand.len(), 2 + defaults.len());
+}
+
+#[test]
+fn test_scene_loads_fonts_for_document_families() {
+    let mut scene =
+        RadiantScene::<RadiantMessage, RadiantNodeType>::new_headless(ScreenDescriptor {
+            size_in_pixels: [200, 100],
+            pixels_per_point: 1.0,
+        });
+    let style = RadiantTextStyle {
+        family: FontFamily::Name("Brand".into()),
+        ..Default::default()
+    };
+    let node = RadiantTextNode::new(Uuid::new_v4(), "Hello".into(), [0.0, 0.0], [100.0, 100.0])
+        .with_style(style);
+    scene.add(node.into());
+    assert!(scene.document().font_families.contains("Brand"));
+
+    // Text in a family that is not loaded yet is drawn in the default one.
+    scene.render().unwrap();
+
+    let response = scene.handle_message(RadiantSceneMessage::LoadFont {
+        family: "Brand".into(),
+        name: "brand".into(),
+        data: font_bytes("Hack"),
+    });
+    assert!(matches!(
+        response,
+        Some(RadiantSceneResponse::FontLoaded { family, .. }) if family == "Brand"
+    ));
+    assert!(scene.font_registry().contains_family("Brand"));
+    scene.render().unwrap();
+}
+
+#[test]
+fn test_document_font_families_follow_their_nodes() {
+    let mut scene =
+        RadiantScene::<RadiantMessage, RadiantNodeType>::new_headless(ScreenDescriptor {
+            size_in_pixels: [200, 100],
+            pixels_per_point: 1.0,
+        });
+    let style = RadiantTextStyle {
+        family: FontFamily::Name("Brand".into()),
+        ..Default::default()
+    };
+    let ids = [Uuid::new_v4(), Uuid::new_v4()];
+    for id in ids {
+        let node = RadiantTextNode::new(id, "Hello".into(), [0.0, 0.0], [100.0, 100.0])
+            .with_style(style.clone());
+        scene.add(node.into());
+    }
+    let mut document = scene.document_mut();
+    assert!(!document.update_font_families(ids[0]));
+
+    // The family stays in use until its last node is gone.
+    document.remove(ids[0]);
+    assert!(document.font_families.contains("Brand"));
+    document.remove(ids[1]);
+    assert!(document.font_families.is_empty());
+}
+
+/// `data` with the weight of its face set to `weight`.
+fn with_weight(mut data: Vec<u8>, weight: u16) -> Vec<u8> {
+    let tables = u16::from_be_bytes([data[4], data[5]]) as usize;
+    let record = (0..tables)
+        .map(|index| 12 + index * 16)
+        .find(|record| &data[*record..*record + 4] == b"OS/2")
+        .unwrap();
+    let offset = u32::from_be_bytes(data[record + 8..record + 12].try_into().unwrap()) as usize;
+    data[offset + 4..offset + 6].copy_from_slice(&weight.to_be_bytes());
+    data
+}
+
+#[test]
+fn test_font_registry_puts_bold_faces_in_a_bold_family() {
+    let mut registry = RadiantFontRegistry::new();
+    registry
+        .load(
+            "Brand",
+            "brand-bold",
+            with_weight(font_bytes("Ubuntu-Light"), 700),
+        )
+        .unwrap();
+    registry
+        .load("Brand", "brand", font_bytes("Ubuntu-Light"))
+        .unwrap();
+    registry.load("Plain", "plain", font_bytes("Hack")).unwrap();
+
+    let definitions = registry.font_definitions();
+    let regular = &definitions.families[&FontFamily::Name("Brand".into())];
+    assert_eq!(
+        regular[..2],
+        ["brand".to_string(), "brand-bold".to_string()]
+    );
+    let bold = &definitions.families[&bold_family("Brand")];
+    assert_eq!(bold[..2], ["brand-bold".to_string(), "brand".to_string()]);
+    assert!(!definitions.families.contains_key(&bold_family("Plain")));
+}
@@ -0,0 +1,156 @@
+use epaint::{text::FontDefinitions, Fonts};
+use radiantkit::RadiantMessage;
+use radiantkit_core::{
+    RadiantNode, RadiantTessellatable, RadiantTool, ScreenDescriptor, SubscriptionCallback,
+};
+use radiantkit_path::{simplify_stroke, FreehandTool, RadiantStrokeNode, RadiantStrokePoint};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+#[test]
+fn test_freehand_tool_streams_and_simplifies_a_stroke() {
+    let mut tool = FreehandTool::new();
+    let Some(RadiantMessage::AddStroke { id, points }) =
+        RadiantTool::<RadiantMessage>::on_mouse_down(&mut tool, None, [0.0, 0.0])
+    else {
+        panic!("expected the stroke to be added");
+    };
+    assert_eq!(points.len(), 1);
+
+    for x in 1..=50 {
+        let message =
+            RadiantTool::<RadiantMessage>::on_mouse_move(&mut tool, [x as f32 * 2.0, 0.0]);
+        assert!(matches!(
+            message,
+            Some(RadiantMessage::ExtendStroke { id: extended, .. }) if Some(extended) == id
+        ));
+    }
+
+    let Some(RadiantMessage::SetStrokePoints { points, .. }) =
+        RadiantTool::<RadiantMessage>::on_mouse_up(&mut tool, [100.0, 0.0])
+    else {
+        panic!("expected the finished stroke");
+    };
+    // A straight line simplifies to its two ends.
+    assert_eq!(points.len(), 2);
+    assert_eq!(points[1].position, [100.0, 0.0]);
+}
+
+#[test]
+fn test_simplify_stroke_keeps_corners() {
+    let point = |x: f32, y: f32| RadiantStrokePoint {
+        position: [x, y],
+        width: 2.0,
+    };
+    let points = vec![
+        point(0.0, 0.0),
+        point(50.0, 0.1),
+        point(100.0, 0.0),
+        point(100.0, 50.0),
+        point(100.0, 100.0),
+    ];
+
+    let simplified = simplify_stroke(&points, 1.0);
+    assert_eq!(simplified, vec![points[0], points[2], points[4]]);
+}
+
+#[test]
+fn test_stroke_hit_test_uses_width() {
+    let point = |x: f32, width: f32| RadiantStrokePoint {
+        position: [x, 0.0],
+        width,
+    };
+    let stroke = RadiantStrokeNode::new(
+        Uuid::new_v4(),
+        vec![point(0.0, 10.0), point(50.0, 10.0), point(100.0, 10.0)],
+    );
+
+    assert!(stroke.hit_test([50.0, 4.0], 0.0));
+    assert!(!stroke.hit_test([50.0, 6.0], 0.0));
+    assert_eq!(stroke.get_bounding_rect(), [-5.0, -5.0, 105.0, 5.0]);
+}
+
+#[test]
+fn test_simplify_stroke_keeps_width_changes() {
+    let point = |x: f32, width: f32| RadiantStrokePoint {
+        position: [x, 0.0],
+        width,
+    };
+    let points = vec![point(0.0, 4.0), point(10.0, 12.0), point(20.0, 4.0)];
+    assert_eq!(simplify_stroke(&points, 0.75), points);
+
+    let points = vec![point(0.0, 4.0), point(10.0, 5.0), point(20.0, 6.0)];
+    assert_eq!(simplify_stroke(&points, 0.75), vec![points[0], points[2]]);
+}
+
+#[test]
+fn test_extending_a_stroke_matches_building_it_at_once() {
+    let point = |x: f32, y: f32| RadiantStrokePoint {
+        position: [x, y],
+        width: 4.0,
+    };
+    let points = vec![
+        point(50.0, 50.0),
+        point(70.0, 45.0),
+        point(85.0, 60.0),
+        point(60.0, 90.0),
+        point(30.0, 70.0),
+        point(20.0, 30.0),
+    ];
+    let screen_descriptor = ScreenDescriptor {
+        size_in_pixels: [200, 200],
+        pixels_per_point: 1.0,
+    };
+    let fonts = Fonts::new(1.0, 1600, FontDefinitions::default());
+
+    let mut stroke = RadiantStrokeNode::new(Uuid::new_v4(), points[..2].to_vec());
+    stroke.tessellate(false, &screen_descriptor, &fonts);
+    for point in &points[2..] {
+        let revision = stroke.base().revision;
+        stroke.extend_points([*point]);
+        assert!(stroke.base().revision > revision);
+        assert!(!stroke
+            .tessellate(false, &screen_descriptor, &fonts)
+            .is_empty());
+    }
+
+    let built = RadiantStrokeNode::new(Uuid::new_v4(), points.clone());
+    assert_eq!(stroke.transformed_points(), built.transformed_points());
+    let [x0, y0, x1, y1] = stroke.get_bounding_rect();
+    let [bx0, by0, bx1, by1] = built.get_bounding_rect();
+    assert!(x0 <= bx0 && y0 <= by0 && x1 >= bx1 && y1 >= by1);
+    assert!(bx0 - x0 < 1.0 && by0 - y0 < 1.0 && x1 - bx1 < 1.0 && y1 - by1 < 1.0);
+}
+
+#[test]
+fn test_extending_a_stroke_streams_it_to_observers() {
+    let point = |x: f32| RadiantStrokePoint {
+        position: [x, 0.0],
+        width: 2.0,
+    };
+    let mut node = RadiantStrokeNode::new(Uuid::new_v4(), vec![point(0.0)]);
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let sink = sent.clone();
+    let callback: Arc<SubscriptionCallback> =
+        Arc::new(move |data: &str| sink.lock().unwrap().push(data.to_string()));
+    let _subscription = node.base.observers.subscribe(callback);
+
+    for x in 1..20 {
+        node.extend_points([point(x as f32)]);
+    }
+
+    // Observers see the stroke grow while it is drawn, not only once it is finished.
+    let sent = sent.lock().unwrap();
+    assert!(sent.len() > 1);
+    let streamed: Vec<usize> = sent
+        .iter()
+        .map(|data| {
+            serde_json::from_str::<RadiantStrokeNode>(data)
+                .unwrap()
+                .points
+                .len()
+        })
+        .collect();
+    assert!(streamed.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(streamed.iter().all(|&points| points < 20));
+}
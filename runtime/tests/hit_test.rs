@@ -0,0 +1,17 @@
+use radiantkit::RadiantNodeType;
+use radiantkit_core::{RadiantDocumentNode, RadiantRectangleNode};
+use uuid::Uuid;
+
+#[test]
+fn test_hit_stack_is_topmost_first() {
+    let mut document = RadiantDocumentNode::<RadiantNodeType>::new();
+    let bottom = Uuid::from_u128(1);
+    let top = Uuid::from_u128(2);
+    document.add(RadiantRectangleNode::new(bottom, [0.0, 0.0], [100.0, 100.0]).into());
+    document.add(RadiantRectangleNode::new(top, [50.0, 50.0], [100.0, 100.0]).into());
+
+    assert_eq!(document.hit_stack([75.0, 75.0], 0.0), vec![top, bottom]);
+    assert_eq!(document.hit_test([25.0, 25.0], 0.0), Some(bottom));
+    assert_eq!(document.hit_test([300.0, 300.0], 0.0), None);
+    assert_eq!(document.hit_test([152.0, 152.0], 4.0), Some(top));
+}
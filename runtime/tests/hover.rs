@@ -0,0 +1,39 @@
+use radiantkit_core::{
+    HoverInteraction, RadiantNode, RadiantRectangleNode, RadiantSceneMessage, RadiantTessellatable,
+    RadiantTool, SelectionTool,
+};
+use uuid::Uuid;
+
+#[test]
+fn test_selection_tool_hovers_until_mouse_down() {
+    let mut tool = SelectionTool::new();
+    let message: Option<RadiantSceneMessage> = tool.on_mouse_move([5.0, 5.0]);
+    assert!(matches!(
+        message,
+        Some(RadiantSceneMessage::HoverNode { position }) if position == [5.0, 5.0]
+    ));
+
+    let _: Option<RadiantSceneMessage> = tool.on_mouse_down(None, [5.0, 5.0]);
+    let message: Option<RadiantSceneMessage> = tool.on_mouse_move([6.0, 6.0]);
+    assert!(message.is_none());
+}
+
+#[test]
+fn test_hover_outline_follows_node() {
+    let id = Uuid::new_v4();
+    let mut node = RadiantRectangleNode::new(id, [0.0, 0.0], [10.0, 10.0]);
+    node.set_needs_tessellation(false);
+    let mut hover = HoverInteraction::new();
+
+    hover.enable(&node);
+    assert_eq!(hover.hovered_node_id, Some(id));
+    assert_eq!(hover.nodes[1].start.x, node.get_bounding_rect()[2]);
+
+    node.transform_mut().set_position(&[20.0, 0.0].into());
+    node.set_needs_tessellation(false);
+    hover.update(&node);
+    assert_eq!(hover.nodes[3].start.x, 20.0);
+
+    hover.disable();
+    assert_eq!(hover.hovered_node_id, None);
+}
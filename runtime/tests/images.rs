@@ -0,0 +1,77 @@
+use radiantkit::RadiantMessage;
+use radiantkit_image::{decode_data_uri, load_image, RadiantImageError, RadiantImageSource};
+use std::sync::mpsc;
+
+/// A 1x1 PNG.
+const PIXEL: &str = concat!(
+    "data:image/png;base64,",
+    "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAA",
+    "DUlEQVR42mP8z8BQDwAEhQGAhKmMIQAAAABJRU5ErkJggg==",
+);
+
+fn load(source: RadiantImageSource) -> Result<epaint::ColorImage, RadiantImageError> {
+    let (sender, receiver) = mpsc::channel();
+    load_image(source, move |result| sender.send(result).unwrap());
+    receiver
+        .try_recv()
+        .expect("local sources load before returning")
+}
+
+#[test]
+fn test_load_image_from_data_uri_and_bytes() {
+    let image = load(RadiantImageSource::Url(PIXEL.into())).unwrap();
+    assert_eq!(image.size, [1, 1]);
+
+    let bytes = decode_data_uri(PIXEL).unwrap();
+    let image = load(RadiantImageSource::Bytes(bytes)).unwrap();
+    assert_eq!(image.size, [1, 1]);
+
+    assert_eq!(decode_data_uri("data:,%41%42c").unwrap(), b"ABc");
+}
+
+#[test]
+fn test_load_image_reports_failures() {
+    assert!(matches!(
+        load(RadiantImageSource::Bytes(vec![1, 2, 3])),
+        Err(RadiantImageError::Decode { .. })
+    ));
+    assert!(matches!(
+        load(RadiantImageSource::Url("data:image/png;base64".into())),
+        Err(RadiantImageError::InvalidDataUri { .. })
+    ));
+    assert!(matches!(
+        load(RadiantImageSource::Path("/nonexistent/image.png".into())),
+        Err(RadiantImageError::Read { .. })
+    ));
+}
+
+#[test]
+fn test_add_image_accepts_the_old_path_form() {
+    let message: RadiantMessage = serde_json::from_str(
+        r#"{"AddImage":{"name":"logo","path":"https://example.com/logo.png"}}"#,
+    )
+    .unwrap();
+    let RadiantMessage::AddImage {
+        source, position, ..
+    } = message
+    else {
+        panic!("expected AddImage");
+    };
+    assert!(
+        matches!(source, RadiantImageSource::Url(url) if url == "https://example.com/logo.png")
+    );
+    assert_eq!(position, [100.0, 200.0]);
+
+    let message: RadiantMessage = serde_json::from_str(
+        r#"{"AddImage":{"name":"logo","source":{"Path":"logo.png"},"position":[1,2]}}"#,
+    )
+    .unwrap();
+    let RadiantMessage::AddImage {
+        source, position, ..
+    } = message
+    else {
+        panic!("expected AddImage");
+    };
+    assert!(matches!(source, RadiantImageSource::Path(path) if path == "logo.png"));
+    assert_eq!(position, [1.0, 2.0]);
+}
@@ -0,0 +1,114 @@
+use radiantkit_core::{
+    apply_path_edit, is_smooth_point, nearest_path_segment, path_anchors, path_handle,
+    RadiantPathCommand, RadiantPathEdit, RadiantPathHandle,
+};
+
+fn square() -> Vec<RadiantPathCommand> {
+    vec![
+        RadiantPathCommand::MoveTo { to: [0.0, 0.0] },
+        RadiantPathCommand::LineTo { to: [100.0, 0.0] },
+        RadiantPathCommand::LineTo { to: [100.0, 100.0] },
+        RadiantPathCommand::LineTo { to: [0.0, 100.0] },
+        RadiantPathCommand::Close,
+    ]
+}
+
+#[test]
+fn test_path_edit_moves_inserts_and_deletes_points() {
+    let moved = apply_path_edit(
+        &square(),
+        RadiantPathEdit::MovePoint {
+            index: 2,
+            offset: [10.0, 20.0],
+        },
+    )
+    .unwrap();
+    assert_eq!(moved[2], RadiantPathCommand::LineTo { to: [110.0, 120.0] });
+
+    // A double click near the top edge lands on its middle.
+    let (index, t, distance) = nearest_path_segment(&square(), [50.0, 2.0]).unwrap();
+    assert_eq!(index, 1);
+    assert!((t - 0.5).abs() < 1e-3 && (distance - 2.0).abs() < 1e-3);
+
+    let inserted = apply_path_edit(&square(), RadiantPathEdit::InsertPoint { index, t }).unwrap();
+    assert_eq!(path_anchors(&inserted).len(), 5);
+    assert_eq!(inserted[1], RadiantPathCommand::LineTo { to: [50.0, 0.0] });
+    let past_the_end = RadiantPathEdit::InsertPoint { index: 10, t };
+    assert!(apply_path_edit(&square(), past_the_end).is_none());
+
+    // Deleting the first point makes the next one start the outline.
+    let deleted = apply_path_edit(&square(), RadiantPathEdit::DeletePoint { index: 0 }).unwrap();
+    assert_eq!(deleted[0], RadiantPathCommand::MoveTo { to: [100.0, 0.0] });
+    assert_eq!(path_anchors(&deleted).len(), 3);
+
+    let line = &square()[..2];
+    assert!(apply_path_edit(line, RadiantPathEdit::DeletePoint { index: 1 }).is_none());
+}
+
+#[test]
+fn test_path_edit_toggles_smooth_points() {
+    let smooth = apply_path_edit(&square(), RadiantPathEdit::ToggleSmooth { index: 1 }).unwrap();
+    assert!(is_smooth_point(&smooth, 1));
+    let handle_in = path_handle(&smooth, 1, RadiantPathHandle::In).unwrap();
+    let handle_out = path_handle(&smooth, 1, RadiantPathHandle::Out).unwrap();
+    // The handles lie on either side of the point, parallel to the previous and next points.
+    assert!((handle_in[0] - handle_in[1] - 100.0).abs() < 1e-3);
+    assert!((handle_out[0] - handle_out[1] - 100.0).abs() < 1e-3);
+    assert!(handle_in[0] < 100.0 && handle_out[0] > 100.0);
+
+    // Dragging one handle of a smooth point turns the other one with it.
+    let dragged = apply_path_edit(
+        &smooth,
+        RadiantPathEdit::MoveHandle {
+            index: 1,
+            handle: RadiantPathHandle::Out,
+            offset: [0.0, -40.0],
+        },
+    )
+    .unwrap();
+    assert!(is_smooth_point(&dragged, 1));
+
+    let corner = apply_path_edit(&dragged, RadiantPathEdit::ToggleSmooth { index: 1 }).unwrap();
+    assert!(!is_smooth_point(&corner, 1));
+    assert_eq!(
+        path_handle(&corner, 1, RadiantPathHandle::In),
+        Some([100.0, 0.0])
+    );
+}
+
+#[test]
+fn test_path_edit_treats_closing_curve_end_as_first_point() {
+    let commands = vec![
+        RadiantPathCommand::MoveTo { to: [0.0, 0.0] },
+        RadiantPathCommand::LineTo { to: [100.0, 0.0] },
+        RadiantPathCommand::CubicTo {
+            control1: [100.0, 100.0],
+            control2: [0.0, 100.0],
+            to: [0.0, 0.0],
+        },
+        RadiantPathCommand::Close,
+    ];
+    assert_eq!(path_anchors(&commands), vec![0, 1]);
+    assert_eq!(
+        path_handle(&commands, 0, RadiantPathHandle::In),
+        Some([0.0, 100.0])
+    );
+
+    let moved = apply_path_edit(
+        &commands,
+        RadiantPathEdit::MovePoint {
+            index: 0,
+            offset: [-10.0, 0.0],
+        },
+    )
+    .unwrap();
+    assert_eq!(moved[0], RadiantPathCommand::MoveTo { to: [-10.0, 0.0] });
+    assert_eq!(
+        moved[2],
+        RadiantPathCommand::CubicTo {
+            control1: [100.0, 100.0],
+            control2: [-10.0, 100.0],
+            to: [-10.0, 0.0],
+        }
+    );
+}
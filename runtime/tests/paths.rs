@@ -0,0 +1,54 @@
+use radiantkit_core::{RadiantFillRule, RadiantNode, RadiantPathCommand};
+use radiantkit_path::RadiantPathNode;
+use uuid::Uuid;
+
+/// A square with a square hole, both wound the same way.
+fn framed_square() -> Vec<RadiantPathCommand> {
+    let square = |min: f32, max: f32| {
+        vec![
+            RadiantPathCommand::MoveTo { to: [min, min] },
+            RadiantPathCommand::LineTo { to: [max, min] },
+            RadiantPathCommand::LineTo { to: [max, max] },
+            RadiantPathCommand::LineTo { to: [min, max] },
+            RadiantPathCommand::Close,
+        ]
+    };
+    [square(100.0, 200.0), square(125.0, 175.0)].concat()
+}
+
+#[test]
+fn test_path_is_positioned_at_its_bounds() {
+    let path = RadiantPathNode::new(Uuid::new_v4(), framed_square());
+
+    assert_eq!(path.get_bounding_rect(), [100.0, 100.0, 200.0, 200.0]);
+    assert_eq!(path.commands[0], RadiantPathCommand::MoveTo { to: [0.0, 0.0] });
+}
+
+#[test]
+fn test_path_hit_test_follows_fill_rule() {
+    let non_zero = RadiantPathNode::new(Uuid::new_v4(), framed_square());
+    let even_odd = non_zero.clone().with_fill_rule(RadiantFillRule::EvenOdd);
+
+    assert!(non_zero.hit_test([110.0, 110.0], 0.0));
+    assert!(non_zero.hit_test([150.0, 150.0], 0.0));
+    assert!(even_odd.hit_test([110.0, 110.0], 0.0));
+    assert!(!even_odd.hit_test([150.0, 150.0], 0.0));
+}
+
+#[test]
+fn test_concave_path_hit_test() {
+    // A "V" whose notch reaches down to the middle.
+    let path = RadiantPathNode::new(
+        Uuid::new_v4(),
+        vec![
+            RadiantPathCommand::MoveTo { to: [0.0, 0.0] },
+            RadiantPathCommand::LineTo { to: [50.0, 50.0] },
+            RadiantPathCommand::LineTo { to: [100.0, 0.0] },
+            RadiantPathCommand::LineTo { to: [50.0, 100.0] },
+            RadiantPathCommand::Close,
+        ],
+    );
+
+    assert!(path.hit_test([50.0, 75.0], 0.0));
+    assert!(!path.hit_test([50.0, 25.0], 0.0));
+}
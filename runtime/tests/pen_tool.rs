@@ -0,0 +1,57 @@
+use radiantkit::RadiantMessage;
+use radiantkit_core::{KeyCode, RadiantPathCommand, RadiantTool};
+use radiantkit_path::PenTool;
+
+fn click(tool: &mut PenTool, position: [f32; 2]) -> Option<RadiantMessage> {
+    let message = RadiantTool::<RadiantMessage>::on_mouse_down(tool, None, position);
+    RadiantTool::<RadiantMessage>::on_mouse_up(tool, position);
+    message
+}
+
+#[test]
+fn test_pen_tool_builds_and_closes_a_path() {
+    let mut tool = PenTool::new();
+    assert!(click(&mut tool, [0.0, 0.0]).is_none());
+
+    let Some(RadiantMessage::AddPath { id, commands, .. }) = click(&mut tool, [100.0, 0.0]) else {
+        panic!("expected the second point to add the path");
+    };
+    assert_eq!(
+        commands,
+        vec![
+            RadiantPathCommand::MoveTo { to: [0.0, 0.0] },
+            RadiantPathCommand::LineTo { to: [100.0, 0.0] },
+        ]
+    );
+
+    // Dragging pulls out a handle, turning the new segment into a curve.
+    RadiantTool::<RadiantMessage>::on_mouse_down(&mut tool, None, [100.0, 100.0]);
+    let message = RadiantTool::<RadiantMessage>::on_mouse_move(&mut tool, [120.0, 100.0]);
+    RadiantTool::<RadiantMessage>::on_mouse_up(&mut tool, [120.0, 100.0]);
+    let Some(RadiantMessage::SetPathCommands { commands, .. }) = message else {
+        panic!("expected the drag to update the path");
+    };
+    assert_eq!(
+        commands[2],
+        RadiantPathCommand::CubicTo {
+            control1: [100.0, 0.0],
+            control2: [80.0, 100.0],
+            to: [100.0, 100.0],
+        }
+    );
+
+    let Some(RadiantMessage::SetPathCommands {
+        id: closed_id,
+        commands,
+    }) = click(&mut tool, [2.0, 1.0])
+    else {
+        panic!("expected a click on the first point to close the path");
+    };
+    assert_eq!(closed_id, id.unwrap());
+    assert_eq!(commands.last(), Some(&RadiantPathCommand::Close));
+
+    // The next click starts a new path.
+    assert!(click(&mut tool, [300.0, 300.0]).is_none());
+    RadiantTool::<RadiantMessage>::on_key_down(&mut tool, KeyCode::Escape, Default::default());
+    assert!(click(&mut tool, [400.0, 400.0]).is_none());
+}
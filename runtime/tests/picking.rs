@@ -0,0 +1,55 @@
+use radiantkit::RadiantNodeType;
+use radiantkit_core::{RadiantDocumentNode, RadiantNode, RadiantPickBuffer, RadiantRectangleNode};
+use uuid::Uuid;
+
+#[test]
+fn test_picking_ids_are_released_on_remove() {
+    let mut document = RadiantDocumentNode::<RadiantNodeType>::new();
+    let first = Uuid::from_u128(1);
+    let second = Uuid::from_u128(2);
+    document.add(RadiantRectangleNode::new(first, [0.0, 0.0], [10.0, 10.0]).into());
+    document.add(RadiantRectangleNode::new(second, [20.0, 0.0], [10.0, 10.0]).into());
+
+    let first_pick_id = document.get_node(first).unwrap().base().pick_id;
+    assert_ne!(first_pick_id, 0);
+    assert_eq!(document.picking_ids().node(first_pick_id), Some(first));
+
+    assert!(document.remove(first).is_some());
+    assert!(document.get_node(first).is_none());
+    assert_eq!(document.picking_ids().node(first_pick_id), None);
+    assert!(document.query_point([5.0, 5.0], 0.0).is_empty());
+    assert_eq!(document.picking_ids().len(), 1);
+
+    let third = Uuid::from_u128(3);
+    document.add(RadiantRectangleNode::new(third, [40.0, 0.0], [10.0, 10.0]).into());
+    assert_eq!(
+        document.get_node(third).unwrap().base().pick_id,
+        first_pick_id
+    );
+}
+
+#[test]
+fn test_pick_radius_finds_thin_shapes() {
+    // A one pixel wide vertical line at x = 5 in a 9x9 buffer.
+    let mut ids = vec![0; 81];
+    for y in 0..9 {
+        ids[y * 9 + 5] = 7;
+    }
+    let buffer = RadiantPickBuffer::new([0, 0], [9, 9], ids);
+
+    assert!(buffer.ids_within([2, 4], 0).is_empty());
+    assert!(buffer.ids_within([2, 4], 2).is_empty());
+    assert_eq!(buffer.ids_within([2, 4], 3), vec![7]);
+}
+
+#[test]
+fn test_topmost_follows_draw_order() {
+    let mut document = RadiantDocumentNode::<RadiantNodeType>::new();
+    let bottom = Uuid::from_u128(1);
+    let top = Uuid::from_u128(2);
+    document.add(RadiantRectangleNode::new(top, [0.0, 0.0], [10.0, 10.0]).into());
+    document.add(RadiantRectangleNode::new(bottom, [0.0, 0.0], [10.0, 10.0]).into());
+
+    assert_eq!(document.topmost([bottom, top]), Some(top));
+    assert_eq!(document.topmost([Uuid::from_u128(3)]), None);
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use epaint::{text::FontDefinitions, Fonts};
+use radiantkit::RadiantNodeType;
+use radiantkit_core::{
+    RadiantDocumentNode, RadiantDrawList, RadiantNode, RadiantRectangleNode, RadiantTessellatable,
+    ScreenDescriptor, TransformComponent,
+};
+use uuid::Uuid;
+
+#[test]
+fn test_unchanged_nodes_are_not_tessellated_again() {
+    let screen_descriptor = ScreenDescriptor {
+        size_in_pixels: [800, 600],
+        pixels_per_point: 1.0,
+    };
+    let fonts_manager = Fonts::new(1.0, 1600, FontDefinitions::default());
+
+    let mut document = RadiantDocumentNode::<RadiantNodeType>::new();
+    let first = Uuid::from_u128(1);
+    let second = Uuid::from_u128(2);
+    document.add(RadiantRectangleNode::new(first, [10.0, 10.0], [100.0, 100.0]).into());
+    document.add(RadiantRectangleNode::new(second, [200.0, 10.0], [100.0, 100.0]).into());
+
+    let mut uploaded = HashMap::new();
+    let mut draw_list = RadiantDrawList::new(Some(&uploaded));
+    document.collect(false, &screen_descriptor, &fonts_manager, &mut draw_list);
+    let items = draw_list.into_items();
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().all(|item| item.primitives.is_some()));
+    for item in items {
+        uploaded.insert(item.id, item.revision);
+    }
+
+    let mut draw_list = RadiantDrawList::new(Some(&uploaded));
+    document.collect(false, &screen_descriptor, &fonts_manager, &mut draw_list);
+    assert!(draw_list
+        .items()
+        .iter()
+        .all(|item| item.primitives.is_none()));
+
+    if let Some(mut node) = document.get_node_mut(second) {
+        if let Some(component) = node.get_component_mut::<TransformComponent>() {
+            component.set_position(&[300.0, 10.0].into());
+        }
+        node.set_needs_tessellation(false);
+    }
+
+    let mut draw_list = RadiantDrawList::new(Some(&uploaded));
+    document.collect(false, &screen_descriptor, &fonts_manager, &mut draw_list);
+    let changed: Vec<Uuid> = draw_list
+        .items()
+        .iter()
+        .filter(|item| item.primitives.is_some())
+        .map(|item| item.id)
+        .collect();
+    assert_eq!(changed, vec![second]);
+}
+
+#[test]
+fn test_every_node_is_uploaded_when_nothing_can_be_reused() {
+    let screen_descriptor = ScreenDescriptor {
+        size_in_pixels: [800, 600],
+        pixels_per_point: 2.0,
+    };
+    let fonts_manager = Fonts::new(2.0, 1600, FontDefinitions::default());
+
+    let mut document = RadiantDocumentNode::<RadiantNodeType>::new();
+    document.add(RadiantRectangleNode::new(Uuid::from_u128(1), [10.0, 10.0], [50.0, 50.0]).into());
+    document.add(RadiantRectangleNode::new(Uuid::from_u128(2), [90.0, 10.0], [50.0, 50.0]).into());
+
+    let mut draw_list = RadiantDrawList::new(None);
+    document.collect(false, &screen_descriptor, &fonts_manager, &mut draw_list);
+    assert_eq!(draw_list.len(), 2);
+    assert!(draw_list
+        .items()
+        .iter()
+        .all(|item| item.primitives.is_some()));
+}
@@ -0,0 +1,90 @@
+use radiantkit::{RadiantMessage, RadiantNodeType};
+use radiantkit_core::{
+    RadiantNode, RadiantPathCommand, RadiantPixmap, RadiantRectangleNode, RadiantScene,
+    RadiantSceneMessage, ScreenDescriptor,
+};
+use radiantkit_path::RadiantPathNode;
+use uuid::Uuid;
+
+fn headless_scene() -> RadiantScene<RadiantMessage, RadiantNodeType> {
+    RadiantScene::new_headless(ScreenDescriptor {
+        size_in_pixels: [64, 64],
+        pixels_per_point: 1.0,
+    })
+}
+
+#[test]
+fn test_software_backend_renders_nodes() {
+    let mut scene = headless_scene();
+    let id = Uuid::new_v4();
+    let mut node = RadiantRectangleNode::new(id, [10.0, 10.0], [20.0, 20.0]);
+    node.color_mut().set_fill_color(epaint::Color32::RED);
+    scene.add(node.into());
+
+    scene.render().unwrap();
+    let pixmap = scene.software_renderer().unwrap().pixmap();
+    assert_eq!(pixmap.size, [64, 64]);
+    assert_eq!(pixmap.get([20, 20]), epaint::Color32::RED);
+    assert_eq!(
+        pixmap.get([50, 50]),
+        epaint::Color32::from(epaint::Rgba::from_rgb(0.1, 0.2, 0.3))
+    );
+    assert_eq!(pixmap.as_rgba8().len(), 64 * 64 * 4);
+}
+
+#[test]
+fn test_software_backend_picks_nodes() {
+    let mut scene = headless_scene();
+    let id = Uuid::new_v4();
+    scene.add(RadiantRectangleNode::new(id, [10.0, 10.0], [20.0, 20.0]).into());
+
+    assert_eq!(pollster::block_on(scene.select([20.0, 20.0])), Some(id));
+    assert_eq!(pollster::block_on(scene.select([50.0, 50.0])), None);
+    assert_eq!(
+        pollster::block_on(scene.select_rect([0.0, 0.0, 64.0, 64.0])),
+        vec![id]
+    );
+}
+
+#[test]
+fn test_pixmap_copy_crops_to_the_destination() {
+    let mut source = RadiantPixmap::new([4, 4]);
+    source.pixels.fill(epaint::Color32::RED);
+    let mut pixmap = RadiantPixmap::new([6, 6]);
+    pixmap.copy_from(&source, [4, 1]);
+
+    assert_eq!(pixmap.get([3, 1]), epaint::Color32::TRANSPARENT);
+    assert_eq!(pixmap.get([4, 1]), epaint::Color32::RED);
+    assert_eq!(pixmap.get([5, 4]), epaint::Color32::RED);
+    assert_eq!(pixmap.get([5, 5]), epaint::Color32::TRANSPARENT);
+}
+
+#[test]
+fn test_software_backend_picks_path_edit_handles() {
+    let mut scene = headless_scene();
+    let id = Uuid::new_v4();
+    let commands = vec![
+        RadiantPathCommand::MoveTo { to: [10.0, 10.0] },
+        RadiantPathCommand::LineTo { to: [40.0, 10.0] },
+        RadiantPathCommand::LineTo { to: [40.0, 40.0] },
+        RadiantPathCommand::LineTo { to: [10.0, 40.0] },
+        RadiantPathCommand::Close,
+    ];
+    scene.add(RadiantPathNode::new(id, commands).into());
+    scene.handle_message(RadiantSceneMessage::DoubleClickNode {
+        id: Some(id),
+        position: [25.0, 25.0],
+    });
+    assert_eq!(scene.interaction_manager.path_edit_node_id(), Some(id));
+
+    let anchor = pollster::block_on(scene.select([40.0, 40.0])).unwrap();
+    assert!(scene.interaction_manager.is_interaction(anchor));
+    assert_eq!(
+        scene
+            .interaction_manager
+            .path_edit_interaction
+            .anchor_index(anchor),
+        Some(2)
+    );
+    assert_eq!(pollster::block_on(scene.select([25.0, 25.0])), Some(id));
+}
@@ -0,0 +1,55 @@
+use radiantkit::RadiantNodeType;
+use radiantkit_core::{
+    RadiantDocumentNode, RadiantNode, RadiantRectangleNode, RadiantTessellatable,
+    TransformComponent,
+};
+use uuid::Uuid;
+
+#[test]
+fn test_spatial_index_follows_node_bounds() {
+    let mut document = RadiantDocumentNode::<RadiantNodeType>::new();
+    let id = Uuid::new_v4();
+    document.add(RadiantRectangleNode::new(id, [0.0, 0.0], [10.0, 10.0]).into());
+
+    assert_eq!(document.query_rect([5.0, 5.0, 20.0, 20.0]), vec![id]);
+    assert_eq!(document.nearest_node([500.0, 500.0]), Some(id));
+
+    if let Some(mut node) = document.get_node_mut(id) {
+        if let Some(component) = node.get_component_mut::<TransformComponent>() {
+            component.set_position(&[1000.0, 1000.0].into());
+        }
+        node.set_needs_tessellation(false);
+    }
+
+    assert!(document.query_rect([5.0, 5.0, 20.0, 20.0]).is_empty());
+    assert_eq!(document.query_point([1005.0, 1005.0], 0.0), vec![id]);
+    assert_eq!(document.nearest_nodes([1020.0, 1005.0], 15.0), vec![id]);
+}
+
+#[test]
+fn test_spatial_index_holds_zero_size_nodes() {
+    let mut document = RadiantDocumentNode::<RadiantNodeType>::new();
+    let id = Uuid::new_v4();
+    document.add(RadiantRectangleNode::new(id, [0.0, 0.0], [0.0, 0.0]).into());
+
+    assert_eq!(document.query_point([0.0, 0.0], 0.0), vec![id]);
+}
+
+#[test]
+fn test_spatial_index_is_rebuilt_after_deserialization() {
+    let mut document = RadiantDocumentNode::<RadiantNodeType>::new();
+    let id = Uuid::new_v4();
+    document.add(RadiantRectangleNode::new(id, [0.0, 0.0], [10.0, 10.0]).into());
+
+    let json = serde_json::to_string(&document).unwrap();
+    let mut document: RadiantDocumentNode<RadiantNodeType> = serde_json::from_str(&json).unwrap();
+    assert_eq!(document.query_rect([5.0, 5.0, 20.0, 20.0]), vec![id]);
+
+    if let Some(mut node) = document.get_node_mut(id) {
+        if let Some(component) = node.get_component_mut::<TransformComponent>() {
+            component.set_position(&[1000.0, 1000.0].into());
+        }
+        node.set_needs_tessellation(false);
+    }
+    assert_eq!(document.query_point([1005.0, 1005.0], 0.0), vec![id]);
+}
@@ -0,0 +1,48 @@
+use radiantkit::{import_svg, RadiantNodeType};
+use radiantkit_core::RadiantNode;
+
+#[test]
+fn test_import_svg() {
+    let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" width="200" height="200">
+        <g transform="translate(10 20)">
+            <rect x="5" y="5" width="40" height="30" fill="#ff0000"/>
+            <path d="M 0 0 L 50 0 L 25 40 Z" fill="#00ff00" stroke="#0000ff"/>
+        </g>
+        <circle cx="100" cy="100" r="10" fill="url(#gradient)"/>
+        <linearGradient id="gradient">
+            <stop offset="0" stop-color="red"/>
+            <stop offset="1" stop-color="blue"/>
+        </linearGradient>
+    </svg>"##;
+    let (nodes, unsupported) = import_svg(svg, [100.0, 0.0]).unwrap();
+
+    assert_eq!(nodes.len(), 2);
+    let RadiantNodeType::Artboard(group) = &nodes[0] else {
+        panic!("expected a group, got {:?}", nodes[0]);
+    };
+    assert_eq!(group.nodes.len(), 2);
+    assert_eq!(group.get_bounding_rect(), [110.0, 20.0, 160.0, 60.0]);
+    let children: Vec<_> = group.nodes.values().map(|node| node.read()).collect();
+    let rectangle = children
+        .iter()
+        .find_map(|node| match &**node {
+            RadiantNodeType::Rectangle(rectangle) => Some(rectangle),
+            _ => None,
+        })
+        .expect("expected a rectangle in the group");
+    assert_eq!(rectangle.get_bounding_rect(), [115.0, 25.0, 155.0, 55.0]);
+    assert_eq!(
+        rectangle.color().fill_color(),
+        epaint::Color32::from_rgb(255, 0, 0)
+    );
+    let path = children
+        .iter()
+        .find(|node| matches!(&***node, RadiantNodeType::Path(_)))
+        .expect("expected a path in the group");
+    assert_eq!(path.get_bounding_rect(), [110.0, 20.0, 160.0, 60.0]);
+    assert!(matches!(nodes[1], RadiantNodeType::Path(_)));
+    assert!(group.to_vector().is_some());
+    assert_eq!(unsupported, vec!["gradient".to_string()]);
+
+    assert!(import_svg("<svg", [0.0, 0.0]).is_err());
+}
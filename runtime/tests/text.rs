@@ -0,0 +1,407 @@
+use epaint::{text::FontDefinitions, Fonts};
+use radiantkit::{RadiantMessage, RadiantNodeType};
+use radiantkit_core::{
+    KeyCode, RadiantImeEvent, RadiantKeyModifiers, RadiantNode, RadiantScene, RadiantSceneMessage,
+    RadiantTessellatable, RadiantTextDelta, ScreenDescriptor,
+};
+use radiantkit_text::{
+    RadiantTextLayout, RadiantTextMessage, RadiantTextNode, RadiantTextSelection,
+    RadiantTextSizing, RadiantTextSpans, RadiantTextStyle,
+};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+const NONE: RadiantKeyModifiers = RadiantKeyModifiers {
+    shift: false,
+    ctrl: false,
+    alt: false,
+    meta: false,
+};
+const SHIFT: RadiantKeyModifiers = RadiantKeyModifiers {
+    shift: true,
+    ..NONE
+};
+
+fn bold() -> RadiantTextStyle {
+    RadiantTextStyle {
+        bold: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_text_spans_split_and_merge() {
+    let mut spans = RadiantTextSpans::new(11, Default::default());
+    spans.set_style(6..11, bold());
+    spans.set_style(0..3, bold());
+    let lengths: Vec<usize> = spans.iter().map(|span| span.length).collect();
+    assert_eq!(lengths, vec![3, 3, 5]);
+
+    // Restyling the plain run in the middle joins all three.
+    spans.set_style(3..6, bold());
+    assert_eq!(spans.iter().count(), 1);
+
+    // Inserted characters take the style of the one before them.
+    let mut spans = RadiantTextSpans::new(5, Default::default());
+    spans.set_style(0..2, bold());
+    spans.insert(2, 3);
+    assert_eq!(spans.style_at(4), bold());
+    assert_eq!(spans.len(), 8);
+
+    spans.remove(0..5);
+    assert_eq!(spans.iter().count(), 1);
+    spans.remove(0..3);
+    assert!(spans.is_empty());
+    assert_eq!(spans.style_at(0), RadiantTextStyle::default());
+}
+
+#[test]
+fn test_text_styles_round_trip_through_observers() {
+    let id = Uuid::new_v4();
+    let mut node = RadiantTextNode::new(id, "Hello world".into(), [0.0, 0.0], [100.0, 100.0]);
+    let sent = Arc::new(Mutex::new(String::new()));
+    let sink = sent.clone();
+    let _subscription = node.observe(move |data| *sink.lock().unwrap() = data.to_string());
+
+    node.handle_message(RadiantTextMessage::SetTextStyle {
+        id,
+        range: 6..11,
+        style: RadiantTextStyle {
+            underline: true,
+            color: epaint::Color32::RED,
+            ..Default::default()
+        },
+    });
+
+    let mut remote = RadiantTextNode::new(id, String::new(), [0.0, 0.0], [100.0, 100.0]);
+    remote.replace(&sent.lock().unwrap());
+    assert_eq!(remote.text, "Hello world");
+    assert_eq!(remote.spans, node.spans);
+    assert_eq!(remote.spans.style_at(8).color, epaint::Color32::RED);
+    assert!(!remote.spans.style_at(2).underline);
+
+    // Garbled remote data leaves the node as it was.
+    remote.replace("{");
+    assert_eq!(remote.text, "Hello world");
+}
+
+#[test]
+fn test_text_caret_selects_and_edits() {
+    let mut node = RadiantTextNode::new(
+        Uuid::new_v4(),
+        "Hello world".into(),
+        [0.0, 0.0],
+        [100.0, 100.0],
+    );
+    node.spans.set_style(0..5, bold());
+    for _ in 0..6 {
+        node.handle_key_down(KeyCode::ArrowLeft, NONE);
+    }
+    for _ in 0..5 {
+        node.handle_key_down(KeyCode::ArrowLeft, SHIFT);
+    }
+    assert_eq!(node.selection.range(), 0..5);
+
+    // Typing over a selection keeps its style.
+    node.handle_key_down(KeyCode::Char("Goodbye".into()), NONE);
+    assert_eq!(node.text, "Goodbye world");
+    assert_eq!(node.selection, RadiantTextSelection::caret(7));
+    assert_eq!(node.spans.len(), 13);
+    assert!(node.spans.style_at(6).bold && !node.spans.style_at(7).bold);
+
+    node.handle_key_down(KeyCode::Backspace, NONE);
+    node.handle_key_down(KeyCode::Delete, NONE);
+    assert_eq!(node.text, "Goodbyworld");
+
+    node.handle_key_down(KeyCode::ArrowRight, SHIFT);
+    assert_eq!(node.selection.range(), 6..7);
+    node.handle_key_down(KeyCode::ArrowLeft, NONE);
+    assert_eq!(node.selection, RadiantTextSelection::caret(6));
+}
+
+#[test]
+fn test_text_edits_whole_grapheme_clusters() {
+    // An accented "e" from two code points, then a family emoji joined from five.
+    let text = "ae\u{301}\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+    let mut node = RadiantTextNode::new(Uuid::new_v4(), text.into(), [0.0, 0.0], [100.0, 100.0]);
+    node.handle_key_down(KeyCode::ArrowLeft, SHIFT);
+    assert_eq!(node.selection.range(), 3..8);
+    node.handle_key_down(KeyCode::Backspace, NONE);
+    assert_eq!(node.text, "ae\u{301}");
+
+    node.handle_key_down(KeyCode::ArrowLeft, NONE);
+    assert_eq!(node.selection, RadiantTextSelection::caret(1));
+    node.handle_key_down(KeyCode::Delete, NONE);
+    assert_eq!(node.text, "a");
+}
+
+#[test]
+fn test_text_ime_composes_at_caret() {
+    let screen_descriptor = ScreenDescriptor {
+        size_in_pixels: [800, 600],
+        pixels_per_point: 1.0,
+    };
+    let fonts = Fonts::new(1.0, 1600, FontDefinitions::default());
+    let mut node = RadiantTextNode::new(Uuid::new_v4(), "ab".into(), [0.0, 0.0], [100.0, 100.0]);
+    node.handle_key_down(KeyCode::ArrowLeft, NONE);
+
+    // The preedit is laid out but not typed until it is committed.
+    assert!(node.handle_ime(RadiantImeEvent::Preedit("xy".into())));
+    node.fit_content(&fonts);
+    node.tessellate(false, &screen_descriptor, &fonts);
+    let composing_width = node.get_bounding_rect()[2];
+    assert_eq!(node.text, "ab");
+
+    assert!(node.handle_ime(RadiantImeEvent::Commit("z".into())));
+    assert!(node.preedit.is_empty());
+    assert_eq!(node.text, "azb");
+    assert_eq!(node.selection, RadiantTextSelection::caret(2));
+    node.fit_content(&fonts);
+    node.tessellate(false, &screen_descriptor, &fonts);
+    assert!(node.get_bounding_rect()[2] < composing_width);
+}
+
+#[test]
+fn test_text_press_places_caret_and_drags_selection() {
+    let screen_descriptor = ScreenDescriptor {
+        size_in_pixels: [800, 600],
+        pixels_per_point: 1.0,
+    };
+    let fonts = Fonts::new(1.0, 1600, FontDefinitions::default());
+    let mut node = RadiantTextNode::new(
+        Uuid::new_v4(),
+        "Hello world".into(),
+        [10.0, 10.0],
+        [100.0, 100.0],
+    );
+    node.tessellate(false, &screen_descriptor, &fonts);
+
+    // Presses move the node until a double click starts editing.
+    assert!(!node.handle_press([0.0, 15.0], false));
+    assert!(node.begin_editing([1000.0, 15.0]));
+    assert_eq!(node.selection, RadiantTextSelection::caret(11));
+    assert!(node.handle_press([0.0, 15.0], false));
+    assert_eq!(node.selection, RadiantTextSelection::caret(0));
+    assert!(node.handle_press([1000.0, 15.0], true));
+    assert_eq!(node.selection.range(), 0..11);
+
+    node.handle_key_down(KeyCode::Home, NONE);
+    assert_eq!(node.selection, RadiantTextSelection::caret(0));
+    node.handle_key_down(KeyCode::End, SHIFT);
+    assert_eq!(node.selection.range(), 0..11);
+
+    node.end_editing();
+    assert!(!node.handle_press([0.0, 15.0], false));
+}
+
+#[test]
+fn test_text_double_click_starts_editing_until_deselected() {
+    let mut scene =
+        RadiantScene::<RadiantMessage, RadiantNodeType>::new_headless(ScreenDescriptor {
+            size_in_pixels: [800, 600],
+            pixels_per_point: 1.0,
+        });
+    let id = Uuid::new_v4();
+    let text = RadiantTextNode::new(id, "Hello world".into(), [10.0, 10.0], [100.0, 100.0]);
+    scene.add(text.into());
+    scene.render().unwrap();
+    let editing = |scene: &RadiantScene<RadiantMessage, RadiantNodeType>| matches!(&*scene.document().get_node(id).unwrap(), RadiantNodeType::Text(text) if text.editing);
+
+    let press = RadiantSceneMessage::PressNode {
+        id: Some(id),
+        position: [12.0, 15.0],
+    };
+    scene.handle_message(press.clone());
+    scene.handle_message(press.clone());
+    assert!(!editing(&scene));
+    assert_eq!(scene.ime_cursor_area(), None);
+
+    scene.handle_message(RadiantSceneMessage::DoubleClickNode {
+        id: Some(id),
+        position: [12.0, 15.0],
+    });
+    assert!(editing(&scene));
+    // The press places the caret instead of selecting the node again.
+    assert!(scene.handle_message(press).is_none());
+    // Input methods compose at the caret, at the start of the text.
+    scene.render().unwrap();
+    let [x, y, _, height] = scene.ime_cursor_area().unwrap();
+    assert!((x - 10.0).abs() < 1.0 && y < 15.0 && y + height > 15.0);
+
+    scene.handle_message(RadiantSceneMessage::SelectNode { id: None });
+    assert!(!editing(&scene));
+    assert_eq!(scene.ime_cursor_area(), None);
+}
+
+#[test]
+fn test_text_box_wraps_and_reflows_on_resize() {
+    let screen_descriptor = ScreenDescriptor {
+        size_in_pixels: [800, 600],
+        pixels_per_point: 1.0,
+    };
+    let fonts = Fonts::new(1.0, 1600, FontDefinitions::default());
+    let id = Uuid::new_v4();
+    let mut node = RadiantTextNode::new(id, "Hello world".into(), [0.0, 0.0], [100.0, 100.0]);
+    node.fit_content(&fonts);
+    node.tessellate(false, &screen_descriptor, &fonts);
+    let [_, _, width, height] = node.get_bounding_rect();
+    assert_eq!(node.transform().scale().x, width);
+
+    // Narrowing an auto-width box through its bounding box wraps the text.
+    node.transform_mut()
+        .transform_scale(&[40.0 - width, 0.0].into());
+    node.set_needs_tessellation(true);
+    assert_eq!(node.layout.sizing, RadiantTextSizing::AutoHeight);
+    node.fit_content(&fonts);
+    node.tessellate(false, &screen_descriptor, &fonts);
+    let [_, _, wrapped_width, wrapped_height] = node.get_bounding_rect();
+    assert_eq!(wrapped_width, 40.0);
+    assert!(wrapped_height > 1.5 * height);
+
+    node.handle_message(RadiantTextMessage::SetTextLayout {
+        id,
+        layout: RadiantTextLayout {
+            line_height: 2.0,
+            ..node.layout
+        },
+    });
+    node.fit_content(&fonts);
+    node.tessellate(false, &screen_descriptor, &fonts);
+    let spaced_height = node.get_bounding_rect()[3];
+    assert!((spaced_height - 2.0 * wrapped_height).abs() < 1e-3);
+
+    node.transform_mut().transform_scale(&[0.0, 10.0].into());
+    node.set_needs_tessellation(true);
+    assert_eq!(node.layout.sizing, RadiantTextSizing::Fixed);
+    node.fit_content(&fonts);
+    node.tessellate(false, &screen_descriptor, &fonts);
+    assert_eq!(node.get_bounding_rect()[3], spaced_height + 10.0);
+}
+
+#[test]
+fn test_text_box_fits_its_text_and_sends_the_size() {
+    let fonts = Fonts::new(1.0, 1600, FontDefinitions::default());
+    let id = Uuid::new_v4();
+    let mut node = RadiantTextNode::new(id, "Hello".into(), [0.0, 0.0], [100.0, 100.0]);
+    let sent = Arc::new(Mutex::new(String::new()));
+    let sink = sent.clone();
+    let _subscription = node.observe(move |data| *sink.lock().unwrap() = data.to_string());
+
+    assert!(node.fit_content(&fonts));
+    assert!(node.transform().scale().x < 100.0);
+    let mut remote = RadiantTextNode::new(id, String::new(), [0.0, 0.0], [100.0, 100.0]);
+    remote.replace(&sent.lock().unwrap());
+    let (scale, remote_scale) = (node.transform().scale(), remote.transform().scale());
+    assert_eq!([remote_scale.x, remote_scale.y], [scale.x, scale.y]);
+
+    // A box that fits its text already is left alone.
+    sent.lock().unwrap().clear();
+    assert!(!node.fit_content(&fonts));
+    assert!(sent.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_text_letter_spacing_widens_and_wraps_rows() {
+    let screen_descriptor = ScreenDescriptor {
+        size_in_pixels: [800, 600],
+        pixels_per_point: 1.0,
+    };
+    let fonts = Fonts::new(1.0, 1600, FontDefinitions::default());
+    let id = Uuid::new_v4();
+    let mut node = RadiantTextNode::new(id, "Hello world".into(), [0.0, 0.0], [100.0, 100.0]);
+    node.fit_content(&fonts);
+    node.tessellate(false, &screen_descriptor, &fonts);
+    let [_, _, width, height] = node.get_bounding_rect();
+
+    // Ten gaps between eleven characters.
+    node.handle_message(RadiantTextMessage::SetTextLayout {
+        id,
+        layout: RadiantTextLayout {
+            letter_spacing: 2.0,
+            ..node.layout
+        },
+    });
+    node.fit_content(&fonts);
+    node.tessellate(false, &screen_descriptor, &fonts);
+    let [_, _, spaced_width, spaced_height] = node.get_bounding_rect();
+    assert!((spaced_width - (width + 20.0)).abs() < 1e-3);
+    assert_eq!(spaced_height, height);
+
+    // The text fits the box unspaced, but not with its spacing.
+    node.transform_mut().transform_scale(&[-10.0, 0.0].into());
+    node.set_needs_tessellation(true);
+    node.fit_content(&fonts);
+    node.tessellate(false, &screen_descriptor, &fonts);
+    let [_, _, wrapped_width, wrapped_height] = node.get_bounding_rect();
+    assert!((wrapped_width - (width + 10.0)).abs() < 1e-3);
+    assert!(wrapped_height > 1.5 * height);
+}
+
+#[test]
+fn test_text_edits_travel_as_utf16_deltas() {
+    let mut local = RadiantTextNode::new(Uuid::new_v4(), "ab\u{1F600}c".into(), [0.0; 2], [0.0; 2]);
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let sink = sent.clone();
+    local.base.text_observer = Some(Arc::new(move |_, delta: &[RadiantTextDelta]| {
+        sink.lock().unwrap().push(delta.to_vec())
+    }));
+    local.handle_key_down(KeyCode::Char("!".into()), NONE);
+    local.handle_key_down(KeyCode::ArrowLeft, NONE);
+    local.handle_key_down(KeyCode::ArrowLeft, NONE);
+    local.handle_key_down(KeyCode::Backspace, NONE);
+    let sent = sent.lock().unwrap().clone();
+    assert_eq!(
+        sent,
+        vec![
+            vec![
+                RadiantTextDelta::Retain(5),
+                RadiantTextDelta::Insert("!".into())
+            ],
+            vec![RadiantTextDelta::Retain(2), RadiantTextDelta::Delete(2)],
+        ]
+    );
+
+    // A peer with its caret after "a" follows both edits.
+    let mut remote =
+        RadiantTextNode::new(Uuid::new_v4(), "ab\u{1F600}c".into(), [0.0; 2], [0.0; 2]);
+    remote.selection = RadiantTextSelection::caret(1);
+    for delta in &sent {
+        assert!(remote.apply_text_delta(delta));
+    }
+    assert_eq!(remote.text, local.text);
+    assert_eq!(remote.text, "abc!");
+    assert_eq!(remote.spans.len(), 4);
+    assert_eq!(remote.selection, RadiantTextSelection::caret(1));
+}
+
+#[test]
+fn test_text_set_text_sends_the_changed_characters() {
+    let id = Uuid::new_v4();
+    let mut local = RadiantTextNode::new(id, "Hello world".into(), [0.0; 2], [0.0; 2]);
+    local.spans.set_style(6..11, bold());
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let sink = sent.clone();
+    local.base.text_observer = Some(Arc::new(move |_, delta: &[RadiantTextDelta]| {
+        sink.lock().unwrap().push(delta.to_vec())
+    }));
+
+    local.handle_message(RadiantTextMessage::SetText {
+        id,
+        text: "Hello brave world".into(),
+    });
+    let sent = sent.lock().unwrap().clone();
+    assert_eq!(
+        sent,
+        vec![vec![
+            RadiantTextDelta::Retain(6),
+            RadiantTextDelta::Insert("brave ".into())
+        ]]
+    );
+    // The runs around the change keep their styles.
+    assert!(!local.spans.style_at(2).bold);
+    assert!(local.spans.style_at(14).bold);
+
+    let mut remote = RadiantTextNode::new(id, "Hello world".into(), [0.0; 2], [0.0; 2]);
+    assert!(remote.apply_text_delta(&sent[0]));
+    assert_eq!(remote.text, local.text);
+}
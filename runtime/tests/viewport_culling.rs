@@ -0,0 +1,39 @@
+use epaint::{text::FontDefinitions, Fonts};
+use radiantkit::RadiantNodeType;
+use radiantkit_core::{
+    RadiantDocumentNode, RadiantNode, RadiantRectangleNode, RadiantTessellatable, ScreenDescriptor,
+    TransformComponent,
+};
+use uuid::Uuid;
+
+#[test]
+fn test_offscreen_nodes_are_culled() {
+    let screen_descriptor = ScreenDescriptor {
+        size_in_pixels: [800, 600],
+        pixels_per_point: 1.0,
+    };
+    let fonts_manager = Fonts::new(1.0, 1600, FontDefinitions::default());
+
+    let mut document = RadiantDocumentNode::<RadiantNodeType>::new();
+    document.add(RadiantRectangleNode::new(Uuid::new_v4(), [10.0, 10.0], [100.0, 100.0]).into());
+    let far_id = Uuid::new_v4();
+    document.add(RadiantRectangleNode::new(far_id, [5000.0, 5000.0], [100.0, 100.0]).into());
+
+    document.tessellate(false, &screen_descriptor, &fonts_manager);
+
+    let stats = document.frame_stats();
+    assert_eq!(stats.visible_nodes, 1);
+    assert_eq!(stats.culled_nodes, 1);
+
+    if let Some(mut node) = document.get_node_mut(far_id) {
+        if let Some(component) = node.get_component_mut::<TransformComponent>() {
+            component.set_position(&[200.0, 200.0].into());
+        }
+        node.set_needs_tessellation(false);
+    }
+    document.tessellate(false, &screen_descriptor, &fonts_manager);
+
+    let stats = document.frame_stats();
+    assert_eq!(stats.visible_nodes, 2);
+    assert_eq!(stats.culled_nodes, 0);
+}
@@ -45,11 +45,20 @@ export class RadiantKitController {
         });
     }
 
-    addImage(path: string, name: string = "", position: number[] = [100, 100], scale: number[] =[100, 100]) {
+    /**
+     * Adds an image from a URL, which may be a `data:` URI. The outcome is reported as an
+     * `ImageLoaded` or `ImageFailed` response.
+     *
+     * @param size the size to draw the image at, or its own size if omitted. This argument was
+     * `scale` and was ignored before, as was `position`; drop it to keep the image's own size.
+     */
+    addImage(url: string, name: string = "", position: number[] = [100, 100], size?: number[]) {
         this._controller.handleMessage({
             AddImage: {
                 name,
-                path,
+                source: { Url: url },
+                position,
+                size,
             },
         });
     }